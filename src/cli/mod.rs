@@ -0,0 +1,115 @@
+use crate::config::AppConfig;
+use crate::network;
+
+/// 命令行用法说明
+const USAGE: &str = "用法 / Usage:
+  network_switcher                    启动图形界面 / Launch GUI
+  network_switcher --apply <名称>     应用指定配置 / Apply a saved config
+  network_switcher --list             列出已保存的配置 / List saved configs
+  network_switcher --current          显示当前网络标识 / Show current network identity
+
+  --verbose, -v                       输出调试日志（也可以用 RUST_LOG 设置）/ Debug logging";
+
+/// 处理命令行参数
+/// 没有参数时返回 None（启动 GUI），否则返回进程退出码
+pub fn run(args: &[String]) -> Option<i32> {
+    let command = args.first()?;
+
+    let code = match command.as_str() {
+        "--apply" => match args.get(1) {
+            Some(name) => apply(name),
+            None => {
+                eprintln!("❌ --apply 需要配置名称\n\n{}", USAGE);
+                1
+            }
+        },
+        "--list" => list(),
+        "--current" => current(),
+        "--help" | "-h" => {
+            println!("{}", USAGE);
+            0
+        }
+        other => {
+            eprintln!("❌ 未知参数: {}\n\n{}", other, USAGE);
+            1
+        }
+    };
+
+    Some(code)
+}
+
+/// 按配置名称应用配置
+fn apply(name: &str) -> i32 {
+    let config = AppConfig::load();
+    network::set_apply_retries(config.apply_retries);
+    network::set_dns_ignore_prefixes(&config.dns_ignore_prefixes);
+    network::set_allow_hooks(config.allow_hooks);
+    let Some(cfg) = config.find_by_name(name) else {
+        eprintln!("❌ 未找到配置: {}", name);
+        return 1;
+    };
+
+    let mut code = 0;
+    if let Some(path) = cfg.profile_path.as_deref() {
+        println!("安装描述文件...");
+        match network::install_profile(path) {
+            Ok(install) => println!("{}", install.label()),
+            Err(e) => {
+                eprintln!("❌ 安装描述文件失败: {}", e);
+                code = 1;
+            }
+        }
+    }
+    for target_service in cfg.resolve_target_services(&config.network_service) {
+        let result = network::apply_config_with_rollback(&target_service, cfg, &|step| println!("{}...", step));
+        network::log_apply(cfg, "manual", &target_service, &result);
+
+        match &result {
+            Ok(_) if cfg.probe_after_apply => {
+                println!("✅ 已应用配置: {} -> {}（连通性检测通过）", cfg.name, target_service)
+            }
+            Ok(_) => println!("✅ 已应用配置: {} -> {}", cfg.name, target_service),
+            Err(e) => {
+                eprintln!("❌ {} 应用失败: {}", target_service, e);
+                code = 1;
+            }
+        }
+        if result.is_ok() {
+            match network::run_post_apply_hook(&target_service, cfg) {
+                Some(Ok(output)) if !output.is_empty() => println!("{}", output),
+                Some(Err(e)) => {
+                    eprintln!("⚠️ 应用后命令失败: {}", e);
+                    code = 1;
+                }
+                _ => {}
+            }
+        }
+    }
+    code
+}
+
+/// 列出已保存的配置名称
+fn list() -> i32 {
+    let config = AppConfig::load();
+    let mut names: Vec<_> = config.configs.values().map(|c| c.name.clone()).collect();
+    names.sort();
+    for name in names {
+        println!("{}", name);
+    }
+    0
+}
+
+/// 打印当前网络标识
+fn current() -> i32 {
+    let identity = network::get_network_identity(true);
+    println!("SSID: {}", identity.ssid.as_deref().unwrap_or("-"));
+    println!("Router MAC: {}", identity.router_mac.as_deref().unwrap_or("-"));
+    println!("BSSID: {}", identity.bssid.as_deref().unwrap_or("-"));
+    println!("Adapter MAC: {}", identity.adapter_mac.as_deref().unwrap_or("-"));
+    println!("Wired: {}", identity.is_wired);
+    println!("Service: {}", identity.service_name.as_deref().unwrap_or("-"));
+    println!("VPN: {}", identity.vpn_active);
+    println!("Proxy: {}", identity.proxy_enabled);
+    println!("Captive portal: {}", identity.portal_url.as_deref().unwrap_or("-"));
+    0
+}
@@ -1,7 +1,40 @@
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use chrono::{Datelike, Duration, NaiveDateTime, NaiveTime};
+use log::{info, warn};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::i18n::{Lang, t, tf};
 use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 use std::fs;
-use std::path::PathBuf;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use toml_edit::{DocumentMut, Item, Table};
+
+use crate::keychain;
+use crate::network;
+
+/// 钥匙串中保存启动密码哈希的账户名
+const APP_PASSWORD_ACCOUNT: &str = "app-password";
+
+/// 配置文件格式版本，格式变化需要迁移时递增
+const CONFIG_VERSION: u32 = 3;
+
+/// 保留的配置备份数量
+const BACKUP_COUNT: usize = 5;
+
+/// 迁移生成的 config.toml 开头的说明
+const TOML_HEADER: &str = "# Network Switcher 配置文件\n# 可以用 # 添加注释，保存时会保留\n\n";
+
+/// 默认窗口大小
+pub const DEFAULT_WINDOW_SIZE: [f32; 2] = [500.0, 600.0];
+/// 最小窗口大小
+pub const MIN_WINDOW_SIZE: [f32; 2] = [400.0, 400.0];
 
 /// 配置类型
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
@@ -11,148 +44,1554 @@ pub enum ConfigType {
     Service,    // 基于网络服务名触发（有线等）
 }
 
+/// 界面主题
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum Theme {
+    #[default]
+    System,     // 跟随系统
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::System, Theme::Light, Theme::Dark];
+
+    /// 显示名称
+    pub fn label(self) -> &'static str {
+        match self {
+            Theme::System => t("theme_system"),
+            Theme::Light => t("theme_light"),
+            Theme::Dark => t("theme_dark"),
+        }
+    }
+}
+
+/// DNS 设置方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum DnsMode {
+    #[default]
+    Auto,       // 清除手动 DNS，使用 DHCP 下发的 DNS
+    Manual,     // 使用 dns_servers
+    Inherit,    // 不修改 DNS，保留系统当前的设置
+}
+
+impl DnsMode {
+    pub const ALL: [DnsMode; 3] = [DnsMode::Auto, DnsMode::Manual, DnsMode::Inherit];
+
+    /// 显示名称
+    pub fn label(self) -> &'static str {
+        match self {
+            DnsMode::Auto => t("dns_auto"),
+            DnsMode::Manual => t("dns_manual"),
+            DnsMode::Inherit => t("dns_inherit"),
+        }
+    }
+}
+
+/// IPv6 设置方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum Ipv6Mode {
+    #[default]
+    Unmanaged,  // 不修改 IPv6，保留系统当前的设置
+    Automatic,  // 自动配置
+    Manual,     // 使用 ipv6_address、ipv6_prefix 和 ipv6_router
+}
+
+impl Ipv6Mode {
+    pub const ALL: [Ipv6Mode; 3] = [Ipv6Mode::Unmanaged, Ipv6Mode::Automatic, Ipv6Mode::Manual];
+
+    /// 显示名称
+    pub fn label(self) -> &'static str {
+        match self {
+            Ipv6Mode::Unmanaged => t("ipv6_unmanaged"),
+            Ipv6Mode::Automatic => t("ipv6_automatic"),
+            Ipv6Mode::Manual => t("ipv6_manual"),
+        }
+    }
+}
+
+/// 常用 DNS 服务器组合
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DnsPreset {
+    pub name: String,
+    pub servers: Vec<String>,
+}
+
+/// 内置的公共 DNS 预设
+fn default_dns_presets() -> Vec<DnsPreset> {
+    let preset = |name: &str, servers: &[&str]| DnsPreset {
+        name: name.to_string(),
+        servers: servers.iter().map(|s| s.to_string()).collect(),
+    };
+    vec![
+        preset("Cloudflare", &["1.1.1.1", "1.0.0.1"]),
+        preset("Google", &["8.8.8.8", "8.8.4.4"]),
+        preset("阿里 DNS", &["223.5.5.5", "223.6.6.6"]),
+    ]
+}
+
+/// 定时规则：在指定星期的时间段内生效
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Schedule {
+    /// 周一到周日是否生效
+    pub days: [bool; 7],
+    /// 开始时间（HH:MM）
+    pub start: String,
+    /// 结束时间（HH:MM），早于开始时间表示跨过午夜
+    pub end: String,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Self {
+            days: [true, true, true, true, true, false, false],
+            start: "09:00".to_string(),
+            end: "18:00".to_string(),
+        }
+    }
+}
+
+impl Schedule {
+    /// 星期的显示名称（周一开始）
+    pub fn day_labels() -> [&'static str; 7] {
+        ["day_mon", "day_tue", "day_wed", "day_thu", "day_fri", "day_sat", "day_sun"].map(t)
+    }
+
+    /// 指定时刻是否在生效时间段内，时间格式错误时视为不生效
+    pub fn is_active(&self, now: NaiveDateTime) -> bool {
+        let (Some(start), Some(end)) = (parse_time(&self.start), parse_time(&self.end)) else {
+            return false;
+        };
+        let day_enabled = |at: NaiveDateTime| self.days[at.weekday().num_days_from_monday() as usize];
+        let time = now.time();
+
+        if start <= end {
+            day_enabled(now) && start <= time && time < end
+        } else {
+            // 跨午夜的时间段，午夜之后的部分属于前一天
+            (day_enabled(now) && time >= start) || (day_enabled(now - Duration::days(1)) && time < end)
+        }
+    }
+}
+
+/// 统一 MAC 地址格式：小写，每段补足两位（ioreg 等工具会省略前导 0）
+pub fn normalize_mac(mac: &str) -> String {
+    mac.trim()
+        .split(':')
+        .map(|part| format!("{:0>2}", part.to_lowercase()))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// 是否为有线网络服务（macOS 的 Ethernet、USB 10/100/1000 LAN，nmcli 的 Wired connection 1）
+/// 按单词匹配，避免把 WLAN 当作 LAN
+fn is_ethernet_service(service: &str) -> bool {
+    service.contains("以太网")
+        || service
+            .split(|c: char| !c.is_alphanumeric())
+            .any(|word| ["ethernet", "lan", "wired"].contains(&word.to_lowercase().as_str()))
+}
+
+/// MAC 地址的简短形式：只保留后 8 个字符（按字符截取，避免非 ASCII 内容切到字符中间）
+pub fn short_mac(mac: &str) -> String {
+    let mut chars: Vec<char> = mac.chars().rev().take(8).collect();
+    chars.reverse();
+    chars.into_iter().collect()
+}
+
+/// 把 SSID 匹配条件编译为正则表达式，不含通配符的普通 SSID 返回 None（精确匹配）
+/// 通配符 * 匹配任意多个字符，? 匹配一个字符，其余部分按字面匹配
+pub fn ssid_regex(pattern: &str, is_regex: bool) -> Option<Result<Regex, regex::Error>> {
+    if is_regex {
+        return Some(Regex::new(&format!("^(?:{})$", pattern)));
+    }
+    if !pattern.contains(['*', '?']) {
+        return None;
+    }
+
+    let mut expr = String::from("^");
+    let mut literal = String::new();
+    for c in pattern.chars() {
+        if c == '*' || c == '?' {
+            expr.push_str(&regex::escape(&literal));
+            literal.clear();
+            expr.push_str(if c == '*' { ".*" } else { "." });
+        } else {
+            literal.push(c);
+        }
+    }
+    expr.push_str(&regex::escape(&literal));
+    expr.push('$');
+    Some(Regex::new(&expr))
+}
+
+/// SSID 是否符合匹配条件，正则表达式无效时不匹配
+fn ssid_matches(pattern: &str, ssid: &str, is_regex: bool) -> bool {
+    match ssid_regex(pattern, is_regex) {
+        None => pattern == ssid,
+        Some(Ok(regex)) => regex.is_match(ssid),
+        Some(Err(_)) => false,
+    }
+}
+
+/// 解析 HH:MM 格式的时间
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
 /// 单个网络配置
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct NetworkConfig {
+    /// 配置唯一标识（UUID），旧配置在加载时自动分配
+    #[serde(default)]
+    pub id: String,
     /// 配置名称（用户自定义）
     pub name: String,
+    /// 备注（如配置的用途），在配置列表中悬停显示
+    #[serde(default)]
+    pub notes: String,
+    /// 标签（如 "家"、"公司"），匹配当前网络的配置的标签构成当前环境
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
     /// 匹配的 WiFi SSID（可选，用于自动匹配）
     #[serde(default)]
     pub ssid: String,
+    /// 同样匹配此配置的其他 SSID（如同一办公室的 2.4G / 5G / 访客网络）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ssids: Vec<String>,
+    /// SSID 按正则表达式匹配（否则含 * 或 ? 时按通配符匹配，其余精确匹配）
+    #[serde(default)]
+    pub ssid_is_pattern: bool,
     /// 配置类型
     #[serde(default)]
     pub config_type: ConfigType,
     /// 路由器 MAC 地址（用于唯一标识网络）
     #[serde(default)]
     pub router_mac: Option<String>,
+    /// 接入点 BSSID（Mesh 网络中区分具体的 AP）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bssid: Option<String>,
+    /// 有线网卡自身的 MAC 地址（区分两个相同型号的扩展坞），只对有线/服务配置生效
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adapter_mac: Option<String>,
     /// 是否自动应用此配置
     #[serde(default)]
     pub auto_apply: bool,
+    /// 信号范围内出现此 SSID 且优先级高于当前网络的配置时，自动连接并应用（需要开启自动切换）
+    #[serde(default)]
+    pub auto_join: bool,
+    /// 有线网络断开（如拔掉网线）且当前网络没有匹配的自动应用配置时应用此配置（需要开启自动切换）
+    #[serde(default)]
+    pub apply_on_wired_loss: bool,
     /// 应用到哪个网络服务 (如 "Wi-Fi", "Thunderbolt Ethernet")
     pub target_service: Option<String>,
+    /// 同时应用到多个网络服务，设置后代替 target_service
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_services: Option<Vec<String>>,
+    /// 应用时修改 IP 设置（IPv4 / IPv6），关闭后保留系统当前的地址
+    #[serde(default = "default_true")]
+    pub manage_ip: bool,
+    /// 应用时修改 DNS 设置（DNS 服务器和搜索域），关闭后保留系统当前的 DNS
+    #[serde(default = "default_true")]
+    pub manage_dns: bool,
+    #[serde(default)]
     pub use_dhcp: bool,
     pub ip_address: Option<String>,
     pub subnet_mask: Option<String>,
     pub router: Option<String>,
+    /// DNS 设置方式，Manual 时使用 dns_servers
+    #[serde(default)]
+    pub dns_mode: DnsMode,
+    #[serde(default)]
     pub dns_servers: Vec<String>,
+    /// DNS 搜索域
+    #[serde(default)]
+    pub search_domains: Vec<String>,
+    /// IPv6 设置方式，Manual 时使用下面的地址、前缀长度和路由器
+    #[serde(default)]
+    pub ipv6_mode: Ipv6Mode,
+    /// IPv6 地址
+    #[serde(default)]
+    pub ipv6_address: Option<String>,
+    /// IPv6 前缀长度
+    #[serde(default)]
+    pub ipv6_prefix: Option<u8>,
+    /// IPv6 路由器
+    #[serde(default)]
+    pub ipv6_router: Option<String>,
+    /// WiFi 密码，仅在钥匙串不可用时保存在配置文件中
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wifi_password: Option<String>,
+    /// 钥匙串中 WiFi 密码的账户名
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wifi_password_account: Option<String>,
+    /// MTU（为空表示不修改）
+    #[serde(default)]
+    pub mtu: Option<u32>,
+    /// 定时规则，设置后只在该时间段内自动应用
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<Schedule>,
+    /// 自动应用优先级，多个配置同时匹配时数值大的优先
+    #[serde(default)]
+    pub priority: i32,
+    /// 应用步骤顺序（"ip"、"ipv6"、"dns"、"search"、"mtu"、"wins"、"doh"、"proxy"），为空时使用默认顺序
+    #[serde(default)]
+    pub apply_steps_order: Vec<String>,
+    /// 应用后检测连通性（ping 路由器并解析公网域名），失败时回滚到之前的配置
+    #[serde(default)]
+    pub probe_after_apply: bool,
+    /// 应用静态 IP 前检查地址是否已被局域网中的其他设备占用（会增加约一秒延迟）
+    #[serde(default)]
+    pub check_ip_conflict: bool,
+    /// 上次成功应用的时间（Unix 秒），从未应用过为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_applied: Option<i64>,
+    /// WINS 服务器（为空表示不修改）
+    #[serde(default)]
+    pub wins_servers: Vec<String>,
+    /// DNS-over-HTTPS 地址模板（为空表示不修改，空字符串表示移除 DoH 描述文件）
+    #[serde(default)]
+    pub doh_template: Option<String>,
+    /// 802.1X 等企业网络的描述文件（.mobileconfig）路径，手动应用时先安装
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile_path: Option<String>,
+    /// HTTP 代理（"主机:端口"），为空表示不修改
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_proxy: Option<String>,
+    /// HTTPS 代理（"主机:端口"），为空表示不修改
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub https_proxy: Option<String>,
+    /// 自动代理配置（PAC）地址，为空表示不修改
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pac_url: Option<String>,
+    /// 不使用代理的主机和域名，为空表示不修改
+    #[serde(default)]
+    pub proxy_bypass: Vec<String>,
+    /// 应用时关闭未设置的代理并清空例外列表（默认保持系统当前的代理，以免覆盖管理员下发的代理）
+    #[serde(default)]
+    pub clear_proxy: bool,
+    /// 锁定：编辑面板默认只读，解锁和删除需要确认（设置了启动密码时需要输入密码）
+    #[serde(default)]
+    pub locked: bool,
+    /// 应用成功后通过 sh -c 执行的命令（如刷新 DNS 缓存），需要在设置中开启 allow_hooks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_apply_command: Option<String>,
 }
 
 /// 应用配置
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
+    /// 配置文件格式版本（旧版本文件没有此字段，视为 0）
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default)]
     pub configs: HashMap<String, NetworkConfig>,
+    /// 当前的配置集（如"个人"、"客户 A"），configs 是它的配置，其他配置集保存在 profiles 目录中
+    #[serde(default = "default_profile")]
+    pub active_profile: String,
+    #[serde(default)]
     pub auto_switch: bool,
+    #[serde(default)]
     pub network_service: String,
+    /// 上次关闭时的窗口大小（0 表示未记录）
+    #[serde(default)]
+    pub window_width: f32,
+    #[serde(default)]
+    pub window_height: f32,
+    /// 上次关闭时的窗口位置
+    #[serde(default)]
+    pub window_x: Option<f32>,
+    #[serde(default)]
+    pub window_y: Option<f32>,
+    /// 启动密码的加盐哈希（Argon2，PHC 格式），仅在钥匙串不可用时保存在配置文件中
+    #[serde(default)]
+    pub password_hash: Option<String>,
+    /// 钥匙串中启动密码哈希的账户名，与 password_hash 都为空表示不需要密码
+    #[serde(default)]
+    pub password_account: Option<String>,
+    /// 自动切换配置时发送系统通知
+    #[serde(default = "default_true")]
+    pub notifications_enabled: bool,
+    /// 手动应用配置时也发送系统通知
+    #[serde(default)]
+    pub notify_on_manual_apply: bool,
+    /// 在本地 Unix socket 上提供状态 JSON
+    #[serde(default)]
+    pub expose_socket: bool,
+    /// 在局域网 HTTP 端口上提供已保存的配置，供另一台机器拉取
+    #[serde(default)]
+    pub sync_enabled: bool,
+    /// 局域网同步端口
+    #[serde(default = "default_sync_port")]
+    pub sync_port: u16,
+    /// 局域网同步的共享令牌，拉取方需要提供相同的令牌
+    #[serde(default)]
+    pub sync_token: String,
+    /// 界面主题
+    #[serde(default)]
+    pub theme: Theme,
+    /// 界面语言，为空时跟随系统
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lang: Option<Lang>,
+    /// 紧凑模式：状态只显示一行，配置以下拉框选择，适合小屏幕
+    #[serde(default)]
+    pub compact: bool,
+    /// DNS 预设，编辑配置时可一键添加
+    #[serde(default = "default_dns_presets")]
+    pub dns_presets: Vec<DnsPreset>,
+    /// 应用配置时命令临时失败（如网卡刚连接）的重试次数
+    #[serde(default = "default_apply_retries")]
+    pub apply_retries: u32,
+    /// 后台检测联网状态的间隔秒数，0 表示不检测
+    #[serde(default = "default_reachability_interval")]
+    pub reachability_interval: u64,
+    /// 在状态区域显示公网 IP（会请求外部的回显服务，默认关闭）
+    #[serde(default)]
+    pub public_ip_enabled: bool,
+    /// 查询公网 IP 的地址，需要返回纯文本的 IP 地址
+    #[serde(default = "default_public_ip_url")]
+    pub public_ip_url: String,
+    /// 读取实际使用的 DNS 时忽略的地址前缀（见 DEFAULT_DNS_IGNORE_PREFIXES）
+    #[serde(default = "default_dns_ignore_prefixes")]
+    pub dns_ignore_prefixes: Vec<String>,
+    /// 已完成首次运行的权限检查，之后不再检查和提示
+    #[serde(default)]
+    pub first_run_completed: bool,
+    /// 允许执行配置中的应用后命令（会执行任意 shell 命令，默认关闭）
+    #[serde(default)]
+    pub allow_hooks: bool,
+    /// 配置文件存在但无法读取，此时拒绝保存以免覆盖原文件
+    #[serde(skip)]
+    pub load_failed: bool,
+    /// 配置文件损坏时实际读取的恢复文件
+    #[serde(skip)]
+    pub recovered_from: Option<PathBuf>,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            configs: HashMap::new(),
+            active_profile: default_profile(),
+            auto_switch: false,
+            network_service: String::new(),
+            window_width: 0.0,
+            window_height: 0.0,
+            window_x: None,
+            window_y: None,
+            password_hash: None,
+            password_account: None,
+            notifications_enabled: true,
+            notify_on_manual_apply: false,
+            expose_socket: false,
+            sync_enabled: false,
+            sync_port: DEFAULT_SYNC_PORT,
+            sync_token: String::new(),
+            theme: Theme::System,
+            lang: None,
+            compact: false,
+            dns_presets: default_dns_presets(),
+            apply_retries: DEFAULT_APPLY_RETRIES,
+            reachability_interval: DEFAULT_REACHABILITY_INTERVAL,
+            public_ip_enabled: false,
+            public_ip_url: default_public_ip_url(),
+            dns_ignore_prefixes: default_dns_ignore_prefixes(),
+            first_run_completed: false,
+            allow_hooks: false,
+            load_failed: false,
+            recovered_from: None,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// 默认的命令失败重试次数
+pub const DEFAULT_APPLY_RETRIES: u32 = 2;
+
+/// 默认配置集的名称（旧版本的配置都属于它）
+pub const DEFAULT_PROFILE: &str = "默认";
+
+fn default_profile() -> String {
+    DEFAULT_PROFILE.to_string()
+}
+
+/// 默认的局域网同步端口
+pub const DEFAULT_SYNC_PORT: u16 = 47800;
+
+/// 默认每 5 秒检测一次联网状态
+pub const DEFAULT_REACHABILITY_INTERVAL: u64 = 5;
+
+fn default_sync_port() -> u16 {
+    DEFAULT_SYNC_PORT
+}
+
+fn default_apply_retries() -> u32 {
+    DEFAULT_APPLY_RETRIES
+}
+
+fn default_reachability_interval() -> u64 {
+    DEFAULT_REACHABILITY_INTERVAL
+}
+
+/// 默认忽略的 DNS 前缀
+/// Clash、Surge 等代理工具的增强模式把系统 DNS 指向 198.18.0.0/15（RFC 2544 保留的测试网段）
+/// 中的虚拟地址来接管域名解析，显示它既不是网络真正的 DNS，也不应在“用当前设置”时保存到配置中
+pub const DEFAULT_DNS_IGNORE_PREFIXES: &[&str] = &["198.18."];
+
+fn default_dns_ignore_prefixes() -> Vec<String> {
+    DEFAULT_DNS_IGNORE_PREFIXES.iter().map(|s| s.to_string()).collect()
+}
+
+/// 默认的公网 IP 查询地址
+pub const DEFAULT_PUBLIC_IP_URL: &str = "https://api.ipify.org";
+
+fn default_public_ip_url() -> String {
+    DEFAULT_PUBLIC_IP_URL.to_string()
+}
+
+/// 是否为 TOML 配置文件（包括 config.toml 的临时文件和备份）
+fn is_toml(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with(".toml") || name.contains(".toml."))
+}
+
+/// 保存时先写入的临时文件
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+/// 把旧 TOML 文件中的注释和空行按键名带到新生成的内容中，解析失败时直接使用新内容
+fn preserve_toml_comments(old: &str, new: &str) -> String {
+    let (Ok(old), Ok(mut doc)) = (old.parse::<DocumentMut>(), new.parse::<DocumentMut>()) else {
+        return new.to_string();
+    };
+    copy_toml_decor(old.as_table(), doc.as_table_mut());
+    doc.set_trailing(old.trailing().clone());
+    doc.to_string()
+}
+
+fn copy_toml_decor(old: &Table, new: &mut Table) {
+    *new.decor_mut() = old.decor().clone();
+    for (mut key, item) in new.iter_mut() {
+        let Some((old_key, old_item)) = old.get_key_value(key.get()) else {
+            continue;
+        };
+        *key.leaf_decor_mut() = old_key.leaf_decor().clone();
+        match (item, old_item) {
+            (Item::Table(table), Item::Table(old_table)) => copy_toml_decor(old_table, table),
+            // 行尾注释在值的后缀中
+            (Item::Value(value), Item::Value(old_value)) => *value.decor_mut() = old_value.decor().clone(),
+            (Item::ArrayOfTables(tables), Item::ArrayOfTables(old_tables)) => {
+                for (table, old_table) in tables.iter_mut().zip(old_tables.iter()) {
+                    copy_toml_decor(old_table, table);
+                }
+            }
+            _ => {}
+        }
+    }
 }
 
 impl AppConfig {
+    /// 配置文件路径，存在 config.toml 时优先使用它，否则使用 config.json
     pub fn config_path() -> PathBuf {
-        dirs::config_dir()
+        let dir = dirs::config_dir()
             .unwrap_or_else(|| PathBuf::from("."))
-            .join("network-switcher")
-            .join("config.json")
+            .join("network-switcher");
+        let toml = dir.join("config.toml");
+        if toml.exists() { toml } else { dir.join("config.json") }
     }
 
     pub fn load() -> Self {
         let path = Self::config_path();
-        if path.exists() {
-            match fs::read_to_string(&path) {
-                Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
-                Err(_) => Self::default(),
+        let mut config = match fs::read_to_string(&path) {
+            Err(_) => Self::default(),
+            Ok(content) if content.trim().is_empty() => Self::default(),
+            Ok(content) => match Self::parse(&path, &content) {
+                Ok(config) => config,
+                // 配置文件损坏时依次尝试临时文件和备份
+                Err(_) => Self::recover().unwrap_or_else(|| Self {
+                    load_failed: true,
+                    ..Self::default()
+                }),
+            },
+        };
+        config.migrate();
+        if config.configs.values().any(NetworkConfig::missing_target_service) {
+            let backend = network::backend();
+            let migrated = config.migrate_target_services(&backend.network_services(), backend.ethernet_service().as_deref());
+            // 保存迁移结果，之后启动不再迁移
+            if migrated > 0 {
+                info!("已为 {} 个没有目标服务的配置设置默认服务", migrated);
+                if let Err(e) = config.save() {
+                    warn!("保存迁移后的配置失败: {}", e);
+                }
+            }
+            let remaining = config.configs.values().filter(|c| c.missing_target_service()).count();
+            if remaining > 0 {
+                warn!("{} 个有线配置没有目标服务且未找到有线网络服务，应用时使用当前选择的服务", remaining);
             }
-        } else {
-            Self::default()
+        }
+        config
+    }
+
+    /// 从临时文件或备份中读取第一个能解析的配置
+    fn recover() -> Option<Self> {
+        let candidates = std::iter::once(tmp_path(&Self::config_path()))
+            .chain((1..=BACKUP_COUNT).map(Self::backup_path));
+        for path in candidates {
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            if let Ok(mut config) = Self::parse(&path, &content) {
+                config.recovered_from = Some(path);
+                return Some(config);
+            }
+        }
+        None
+    }
+
+    /// 把旧版本的配置迁移到当前格式
+    fn migrate(&mut self) {
+        self.migrate_ids();
+        if self.version < 2 {
+            self.migrate_dns_mode();
+        }
+        if self.version < 3 {
+            self.migrate_ipv6_mode();
+        }
+        self.version = CONFIG_VERSION;
+    }
+
+    /// 版本 1 以 DNS 列表是否为空区分自动和手动，改为显式的 dns_mode
+    fn migrate_dns_mode(&mut self) {
+        for config in self.configs.values_mut() {
+            if !config.dns_servers.is_empty() {
+                config.dns_mode = DnsMode::Manual;
+            }
+        }
+    }
+
+    /// 版本 2 以 IPv6 地址是否为空区分手动和不修改，改为显式的 ipv6_mode
+    fn migrate_ipv6_mode(&mut self) {
+        for config in self.configs.values_mut() {
+            if config.ipv6_address.is_some() {
+                config.ipv6_mode = Ipv6Mode::Manual;
+            }
+        }
+    }
+
+    /// 为没有目标服务的旧配置设置默认服务，返回迁移的配置数量
+    /// WiFi 配置使用与 SSID 同名的服务（nmcli 按 SSID 命名连接），否则使用 Wi-Fi 服务；
+    /// 有线配置使用已连接的有线服务，没有时使用第一个有线服务，找不到时保持为空
+    fn migrate_target_services(&mut self, services: &[String], connected_ethernet: Option<&str>) -> usize {
+        let wifi_service = services
+            .iter()
+            .find(|s| matches!(s.as_str(), "Wi-Fi" | "AirPort"))
+            .map_or("Wi-Fi", String::as_str);
+        let ethernet_service = connected_ethernet
+            .or_else(|| services.iter().map(String::as_str).find(|s| is_ethernet_service(s)));
+
+        let mut migrated = 0;
+        for config in self.configs.values_mut().filter(|c| c.missing_target_service()) {
+            let service = match config.config_type {
+                ConfigType::Wifi if services.contains(&config.ssid) => Some(config.ssid.as_str()),
+                ConfigType::Wifi => Some(wifi_service),
+                ConfigType::Service => ethernet_service,
+            };
+            if let Some(service) = service {
+                config.target_service = Some(service.to_string());
+                migrated += 1;
+            }
+        }
+        migrated
+    }
+
+    /// 为旧版本（以名称为键）的配置分配 id，并改为以 id 为键
+    /// id 由名称确定性生成，同名的旧配置在任何机器上得到相同的 id
+    fn migrate_ids(&mut self) {
+        let configs = std::mem::take(&mut self.configs);
+        for mut config in configs.into_values() {
+            if config.id.is_empty() {
+                config.id = Uuid::new_v5(&Uuid::NAMESPACE_OID, config.name.as_bytes()).to_string();
+            }
+            self.configs.insert(config.config_key(), config);
         }
     }
 
     pub fn save(&self) -> Result<(), String> {
+        if self.load_failed {
+            return Err(t("err_config_unreadable_save").to_string());
+        }
         let path = Self::config_path();
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent).map_err(|e| e.to_string())?;
         }
-        let content = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
-        fs::write(&path, content).map_err(|e| e.to_string())
+        let content = self.serialize(&path)?;
+
+        // 内容没有变化时不写入，避免无意义的保存挤掉有用的备份
+        if fs::read_to_string(&path).is_ok_and(|old| old == content) {
+            return Ok(());
+        }
+        if path.exists() {
+            Self::rotate_backups(&path)?;
+        }
+
+        // 先写临时文件再重命名，写入中途崩溃不会损坏原文件
+        let tmp = tmp_path(&path);
+        fs::write(&tmp, content).map_err(|e| e.to_string())?;
+        fs::rename(&tmp, &path).map_err(|e| e.to_string())
+    }
+
+    /// 删除保存中途退出时留下的临时文件
+    pub fn remove_temp_files() {
+        let tmp = tmp_path(&Self::config_path());
+        if tmp.exists() {
+            let _ = fs::remove_file(tmp);
+        }
+    }
+
+    /// 按文件格式解析配置
+    fn parse(path: &Path, content: &str) -> Result<Self, String> {
+        if is_toml(path) {
+            toml::from_str(content).map_err(|e| e.to_string())
+        } else {
+            serde_json::from_str(content).map_err(|e| e.to_string())
+        }
+    }
+
+    /// 按文件格式序列化配置，TOML 文件已存在时保留其中的注释
+    fn serialize(&self, path: &Path) -> Result<String, String> {
+        if !is_toml(path) {
+            return serde_json::to_string_pretty(self).map_err(|e| e.to_string());
+        }
+        let content = toml::to_string(self).map_err(|e| e.to_string())?;
+        Ok(match fs::read_to_string(path) {
+            Ok(old) => preserve_toml_comments(&old, &content),
+            Err(_) => content,
+        })
+    }
+
+    /// 把当前的 JSON 配置迁移为 config.toml，之后读取和保存都使用它，原 config.json 保留不再使用
+    pub fn migrate_to_toml(&self) -> Result<PathBuf, String> {
+        if self.load_failed {
+            return Err(t("err_config_unreadable_migrate").to_string());
+        }
+        let path = Self::config_path();
+        if is_toml(&path) {
+            return Err(t("err_already_toml").to_string());
+        }
+        let content = toml::to_string(self).map_err(|e| e.to_string())?;
+        let toml_path = path.with_file_name("config.toml");
+        fs::write(&toml_path, format!("{}{}", TOML_HEADER, content)).map_err(|e| e.to_string())?;
+        Ok(toml_path)
+    }
+
+    /// 第 n 个备份的路径（1 为最新），与配置文件同格式
+    fn backup_path(n: usize) -> PathBuf {
+        let path = Self::config_path();
+        let name = path.file_name().and_then(|name| name.to_str()).unwrap_or("config.json");
+        path.with_file_name(format!("{}.bak.{}", name, n))
+    }
+
+    /// 备份轮转：bak.1 -> bak.2 ... 最旧的被删除，当前文件复制为 bak.1
+    fn rotate_backups(path: &Path) -> Result<(), String> {
+        let _ = fs::remove_file(Self::backup_path(BACKUP_COUNT));
+        for n in (1..BACKUP_COUNT).rev() {
+            let from = Self::backup_path(n);
+            if from.exists() {
+                fs::rename(&from, Self::backup_path(n + 1)).map_err(|e| e.to_string())?;
+            }
+        }
+        fs::copy(path, Self::backup_path(1)).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// 列出已有的备份：(编号, 修改时间)，编号越小越新
+    pub fn list_backups() -> Vec<(usize, SystemTime)> {
+        (1..=BACKUP_COUNT)
+            .filter_map(|n| {
+                let modified = fs::metadata(Self::backup_path(n)).and_then(|m| m.modified()).ok()?;
+                Some((n, modified))
+            })
+            .collect()
+    }
+
+    /// 恢复第 n 个备份并保存为当前配置（当前配置会先被备份）
+    pub fn restore_backup(n: usize) -> Result<AppConfig, String> {
+        let config = Self::import_from_path(&Self::backup_path(n))?;
+        config.save()?;
+        Ok(config)
+    }
+
+    /// 未激活的配置集所在的目录（与 config.json 同目录）
+    fn profiles_dir() -> PathBuf {
+        Self::config_path().with_file_name("profiles")
+    }
+
+    /// 配置集文件的路径
+    fn profile_path(name: &str) -> PathBuf {
+        Self::profiles_dir().join(format!("{}.json", name))
+    }
+
+    /// 所有配置集的名称（包括当前配置集），按名称排序
+    pub fn list_profiles(&self) -> Vec<String> {
+        let mut profiles = vec![self.active_profile.clone()];
+        if let Ok(entries) = fs::read_dir(Self::profiles_dir()) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().is_some_and(|ext| ext == "json")
+                    && let Some(name) = path.file_stem().and_then(|stem| stem.to_str())
+                {
+                    profiles.push(name.to_string());
+                }
+            }
+        }
+        profiles.sort();
+        profiles.dedup();
+        profiles
+    }
+
+    /// 切换到另一个配置集：当前的配置写入它的配置集文件，再读取目标配置集（不存在时新建为空）
+    pub fn switch_profile(&mut self, name: &str) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+            return Err(tf("err_profile_name_invalid", &[&name]));
+        }
+        if name == self.active_profile {
+            return Ok(());
+        }
+
+        let configs = match fs::read_to_string(Self::profile_path(name)) {
+            Ok(content) => serde_json::from_str(&content).map_err(|e| tf("err_profile_corrupted", &[&name, &e]))?,
+            Err(_) => HashMap::new(),
+        };
+        fs::create_dir_all(Self::profiles_dir()).map_err(|e| e.to_string())?;
+        let content = serde_json::to_string_pretty(&self.configs).map_err(|e| e.to_string())?;
+        fs::write(Self::profile_path(&self.active_profile), content).map_err(|e| e.to_string())?;
+
+        self.configs = configs;
+        self.active_profile = name.to_string();
+        self.save()?;
+        // 当前配置集保存在 config.json 中，保存成功后再删除它的配置集文件
+        let _ = fs::remove_file(Self::profile_path(name));
+        Ok(())
+    }
+
+    /// 删除未激活的配置集
+    pub fn delete_profile(&self, name: &str) -> Result<(), String> {
+        if name == self.active_profile {
+            return Err(t("err_delete_current_profile").to_string());
+        }
+        fs::remove_file(Self::profile_path(name)).map_err(|e| e.to_string())
+    }
+
+    /// 恢复窗口大小，未记录时使用默认值，并限制不小于最小尺寸
+    pub fn window_size(&self) -> [f32; 2] {
+        if self.window_width <= 0.0 || self.window_height <= 0.0
+            || !self.window_width.is_finite() || !self.window_height.is_finite()
+        {
+            return DEFAULT_WINDOW_SIZE;
+        }
+        [
+            self.window_width.max(MIN_WINDOW_SIZE[0]),
+            self.window_height.max(MIN_WINDOW_SIZE[1]),
+        ]
+    }
+
+    /// 恢复窗口位置，未记录或数值异常时返回 None
+    pub fn window_position(&self) -> Option<[f32; 2]> {
+        let x = self.window_x.filter(|v| v.is_finite())?;
+        let y = self.window_y.filter(|v| v.is_finite())?;
+        Some([x, y])
+    }
+
+    /// 去掉启动密码、同步令牌和 WiFi 密码的副本，用于诊断报告等需要分享的场合
+    pub fn redacted(&self) -> AppConfig {
+        let mut config = self.clone();
+        config.password_hash = None;
+        config.password_account = None;
+        config.sync_token.clear();
+        for cfg in config.configs.values_mut() {
+            cfg.wifi_password = None;
+            cfg.wifi_password_account = None;
+        }
+        config
+    }
+
+    /// 导出全部配置到指定文件，扩展名为 .toml 时导出为 TOML
+    /// 导出的文件可能会分享给别人，不包含密码、同步令牌等敏感信息
+    pub fn export_to_path(&self, path: &Path) -> Result<(), String> {
+        let content = self.redacted().serialize(path)?;
+        fs::write(path, content).map_err(|e| e.to_string())
+    }
+
+    /// 从指定文件导入配置（JSON 或 TOML）
+    pub fn import_from_path(path: &Path) -> Result<AppConfig, String> {
+        let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        if is_toml(path) {
+            let mut config = Self::parse(path, &content)?;
+            config.migrate();
+            return Ok(config);
+        }
+        let value: serde_json::Value = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+        // 顶层必须是 JSON 对象
+        if !value.is_object() {
+            return Err(t("err_not_json_object").to_string());
+        }
+        let mut config: AppConfig = serde_json::from_value(value).map_err(|e| e.to_string())?;
+        config.migrate();
+        Ok(config)
+    }
+
+    /// 合并导入的配置，按 config_key 去重，返回 (新增数, 跳过数)
+    pub fn merge_configs(&mut self, other: AppConfig) -> (usize, usize) {
+        let mut added = 0;
+        let mut skipped = 0;
+        for config in other.configs.into_values() {
+            match self.configs.entry(config.config_key()) {
+                Entry::Occupied(_) => skipped += 1,
+                Entry::Vacant(entry) => {
+                    entry.insert(config);
+                    added += 1;
+                }
+            }
+        }
+        (added, skipped)
     }
 
-    pub fn add_config(&mut self, config: NetworkConfig) {
-        // 使用唯一键存储
+    /// 合并从另一台机器拉取的配置：新配置直接加入（重名时自动改名），
+    /// 内容相同的跳过，内容不同的作为冲突返回，由用户决定是否覆盖
+    pub fn merge_remote(&mut self, remote: HashMap<String, NetworkConfig>) -> (usize, Vec<NetworkConfig>) {
+        let mut added = 0;
+        let mut conflicts = Vec::new();
+        for mut config in remote.into_values() {
+            match self.configs.get(&config.config_key()) {
+                Some(local) if local.same_settings(&config) => {}
+                Some(_) => conflicts.push(config),
+                None => {
+                    if self.find_by_name(&config.name).is_some() {
+                        config.name = format!("{} (同步)", config.name);
+                    }
+                    self.add_config(config);
+                    added += 1;
+                }
+            }
+        }
+        conflicts.sort_by(|a, b| a.name.cmp(&b.name));
+        (added, conflicts)
+    }
+
+    /// 是否设置了启动密码
+    pub fn has_password(&self) -> bool {
+        self.password_account.is_some() || self.password_hash.as_deref().is_some_and(|h| !h.is_empty())
+    }
+
+    /// 设置启动密码，空密码表示取消密码
+    /// 哈希优先保存到钥匙串，钥匙串不可用时保存在配置文件中
+    pub fn set_password(&mut self, password: &str) -> Result<(), String> {
+        let hash = if password.is_empty() {
+            None
+        } else {
+            let salt = SaltString::generate(&mut OsRng);
+            let hash = Argon2::default()
+                .hash_password(password.as_bytes(), &salt)
+                .map_err(|e| e.to_string())?;
+            Some(hash.to_string())
+        };
+
+        if let Some(account) = self.password_account.take() {
+            keychain::delete_password(&account);
+        }
+        self.password_hash = hash;
+        self.move_password_to_keychain();
+        Ok(())
+    }
+
+    /// 校验启动密码，未设置密码时总是通过
+    pub fn verify_password(&self, password: &str) -> bool {
+        let hash = match self.password_account.as_deref() {
+            // 钥匙串中的条目丢失时不允许解锁
+            Some(account) => match keychain::get_password(account) {
+                Some(hash) => hash,
+                None => return false,
+            },
+            None => match self.password_hash.as_deref().filter(|h| !h.is_empty()) {
+                Some(hash) => hash.to_string(),
+                None => return true,
+            },
+        };
+        match PasswordHash::new(&hash) {
+            Ok(parsed) => Argon2::default()
+                .verify_password(password.as_bytes(), &parsed)
+                .is_ok(),
+            Err(_) => false,
+        }
+    }
+
+    pub fn add_config(&mut self, mut config: NetworkConfig) {
+        // 使用唯一键存储，没有 id 的配置先分配 id
+        if config.id.is_empty() {
+            config.id = NetworkConfig::new_id();
+        }
         let key = config.config_key();
         self.configs.insert(key, config);
     }
 
     pub fn remove_config(&mut self, key: &str) {
-        self.configs.remove(key);
+        if let Some(account) = self.configs.remove(key).and_then(|c| c.wifi_password_account) {
+            keychain::delete_password(&account);
+        }
     }
 
-    /// 根据 SSID 和 MAC 地址查找自动应用的配置
-    pub fn find_auto_apply_config(&self, ssid: &str, router_mac: Option<&str>) -> Option<&NetworkConfig> {
-        // 只查找标记为自动应用的配置
-        // 优先精确匹配（SSID + MAC）
-        for config in self.configs.values() {
-            if config.auto_apply && config.matches_network(ssid, router_mac) {
-                return Some(config);
+    /// 配置文件中是否有本应保存在钥匙串中的密码
+    pub fn secrets_in_file(&self) -> bool {
+        self.password_hash.is_some() || self.configs.values().any(|c| c.wifi_password.is_some())
+    }
+
+    /// 把配置文件中的密码移到钥匙串，有改动时返回 true
+    pub fn move_secrets_to_keychain(&mut self) -> bool {
+        if !keychain::is_available() || !self.secrets_in_file() {
+            return false;
+        }
+        let mut changed = self.move_password_to_keychain();
+        for config in self.configs.values_mut() {
+            if config.wifi_password.is_some() && config.store_wifi_password().is_ok() {
+                changed = true;
             }
         }
+        changed
+    }
 
-        // 如果没有精确匹配，尝试仅匹配 SSID（兼容旧配置）
-        for config in self.configs.values() {
-            if config.auto_apply && config.ssid == ssid && config.router_mac.is_none() {
-                return Some(config);
+    /// 把启动密码哈希移到钥匙串，成功时返回 true
+    fn move_password_to_keychain(&mut self) -> bool {
+        let Some(hash) = self.password_hash.as_deref() else {
+            return false;
+        };
+        if !keychain::is_available() || keychain::set_password(APP_PASSWORD_ACCOUNT, hash).is_err() {
+            return false;
+        }
+        self.password_account = Some(APP_PASSWORD_ACCOUNT.to_string());
+        self.password_hash = None;
+        true
+    }
+
+    /// 按名称查找配置
+    pub fn find_by_name(&self, name: &str) -> Option<&NetworkConfig> {
+        self.configs.values().find(|c| c.name == name)
+    }
+
+    /// 是否存在同名但 id 不同的其他配置
+    pub fn has_name_conflict(&self, config: &NetworkConfig) -> bool {
+        self.configs
+            .values()
+            .any(|c| c.name == config.name && c.id != config.id)
+    }
+
+    /// 根据 SSID、MAC 地址、网卡 MAC 和当前时间查找自动应用的配置
+    /// 设置了定时规则的配置只在时间段内参与匹配
+    /// 多个配置同时匹配时：优先级高的优先；优先级相同时绑定 MAC 的优先，
+    /// 其次是绑定 BSSID 的、仅匹配 SSID 的，最后是不限 SSID 的；网络条件相同时带定时规则的优先；
+    /// 仍相同则按名称、再按 id 排序，结果与 HashMap 的遍历顺序无关
+    pub fn find_auto_apply_config(&self, ssid: &str, router_mac: Option<&str>, bssid: Option<&str>, adapter_mac: Option<&str>, now: NaiveDateTime) -> Option<&NetworkConfig> {
+        // 只查找标记为自动应用的配置
+        let mut candidates: Vec<&NetworkConfig> = self
+            .configs
+            .values()
+            .filter(|config| {
+                config.auto_apply
+                    && config.matches_network(ssid, router_mac, bssid, adapter_mac)
+                    && config.schedule.as_ref().is_none_or(|s| s.is_active(now))
+            })
+            .collect();
+
+        candidates.sort_by(|a, b| {
+            b.priority
+                .cmp(&a.priority)
+                .then_with(|| b.match_specificity().cmp(&a.match_specificity()))
+                .then_with(|| b.schedule.is_some().cmp(&a.schedule.is_some()))
+                .then_with(|| a.name.cmp(&b.name))
+                .then_with(|| a.id.cmp(&b.id))
+        });
+
+        candidates.into_iter().next()
+    }
+
+    /// 可以自动连接的配置：开启了 auto_join、SSID 固定（不是通配符或正则）、在定时规则的时间段内，
+    /// 且优先级高于当前网络匹配的配置（min_priority 为空表示当前没有匹配的配置），按优先级从高到低排序
+    pub fn auto_join_candidates(&self, min_priority: Option<i32>, now: NaiveDateTime) -> Vec<&NetworkConfig> {
+        let mut candidates: Vec<&NetworkConfig> = self
+            .configs
+            .values()
+            .filter(|config| {
+                config.auto_join
+                    && config.config_type == ConfigType::Wifi
+                    && !config.ssid.is_empty()
+                    && ssid_regex(&config.ssid, config.ssid_is_pattern).is_none()
+                    && config.schedule.as_ref().is_none_or(|s| s.is_active(now))
+                    && min_priority.is_none_or(|min| config.priority > min)
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.priority.cmp(&a.priority).then_with(|| a.name.cmp(&b.name)));
+        candidates
+    }
+
+    /// 有线网络断开时应用的备用配置：开启了 apply_on_wired_loss 且在定时规则的时间段内，
+    /// 多个时按优先级、名称、id 选择
+    pub fn wired_loss_fallback(&self, now: NaiveDateTime) -> Option<&NetworkConfig> {
+        self.configs
+            .values()
+            .filter(|config| config.apply_on_wired_loss && config.schedule.as_ref().is_none_or(|s| s.is_active(now)))
+            .min_by(|a, b| b.priority.cmp(&a.priority).then_with(|| a.name.cmp(&b.name)).then_with(|| a.id.cmp(&b.id)))
+    }
+
+    /// 当前环境的标签：限定了 SSID 且匹配当前网络的配置的标签（去重排序）
+    /// 不限 SSID 的配置在任何网络上都匹配（如手机热点），不参与判断环境
+    pub fn active_tags(&self, ssid: &str, router_mac: Option<&str>, bssid: Option<&str>, adapter_mac: Option<&str>) -> Vec<String> {
+        let mut tags: Vec<String> = self
+            .configs
+            .values()
+            .filter(|config| !config.matches_any_network() && config.matches_network(ssid, router_mac, bssid, adapter_mac))
+            .flat_map(|config| config.tags.iter().cloned())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
+    /// 所有配置用到的标签（去重排序）
+    pub fn all_tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self.configs.values().flat_map(|config| config.tags.iter().cloned()).collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
+    /// 检查自动应用配置之间的冲突：会在同一网络上同时匹配的配置对，以及不限网络的配置
+    pub fn auto_apply_conflicts(&self) -> Vec<AutoApplyConflict> {
+        let mut configs: Vec<&NetworkConfig> = self.configs.values().filter(|c| c.auto_apply).collect();
+        configs.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut conflicts = Vec::new();
+        for (i, a) in configs.iter().enumerate() {
+            // 不限网络的配置和所有配置都重叠，单独提示即可
+            if a.matches_any_network() {
+                conflicts.push(AutoApplyConflict::MatchesAll(a.config_key()));
+                continue;
+            }
+            for b in &configs[i + 1..] {
+                if !b.matches_any_network() && a.overlaps(b) {
+                    conflicts.push(AutoApplyConflict::Overlap(a.config_key(), b.config_key()));
+                }
             }
         }
+        conflicts
+    }
+}
 
-        None
+/// 自动应用配置之间的冲突（保存的是配置 id）
+#[derive(Debug, Clone, PartialEq)]
+pub enum AutoApplyConflict {
+    /// 两个配置会在同一个网络上同时匹配
+    Overlap(String, String),
+    /// 配置没有匹配条件，会在任何网络上匹配
+    MatchesAll(String),
+}
+
+impl AutoApplyConflict {
+    /// 冲突是否涉及指定的配置
+    pub fn involves(&self, key: &str) -> bool {
+        match self {
+            AutoApplyConflict::Overlap(a, b) => a == key || b == key,
+            AutoApplyConflict::MatchesAll(a) => a == key,
+        }
+    }
+}
+
+/// 配置比较时的字段
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiffField {
+    Mode,
+    Ip,
+    SubnetMask,
+    Router,
+    Dns,
+    Mtu,
+    HttpProxy,
+    HttpsProxy,
+    Pac,
+    ProxyBypass,
+}
+
+impl DiffField {
+    /// 显示名称
+    pub fn label(self) -> &'static str {
+        match self {
+            DiffField::Mode => t("field_mode"),
+            DiffField::Ip => "IP",
+            DiffField::SubnetMask => t("field_subnet_mask"),
+            DiffField::Router => t("field_router"),
+            DiffField::Dns => "DNS",
+            DiffField::Mtu => "MTU",
+            DiffField::HttpProxy => t("field_http_proxy"),
+            DiffField::HttpsProxy => t("field_https_proxy"),
+            DiffField::Pac => "PAC",
+            DiffField::ProxyBypass => t("field_proxy_bypass"),
+        }
+    }
+}
+
+/// 两个配置之间不同的一个字段
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDiff {
+    pub field: DiffField,
+    pub from: String,
+    pub to: String,
+}
+
+impl FieldDiff {
+    fn new(field: DiffField, from: String, to: String) -> Self {
+        Self { field, from, to }
     }
 }
 
 impl NetworkConfig {
     pub fn new(name: String, ssid: String, target_service: Option<String>, config_type: ConfigType, router_mac: Option<String>) -> Self {
         Self {
+            id: Self::new_id(),
             name,
+            notes: String::new(),
+            tags: Vec::new(),
             ssid,
+            ssids: Vec::new(),
+            ssid_is_pattern: false,
             config_type,
             router_mac,
+            bssid: None,
+            adapter_mac: None,
             auto_apply: false,
+            auto_join: false,
+            apply_on_wired_loss: false,
             target_service,
+            target_services: None,
+            manage_ip: true,
+            manage_dns: true,
             use_dhcp: true,
             ip_address: None,
             subnet_mask: None,
             router: None,
+            dns_mode: DnsMode::Auto,
             dns_servers: Vec::new(),
+            search_domains: Vec::new(),
+            ipv6_mode: Ipv6Mode::Unmanaged,
+            ipv6_address: None,
+            ipv6_prefix: None,
+            ipv6_router: None,
+            apply_steps_order: Vec::new(),
+            wifi_password: None,
+            wifi_password_account: None,
+            mtu: None,
+            schedule: None,
+            priority: 0,
+            probe_after_apply: false,
+            check_ip_conflict: false,
+            last_applied: None,
+            wins_servers: Vec::new(),
+            doh_template: None,
+            profile_path: None,
+            http_proxy: None,
+            https_proxy: None,
+            pac_url: None,
+            proxy_bypass: Vec::new(),
+            clear_proxy: false,
+            locked: false,
+            post_apply_command: None,
         }
     }
 
-    /// 生成配置的唯一键（使用配置名称）
+    /// 生成新的配置 id
+    pub fn new_id() -> String {
+        Uuid::new_v4().to_string()
+    }
+
+    /// 生成配置的唯一键（使用配置 id，改名不影响）
     pub fn config_key(&self) -> String {
-        // 使用配置名称作为唯一键
-        self.name.clone()
+        self.id.clone()
+    }
+
+    /// 所有匹配的 SSID（ssid 和 ssids 中非空的项），为空表示不限
+    pub fn match_ssids(&self) -> Vec<&str> {
+        std::iter::once(self.ssid.as_str())
+            .chain(self.ssids.iter().map(String::as_str))
+            .filter(|ssid| !ssid.is_empty())
+            .collect()
+    }
+
+    /// 是否绑定了有线网卡的 MAC 地址
+    fn binds_adapter(&self) -> bool {
+        self.config_type == ConfigType::Service && self.adapter_mac.is_some()
+    }
+
+    /// 应用时是否需要修改代理设置
+    pub fn manages_proxy(&self) -> bool {
+        self.http_proxy.is_some()
+            || self.https_proxy.is_some()
+            || self.pac_url.is_some()
+            || !self.proxy_bypass.is_empty()
+            || self.clear_proxy
+    }
+
+    /// 是否在任何网络上都匹配（没有 SSID、路由器 MAC，也没有绑定网卡）
+    pub fn matches_any_network(&self) -> bool {
+        self.match_ssids().is_empty() && self.router_mac.is_none() && !self.binds_adapter()
     }
 
     /// 匹配网络标识（检查 SSID 和可选的 MAC 地址）
-    pub fn matches_network(&self, ssid: &str, router_mac: Option<&str>) -> bool {
-        // SSID 为空表示不限制
-        if self.ssid.is_empty() {
+    pub fn matches_network(&self, ssid: &str, router_mac: Option<&str>, bssid: Option<&str>, adapter_mac: Option<&str>) -> bool {
+        // 绑定了网卡 MAC 的有线配置只在这块网卡（如某个扩展坞）连接时匹配
+        if self.binds_adapter() {
+            match (&self.adapter_mac, adapter_mac) {
+                (Some(config_mac), Some(current_mac)) if normalize_mac(config_mac) == normalize_mac(current_mac) => {}
+                _ => return false,
+            }
+        }
+
+        // 如果配置有 MAC，则需要 MAC 也匹配
+        if let Some(config_mac) = &self.router_mac {
+            match router_mac {
+                Some(current_mac) if config_mac == current_mac => {}
+                // 配置有 MAC 但当前无法获取 MAC，不匹配
+                _ => return false,
+            }
+        }
+
+        let ssids = self.match_ssids();
+        // SSID 为空表示不限制（设置了路由器 MAC 时只按 MAC 匹配，路由器改名后仍然匹配）
+        if ssids.is_empty() {
             return true;
         }
 
-        // SSID 必须匹配
-        if self.ssid != ssid {
+        // SSID 必须匹配其中之一
+        if !ssids.iter().any(|pattern| ssid_matches(pattern, ssid, self.ssid_is_pattern)) {
             return false;
         }
 
-        // 如果配置有 MAC，则需要 MAC 也匹配
-        if let Some(config_mac) = &self.router_mac {
-            if let Some(current_mac) = router_mac {
-                return config_mac == current_mac;
+        // 如果配置有 BSSID，则需要连接的是同一个 AP
+        if let Some(config_bssid) = &self.bssid {
+            match bssid {
+                Some(current_bssid) if normalize_mac(config_bssid) == normalize_mac(current_bssid) => {}
+                _ => return false,
             }
-            // 配置有 MAC 但当前无法获取 MAC，不匹配
-            return false;
         }
 
-        // 配置无 MAC，仅匹配 SSID
         true
     }
 
+    /// 是否符合列表筛选条件（名称、SSID、目标服务、标签，忽略大小写的子串匹配）
+    pub fn matches_filter(&self, filter: &str) -> bool {
+        let filter = filter.trim().to_lowercase();
+        if filter.is_empty() {
+            return true;
+        }
+
+        [Some(self.name.as_str()), self.target_service.as_deref()]
+            .into_iter()
+            .flatten()
+            .chain(self.match_ssids())
+            .chain(self.tags.iter().map(String::as_str))
+            .any(|field| field.to_lowercase().contains(&filter))
+    }
+
+    /// 没有设置任何目标服务（旧版本的配置），应用时只能使用当前选择的服务
+    pub fn missing_target_service(&self) -> bool {
+        self.target_service.is_none() && self.target_services.as_ref().is_none_or(|services| services.is_empty())
+    }
+
+    /// 实际要应用到的网络服务：优先 target_services，其次 target_service，都为空时使用 fallback
+    pub fn resolve_target_services(&self, fallback: &str) -> Vec<String> {
+        match &self.target_services {
+            Some(services) if !services.is_empty() => services.clone(),
+            _ => vec![self.target_service.clone().unwrap_or_else(|| fallback.to_string())],
+        }
+    }
+
+    /// 获取 WiFi 密码（配置文件中的或钥匙串中的）
+    pub fn resolve_wifi_password(&self) -> Option<String> {
+        self.wifi_password
+            .clone()
+            .or_else(|| self.wifi_password_account.as_deref().and_then(keychain::get_password))
+    }
+
+    /// 把 WiFi 密码存入钥匙串（账户名为配置 id），失败时密码仍保留在配置中
+    pub fn store_wifi_password(&mut self) -> Result<(), String> {
+        let Some(password) = self.wifi_password.as_deref() else {
+            return Ok(());
+        };
+        if !keychain::is_available() {
+            return Err(t("err_keychain_unavailable").to_string());
+        }
+        keychain::set_password(&self.id, password)?;
+        self.wifi_password_account = Some(self.id.clone());
+        self.wifi_password = None;
+        Ok(())
+    }
+
+    /// 两个配置是否会在同一个网络上同时匹配
+    /// 取两者中更具体的匹配条件构造一个网络，两个配置都能匹配它即为重叠
+    /// 多个 SSID 时逐个检查，任意一个两者都能匹配即为重叠
+    pub fn overlaps(&self, other: &NetworkConfig) -> bool {
+        let mut ssids = self.match_ssids();
+        if ssids.is_empty() {
+            ssids = other.match_ssids();
+        }
+        if ssids.is_empty() {
+            ssids.push("");
+        }
+        let router_mac = self.router_mac.as_deref().or(other.router_mac.as_deref());
+        let bssid = self.bssid.as_deref().or(other.bssid.as_deref());
+        let adapter_mac = self.adapter_mac.as_deref().or(other.adapter_mac.as_deref());
+        ssids.into_iter().any(|ssid| {
+            self.matches_network(ssid, router_mac, bssid, adapter_mac) && other.matches_network(ssid, router_mac, bssid, adapter_mac)
+        })
+    }
+
+    /// 两个配置的设置是否相同（忽略上次应用时间和 WiFi 密码，这些不随同步传输）
+    pub fn same_settings(&self, other: &NetworkConfig) -> bool {
+        let comparable = |config: &NetworkConfig| {
+            let mut config = config.clone();
+            config.last_applied = None;
+            config.wifi_password = None;
+            config.wifi_password_account = None;
+            serde_json::to_value(config).ok()
+        };
+        comparable(self) == comparable(other)
+    }
+
+    /// 比较把当前配置 self 切换为 other 时会变化的字段
+    /// 与 network::verify_config 一致：DHCP 时不比较地址，DNS 只在 other 会修改 DNS 时比较，MTU 只在 other 设置了时比较
+    /// other 不管理的 IP 或 DNS 设置不比较
+    pub fn diff(&self, other: &NetworkConfig) -> Vec<FieldDiff> {
+        let mode = |config: &NetworkConfig| if config.use_dhcp { "DHCP" } else { t("static_mode") }.to_string();
+        let value = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
+        let mut diffs = Vec::new();
+
+        if other.manage_ip && self.use_dhcp != other.use_dhcp {
+            diffs.push(FieldDiff::new(DiffField::Mode, mode(self), mode(other)));
+        }
+        if other.manage_ip && !other.use_dhcp {
+            let fields = [
+                (DiffField::Ip, &self.ip_address, &other.ip_address),
+                (DiffField::SubnetMask, &self.subnet_mask, &other.subnet_mask),
+                (DiffField::Router, &self.router, &other.router),
+            ];
+            for (field, from, to) in fields {
+                if from != to {
+                    diffs.push(FieldDiff::new(field, value(from), value(to)));
+                }
+            }
+        }
+
+        let dns = |config: &NetworkConfig| {
+            if config.dns_servers.is_empty() { t("dns_auto").to_string() } else { config.dns_servers.join(", ") }
+        };
+        let target_dns = match other.dns_mode {
+            _ if !other.manage_dns => None,
+            DnsMode::Manual => Some(dns(other)),
+            DnsMode::Auto => Some(t("dns_auto").to_string()),
+            DnsMode::Inherit => None,
+        };
+        if let Some(target_dns) = target_dns
+            && dns(self) != target_dns
+        {
+            diffs.push(FieldDiff::new(DiffField::Dns, dns(self), target_dns));
+        }
+
+        if other.mtu.is_some() && self.mtu != other.mtu {
+            let mtu = |mtu: Option<u32>| mtu.map(|mtu| mtu.to_string()).unwrap_or_else(|| "-".to_string());
+            diffs.push(FieldDiff::new(DiffField::Mtu, mtu(self.mtu), mtu(other.mtu)));
+        }
+
+        // 未设置的代理只在 clear_proxy 时会被关闭
+        let proxies = [
+            (DiffField::HttpProxy, &self.http_proxy, &other.http_proxy),
+            (DiffField::HttpsProxy, &self.https_proxy, &other.https_proxy),
+            (DiffField::Pac, &self.pac_url, &other.pac_url),
+        ];
+        for (field, from, to) in proxies {
+            if (to.is_some() || other.clear_proxy) && from != to {
+                diffs.push(FieldDiff::new(field, value(from), value(to)));
+            }
+        }
+        if (!other.proxy_bypass.is_empty() || other.clear_proxy) && self.proxy_bypass != other.proxy_bypass {
+            let bypass = |config: &NetworkConfig| {
+                if config.proxy_bypass.is_empty() { "-".to_string() } else { config.proxy_bypass.join(", ") }
+            };
+            diffs.push(FieldDiff::new(DiffField::ProxyBypass, bypass(self), bypass(other)));
+        }
+
+        diffs
+    }
+
+    /// 当前实际设置 live 与保存的配置不一致的字段（from 为保存的值，to 为当前值）
+    /// 只比较配置管理的设置；DNS 只在配置为手动 DNS 时比较（自动获取时读到的是 DHCP 下发的 DNS），
+    /// MTU 只在配置设置了时比较
+    pub fn drift_from(&self, live: &NetworkConfig) -> Vec<FieldDiff> {
+        self.diff(live)
+            .into_iter()
+            .filter(|diff| match diff.field {
+                DiffField::Dns => self.manage_dns && self.dns_mode == DnsMode::Manual,
+                DiffField::Mtu => self.mtu.is_some(),
+                DiffField::HttpProxy | DiffField::HttpsProxy | DiffField::Pac | DiffField::ProxyBypass => self.manages_proxy(),
+                DiffField::Mode | DiffField::Ip | DiffField::SubnetMask | DiffField::Router => self.manage_ip,
+            })
+            .collect()
+    }
+
+    /// 用当前实际设置更新配置的 IP、DNS 和模式，名称和匹配条件不变
+    /// 与 drift_from 比较的字段一致：不管理的设置、自动 DNS 和未设置的 MTU 保持不变
+    pub fn update_from(&mut self, live: &NetworkConfig) {
+        if self.manage_ip {
+            self.use_dhcp = live.use_dhcp;
+            self.ip_address = live.ip_address.clone();
+            self.subnet_mask = live.subnet_mask.clone();
+            self.router = live.router.clone();
+        }
+        if self.manage_dns && self.dns_mode == DnsMode::Manual {
+            self.dns_servers = live.dns_servers.clone();
+        }
+        if self.mtu.is_some() {
+            self.mtu = live.mtu;
+        }
+        if self.manages_proxy() {
+            self.http_proxy = live.http_proxy.clone();
+            self.https_proxy = live.https_proxy.clone();
+            self.pac_url = live.pac_url.clone();
+            self.proxy_bypass = live.proxy_bypass.clone();
+        }
+    }
+
+    /// 匹配条件的精确程度：绑定 MAC（路由器加 SSID 或有线网卡）> 只绑定路由器 MAC > 绑定 BSSID > 仅 SSID > 不限
+    pub fn match_specificity(&self) -> u8 {
+        let has_ssid = !self.match_ssids().is_empty();
+        if self.binds_adapter() || (has_ssid && self.router_mac.is_some()) {
+            4
+        } else if self.router_mac.is_some() {
+            3
+        } else if !has_ssid {
+            0
+        } else if self.bssid.is_some() {
+            2
+        } else {
+            1
+        }
+    }
+
     /// 显示名称（给用户看的）
     pub fn display_name(&self) -> String {
         let icon = match self.config_type {
@@ -160,16 +1599,407 @@ impl NetworkConfig {
             ConfigType::Service => "🔌",
         };
         let auto_icon = if self.auto_apply { "🔄" } else { "" };
+        let lock_icon = if self.locked { "🔒" } else { "" };
+
+        // 多个 SSID 时只显示第一个和其余的数量
+        let ssids = self.match_ssids();
+        let ssid_label = match ssids.as_slice() {
+            [] => String::new(),
+            [ssid] => ssid.to_string(),
+            [first, rest @ ..] => format!("{} +{}", first, rest.len()),
+        };
 
         if let Some(mac) = &self.router_mac {
-            // 只显示 MAC 后 8 位
-            let short_mac = &mac[mac.len().saturating_sub(8)..];
-            format!("{}{} {} [{}] ({})", auto_icon, icon, self.name, self.ssid, short_mac)
-        } else if !self.ssid.is_empty() {
-            format!("{}{} {} [{}]", auto_icon, icon, self.name, self.ssid)
+            let short_mac = short_mac(mac);
+            if ssid_label.is_empty() {
+                format!("{}{}{} {} ({})", lock_icon, auto_icon, icon, self.name, short_mac)
+            } else {
+                format!("{}{}{} {} [{}] ({})", lock_icon, auto_icon, icon, self.name, ssid_label, short_mac)
+            }
+        } else if !ssid_label.is_empty() {
+            format!("{}{}{} {} [{}]", lock_icon, auto_icon, icon, self.name, ssid_label)
         } else {
-            format!("{}{} {}", auto_icon, icon, self.name)
+            format!("{}{}{} {}", lock_icon, auto_icon, icon, self.name)
         }
     }
 }
 
+/// 检查静态 IP 的子网掩码是否连续、路由器是否在 IP 所在的子网内
+/// 地址格式错误时不检查（由 validate 单独报告）
+pub fn subnet_issue(config: &NetworkConfig) -> Option<&'static str> {
+    let parse = |value: &Option<String>| value.as_deref()?.trim().parse::<Ipv4Addr>().ok().map(u32::from);
+    let mask = parse(&config.subnet_mask)?;
+    // 连续的掩码是若干个 1 后面全是 0
+    if mask.leading_ones() + mask.trailing_zeros() != 32 {
+        return Some(t("subnet_not_contiguous"));
+    }
+    let ip = parse(&config.ip_address)?;
+    let router = parse(&config.router)?;
+    if ip & mask != router & mask {
+        return Some(t("router_outside_subnet"));
+    }
+    None
+}
+
+/// 解析 "主机:端口" 形式的代理地址
+pub fn parse_proxy_address(address: &str) -> Option<(&str, u16)> {
+    let (host, port) = address.trim().rsplit_once(':')?;
+    let port = port.parse().ok().filter(|port| *port > 0)?;
+    (!host.is_empty() && !host.contains(char::is_whitespace)).then_some((host, port))
+}
+
+/// 校验配置中的地址格式，返回所有错误信息
+pub fn validate(config: &NetworkConfig) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+
+    if config.name.trim().is_empty() {
+        errors.push(t("err_name_empty").to_string());
+    }
+
+    for pattern in config.match_ssids() {
+        if let Some(Err(e)) = ssid_regex(pattern, config.ssid_is_pattern) {
+            errors.push(tf("err_ssid_pattern", &[&pattern, &e]));
+        }
+    }
+
+    if let Some(schedule) = &config.schedule {
+        for (key, value) in [("err_start_time", &schedule.start), ("err_end_time", &schedule.end)] {
+            if parse_time(value).is_none() {
+                errors.push(tf(key, &[value]));
+            }
+        }
+        if !schedule.days.contains(&true) {
+            errors.push(t("err_no_schedule_days").to_string());
+        }
+    }
+
+    // 不管理的 IP 或 DNS 设置应用时不会使用，不需要填写
+    if config.manage_ip && !config.use_dhcp {
+        let fields = [
+            (t("field_ip_address"), &config.ip_address),
+            (t("field_subnet_mask"), &config.subnet_mask),
+            (t("field_router"), &config.router),
+        ];
+        for (label, value) in fields {
+            match value.as_deref().map(str::trim) {
+                None | Some("") => errors.push(tf("err_static_missing", &[&label])),
+                Some(v) if v.parse::<Ipv4Addr>().is_err() => {
+                    errors.push(tf("err_static_invalid", &[&label, &v]));
+                }
+                Some(_) => {}
+            }
+        }
+        if let Some(issue) = subnet_issue(config) {
+            errors.push(issue.to_string());
+        }
+    }
+
+    if config.ipv6_mode == Ipv6Mode::Manual && config.ipv6_address.is_none() {
+        errors.push(t("err_ipv6_missing").to_string());
+    }
+
+    if let Some(ipv6) = config.ipv6_address.as_deref()
+        && ipv6.trim().parse::<Ipv6Addr>().is_err()
+    {
+        errors.push(tf("err_ipv6_invalid", &[&ipv6]));
+    }
+
+    // 以太网帧的 MTU 不会小于 68（IPv4 最小值）或大于 9216（常见巨帧上限）
+    if let Some(mtu) = config.mtu
+        && !(68..=9216).contains(&mtu)
+    {
+        errors.push(tf("err_mtu_range", &[&mtu]));
+    }
+
+    if let Some(prefix) = config.ipv6_prefix
+        && prefix > 128
+    {
+        errors.push(tf("err_ipv6_prefix_range", &[&prefix]));
+    }
+
+    if config.manage_dns && config.dns_mode == DnsMode::Manual && config.dns_servers.is_empty() {
+        errors.push(t("err_manual_dns_empty").to_string());
+    }
+
+    // DNS 可以是 IPv4 或 IPv6 地址
+    for dns in &config.dns_servers {
+        if dns.trim().parse::<IpAddr>().is_err() {
+            errors.push(tf("err_dns_invalid", &[dns]));
+        }
+    }
+
+    for wins in &config.wins_servers {
+        if wins.trim().parse::<Ipv4Addr>().is_err() {
+            errors.push(tf("err_wins_invalid", &[wins]));
+        }
+    }
+
+    if let Some(template) = config.doh_template.as_deref().map(str::trim)
+        && !template.is_empty()
+        && !template.starts_with("https://")
+    {
+        errors.push(tf("err_doh_https", &[&template]));
+    }
+
+    for proxy in [&config.http_proxy, &config.https_proxy].into_iter().flatten() {
+        if parse_proxy_address(proxy).is_none() {
+            errors.push(tf("err_proxy_invalid", &[proxy]));
+        }
+    }
+    if let Some(url) = config.pac_url.as_deref().map(str::trim)
+        && !url.starts_with("http://")
+        && !url.starts_with("https://")
+    {
+        errors.push(tf("err_pac_url", &[&url]));
+    }
+
+    if let Some(path) = config.profile_path.as_deref().map(str::trim)
+        && !path.ends_with(".mobileconfig")
+    {
+        errors.push(tf("err_profile_ext", &[&path]));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+
+    use super::{AppConfig, CONFIG_VERSION, ConfigType, DiffField, DnsMode, Ipv6Mode, NetworkConfig, short_mac};
+
+    const ROUTER_MAC: &str = "aa:bb:cc:dd:ee:ff";
+
+    fn now() -> NaiveDateTime {
+        NaiveDateTime::parse_from_str("2024-01-01 12:00:00", "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    fn auto_config(id: &str, name: &str, ssid: &str, router_mac: Option<&str>) -> NetworkConfig {
+        NetworkConfig {
+            id: id.to_string(),
+            auto_apply: true,
+            ..NetworkConfig::new(name.to_string(), ssid.to_string(), None, ConfigType::Wifi, router_mac.map(str::to_string))
+        }
+    }
+
+    fn app_config(configs: Vec<NetworkConfig>) -> AppConfig {
+        let mut app = AppConfig::default();
+        for config in configs {
+            app.add_config(config);
+        }
+        app
+    }
+
+    /// 在 Office 网络（路由器 MAC 为 router_mac）上匹配到的配置名称
+    fn matched(app: &AppConfig, router_mac: Option<&str>) -> Option<String> {
+        app.find_auto_apply_config("Office", router_mac, None, None, now()).map(|c| c.name.clone())
+    }
+
+    #[test]
+    fn only_ssid_config_matches() {
+        let app = app_config(vec![
+            auto_config("1", "办公室", "Office", None),
+            auto_config("2", "家", "Home", None),
+        ]);
+        assert_eq!(matched(&app, Some(ROUTER_MAC)).as_deref(), Some("办公室"));
+    }
+
+    #[test]
+    fn mac_bound_config_beats_ssid_only() {
+        let app = app_config(vec![
+            auto_config("1", "办公室", "Office", None),
+            auto_config("2", "办公室主路由", "Office", Some(ROUTER_MAC)),
+        ]);
+        assert_eq!(matched(&app, Some(ROUTER_MAC)).as_deref(), Some("办公室主路由"));
+    }
+
+    #[test]
+    fn mac_mismatch_falls_back_to_ssid_only() {
+        let app = app_config(vec![
+            auto_config("1", "办公室", "Office", None),
+            auto_config("2", "办公室主路由", "Office", Some(ROUTER_MAC)),
+        ]);
+        assert_eq!(matched(&app, Some("11:22:33:44:55:66")).as_deref(), Some("办公室"));
+        // 无法获取路由器 MAC 时，绑定 MAC 的配置也不匹配
+        assert_eq!(matched(&app, None).as_deref(), Some("办公室"));
+
+        let app = app_config(vec![auto_config("2", "办公室主路由", "Office", Some(ROUTER_MAC))]);
+        assert_eq!(matched(&app, Some("11:22:33:44:55:66")), None);
+    }
+
+    #[test]
+    fn empty_ssid_is_catch_all() {
+        let app = app_config(vec![auto_config("1", "任意网络", "", None)]);
+        assert_eq!(matched(&app, Some(ROUTER_MAC)).as_deref(), Some("任意网络"));
+
+        // 有更具体的配置时不使用不限 SSID 的配置
+        let app = app_config(vec![
+            auto_config("1", "任意网络", "", None),
+            auto_config("2", "办公室", "Office", None),
+        ]);
+        assert_eq!(matched(&app, Some(ROUTER_MAC)).as_deref(), Some("办公室"));
+    }
+
+    #[test]
+    fn router_mac_only_ignores_ssid() {
+        let app = app_config(vec![auto_config("1", "家", "", Some(ROUTER_MAC))]);
+        // 路由器改名后仍然匹配
+        assert_eq!(matched(&app, Some(ROUTER_MAC)).as_deref(), Some("家"));
+        assert_eq!(app.find_auto_apply_config("Home-New", Some(ROUTER_MAC), None, None, now()).map(|c| c.id.as_str()), Some("1"));
+        assert_eq!(matched(&app, Some("11:22:33:44:55:66")), None);
+        assert_eq!(matched(&app, None), None);
+    }
+
+    #[test]
+    fn router_mac_only_ranks_between_mac_and_ssid() {
+        let configs = vec![
+            auto_config("1", "办公室", "Office", None),
+            auto_config("2", "路由器", "", Some(ROUTER_MAC)),
+            auto_config("3", "任意网络", "", None),
+        ];
+        assert_eq!(matched(&app_config(configs.clone()), Some(ROUTER_MAC)).as_deref(), Some("路由器"));
+
+        let mut configs = configs;
+        configs.push(auto_config("4", "办公室主路由", "Office", Some(ROUTER_MAC)));
+        assert_eq!(matched(&app_config(configs), Some(ROUTER_MAC)).as_deref(), Some("办公室主路由"));
+    }
+
+    #[test]
+    fn priority_beats_specificity() {
+        let app = app_config(vec![
+            auto_config("1", "办公室主路由", "Office", Some(ROUTER_MAC)),
+            NetworkConfig { priority: 10, ..auto_config("2", "任意网络", "", None) },
+        ]);
+        assert_eq!(matched(&app, Some(ROUTER_MAC)).as_deref(), Some("任意网络"));
+    }
+
+    #[test]
+    fn ties_are_broken_by_name_then_id() {
+        let configs = vec![
+            auto_config("3", "B", "Office", None),
+            auto_config("2", "A", "Office", None),
+            auto_config("1", "A", "Office", None),
+        ];
+        // 不同的插入顺序得到相同的结果
+        for rotation in 0..configs.len() {
+            let mut rotated = configs.clone();
+            rotated.rotate_left(rotation);
+            let app = app_config(rotated);
+            let found = app.find_auto_apply_config("Office", None, None, None, now()).unwrap();
+            assert_eq!((found.name.as_str(), found.id.as_str()), ("A", "1"));
+        }
+    }
+
+    fn target_of(app: &AppConfig, id: &str) -> Option<String> {
+        app.configs.get(id).and_then(|c| c.target_service.clone())
+    }
+
+    #[test]
+    fn migrate_target_services_by_config_type() {
+        let services: Vec<String> = ["Wi-Fi", "WLAN", "USB 10/100/1000 LAN", "Thunderbolt Bridge"].map(String::from).to_vec();
+        let mut app = app_config(vec![
+            auto_config("1", "办公室", "Office", None),
+            NetworkConfig { config_type: ConfigType::Service, ..auto_config("2", "有线", "", None) },
+            NetworkConfig { target_service: Some("Thunderbolt Bridge".to_string()), ..auto_config("3", "雷雳", "", None) },
+            NetworkConfig { target_services: Some(vec!["Wi-Fi".to_string()]), ..auto_config("4", "多个目标", "", None) },
+        ]);
+        assert_eq!(app.migrate_target_services(&services, None), 2);
+        assert_eq!(target_of(&app, "1").as_deref(), Some("Wi-Fi"));
+        assert_eq!(target_of(&app, "2").as_deref(), Some("USB 10/100/1000 LAN"));
+        assert_eq!(target_of(&app, "3").as_deref(), Some("Thunderbolt Bridge"));
+        assert_eq!(target_of(&app, "4"), None);
+    }
+
+    #[test]
+    fn migrate_target_services_prefers_connection_named_after_ssid() {
+        let services: Vec<String> = ["Office", "Wired connection 1"].map(String::from).to_vec();
+        let mut app = app_config(vec![
+            auto_config("1", "办公室", "Office", None),
+            NetworkConfig { config_type: ConfigType::Service, ..auto_config("2", "有线", "", None) },
+        ]);
+        assert_eq!(app.migrate_target_services(&services, Some("eth-dock")), 2);
+        assert_eq!(target_of(&app, "1").as_deref(), Some("Office"));
+        assert_eq!(target_of(&app, "2").as_deref(), Some("eth-dock"));
+    }
+
+    #[test]
+    fn migrate_target_services_skips_unknown_ethernet() {
+        let services = vec!["Wi-Fi".to_string()];
+        let mut app = app_config(vec![NetworkConfig { config_type: ConfigType::Service, ..auto_config("1", "有线", "", None) }]);
+        assert_eq!(app.migrate_target_services(&services, None), 0);
+        assert_eq!(target_of(&app, "1"), None);
+    }
+
+    #[test]
+    fn export_leaves_out_secrets() {
+        let mut app = app_config(vec![NetworkConfig {
+            wifi_password: Some("wifi-secret".to_string()),
+            ..auto_config("1", "办公室", "Office", None)
+        }]);
+        app.password_hash = Some("hash-secret".to_string());
+        app.sync_token = "token-secret".to_string();
+
+        let path = std::env::temp_dir().join(format!("network-switcher-export-{}.json", std::process::id()));
+        app.export_to_path(&path).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert!(content.contains("办公室"));
+        for secret in ["wifi-secret", "hash-secret", "token-secret"] {
+            assert!(!content.contains(secret), "导出内容包含 {}", secret);
+        }
+    }
+
+    #[test]
+    fn migrate_v2_sets_manual_ipv6_mode() {
+        let mut app = app_config(vec![
+            NetworkConfig { ipv6_address: Some("fd00::20".to_string()), ..auto_config("1", "静态 IPv6", "Office", None) },
+            auto_config("2", "家", "Home", None),
+        ]);
+        app.version = 2;
+        app.migrate();
+        assert_eq!(app.configs["1"].ipv6_mode, Ipv6Mode::Manual);
+        assert_eq!(app.configs["2"].ipv6_mode, Ipv6Mode::Unmanaged);
+        assert_eq!(app.version, CONFIG_VERSION);
+    }
+
+    #[test]
+    fn drift_only_compares_managed_fields() {
+        let saved = NetworkConfig { mtu: Some(1400), ..auto_config("1", "办公室", "Office", None) };
+        let live = NetworkConfig {
+            dns_servers: vec!["192.168.1.1".to_string()],
+            mtu: Some(1500),
+            ..saved.clone()
+        };
+        // 自动 DNS 时读到的是 DHCP 下发的 DNS，不算偏离
+        let fields: Vec<DiffField> = saved.drift_from(&live).into_iter().map(|diff| diff.field).collect();
+        assert_eq!(fields, vec![DiffField::Mtu]);
+
+        let manual = NetworkConfig { dns_mode: DnsMode::Manual, dns_servers: vec!["1.1.1.1".to_string()], ..saved };
+        let fields: Vec<DiffField> = manual.drift_from(&live).into_iter().map(|diff| diff.field).collect();
+        assert_eq!(fields, vec![DiffField::Dns, DiffField::Mtu]);
+    }
+
+    #[test]
+    fn wired_loss_fallback_picks_highest_priority() {
+        let app = app_config(vec![
+            NetworkConfig { apply_on_wired_loss: true, ..auto_config("1", "家", "Home", None) },
+            NetworkConfig { apply_on_wired_loss: true, priority: 5, ..auto_config("2", "办公室", "Office", None) },
+            NetworkConfig { priority: 10, ..auto_config("3", "任意网络", "", None) },
+        ]);
+        assert_eq!(app.wired_loss_fallback(now()).map(|c| c.id.as_str()), Some("2"));
+        assert!(app_config(vec![auto_config("1", "家", "Home", None)]).wired_loss_fallback(now()).is_none());
+    }
+
+    #[test]
+    fn short_mac_handles_non_ascii() {
+        let mac = "aa:bb:cc:dd:ee:ff路由器";
+        assert_eq!(short_mac(mac), "ee:ff路由器");
+        let config = auto_config("1", "办公室", "Office", Some(mac));
+        assert!(config.display_name().ends_with("(ee:ff路由器)"));
+    }
+}
@@ -1,32 +1,275 @@
 use eframe::egui::{self, FontData, FontDefinitions, FontFamily};
-use crate::config::{AppConfig, ConfigType, NetworkConfig};
-use crate::network;
+use log::{debug, info, warn};
+use crate::config::{self, AppConfig, ConfigType, NetworkConfig, Theme};
+use crate::i18n::{self, Lang, t, tf};
+use crate::network::{self, NetworkError, ProfileInstall, Reachability, ServiceState, SsidStatus};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use crate::notify;
+use crate::status::{AppStatus, StatusServer};
+use crate::sync::{self, SyncServer};
+use crate::tray::{Tray, TrayAction};
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use std::thread;
 
 /// 后台网络状态
 #[derive(Clone, Default)]
 struct NetworkState {
     ssid: Option<String>,
+    wifi_status: Option<SsidStatus>,
+    wifi_device: Option<String>,
     router_mac: Option<String>,
+    bssid: Option<String>,
+    adapter_mac: Option<String>,
+    vpn_active: bool,
+    proxy_enabled: bool,
+    // 是否有已连接的有线网络（与 Wi-Fi 是否同时连接无关）
+    ethernet_connected: bool,
+    link_speed: Option<String>,
+    portal_url: Option<String>,
     config: Option<NetworkConfig>,
-    is_loading: bool,
+    // 当前服务和各配置目标服务的状态
+    service_states: HashMap<String, ServiceState>,
+    // 后台联网检测的结果，取走后清空
+    reachability: Option<Reachability>,
+    // 后台查询的公网 IP，取走后清空
+    public_ip: Option<Result<String, String>>,
+    // 切换服务后单独读取的服务设置，取走后清空
+    service_config: Option<ServiceConfig>,
+    // 结果对应的刷新请求编号，旧请求的结果不会覆盖新请求的
+    generation: u64,
 }
 
+/// 单个服务的设置、链路速度和状态
+#[derive(Clone)]
+struct ServiceConfig {
+    service: String,
+    config: Option<NetworkConfig>,
+    link_speed: Option<String>,
+    state: ServiceState,
+}
+
+/// 后台应用配置的进度和结果
+#[derive(Default)]
+struct ApplyState {
+    // 正在执行的步骤：(服务, 步骤名称)
+    progress: Option<(String, String)>,
+    outcome: Option<ApplyOutcome>,
+}
+
+/// 后台扫描并自动连接 WiFi 的结果：连接的配置和连接结果，没有可连接的网络时为空
+type AutoJoinOutcome = Option<(NetworkConfig, Result<(), String>)>;
+
+/// 配置列表的标签筛选
+#[derive(Clone, PartialEq, Default)]
+enum TagFilter {
+    #[default]
+    All,
+    // 只显示带有当前环境标签的配置
+    Current,
+    Tag(String),
+}
+
+/// 编辑面板的撤销/重做记录，保存每批修改前的配置快照
+#[derive(Default)]
+struct EditHistory {
+    undo: Vec<NetworkConfig>,
+    redo: Vec<NetworkConfig>,
+    // 上一批修改完成时的配置，当前配置与它不同说明有新的修改
+    snapshot: Option<NetworkConfig>,
+}
+
+impl EditHistory {
+    /// 开始编辑新的配置时清空记录
+    fn reset(&mut self, cfg: &NetworkConfig) {
+        self.undo.clear();
+        self.redo.clear();
+        self.snapshot = Some(cfg.clone());
+    }
+
+    /// 有新的修改时把修改前的快照压入撤销栈，超过上限时丢弃最早的
+    fn record(&mut self, current: &NetworkConfig) {
+        let Some(snapshot) = self.snapshot.as_mut() else {
+            self.snapshot = Some(current.clone());
+            return;
+        };
+        if snapshot == current {
+            return;
+        }
+        self.undo.push(std::mem::replace(snapshot, current.clone()));
+        if self.undo.len() > EDIT_UNDO_LIMIT {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    fn undo(&mut self, current: &mut NetworkConfig) -> bool {
+        let Some(previous) = self.undo.pop() else {
+            return false;
+        };
+        self.redo.push(std::mem::replace(current, previous.clone()));
+        self.snapshot = Some(previous);
+        true
+    }
+
+    fn redo(&mut self, current: &mut NetworkConfig) -> bool {
+        let Some(next) = self.redo.pop() else {
+            return false;
+        };
+        self.undo.push(std::mem::replace(current, next.clone()));
+        self.snapshot = Some(next);
+        true
+    }
+}
+
+/// 后台应用配置的结果，由界面线程汇总
+struct ApplyOutcome {
+    config: NetworkConfig,
+    automatic: bool,
+    applied: Vec<String>,
+    problems: Vec<String>,
+    // 因权限不足失败的服务
+    denied: Vec<String>,
+    // 因 IP 冲突失败的服务和冲突说明
+    conflicted: Vec<String>,
+    conflict_detail: String,
+    // 安装描述文件的结果，没有安装时为 None
+    profile: Option<Result<ProfileInstall, NetworkError>>,
+}
+
+/// 提示消息的级别，决定显示颜色和停留时间
+#[derive(Clone, Copy, PartialEq)]
+enum ToastLevel {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl ToastLevel {
+    fn color(self) -> egui::Color32 {
+        match self {
+            ToastLevel::Info => egui::Color32::GRAY,
+            ToastLevel::Success => egui::Color32::from_rgb(100, 200, 100),
+            ToastLevel::Warning => WARNING_COLOR,
+            ToastLevel::Error => egui::Color32::RED,
+        }
+    }
+
+    /// 警告和错误停留更久，以免来不及看
+    fn duration(self) -> Duration {
+        match self {
+            ToastLevel::Info | ToastLevel::Success => TOAST_DURATION,
+            ToastLevel::Warning | ToastLevel::Error => TOAST_DURATION * 2,
+        }
+    }
+}
+
+/// 状态区域下方显示的一条提示消息
+struct Toast {
+    text: String,
+    level: ToastLevel,
+    created: Instant,
+}
+
+impl Toast {
+    fn new(level: ToastLevel, text: impl Into<String>) -> Self {
+        Self { text: text.into(), level, created: Instant::now() }
+    }
+
+    fn expired(&self) -> bool {
+        self.created.elapsed() >= self.level.duration()
+    }
+}
+
+/// DNS 测试结果：(服务器, 结果)，结果为 None 表示仍在测试
+type DnsTestResults = Vec<(String, Option<Result<Duration, String>>)>;
+
+/// 每个服务应用配置后会变化的字段：(服务, 字段列表)
+type ServiceDiffs = Vec<(String, Vec<config::FieldDiff>)>;
+
+/// 后台拉取局域网配置的结果
+type PullResult = Result<HashMap<String, NetworkConfig>, String>;
+
 pub struct NetworkSwitcherApp {
     config: AppConfig,
     current_ssid: Option<String>,
+    // WiFi 连接状态，还没有刷新过时为 None
+    current_wifi_status: Option<SsidStatus>,
+    current_wifi_device: Option<String>,
     current_router_mac: Option<String>,
+    current_bssid: Option<String>,
+    // 有线网卡自身的 MAC 地址
+    current_adapter_mac: Option<String>,
+    current_vpn_active: bool,
+    current_proxy_enabled: bool,
+    current_link_speed: Option<String>,
+    // 最近一次联网检测的结果
+    current_reachability: Option<Reachability>,
+    // 公网 IP 查询结果，以及变化前的公网 IP
+    current_public_ip: Option<Result<String, String>>,
+    previous_public_ip: Option<String>,
+    // 需要登录门户时的门户地址
+    current_portal_url: Option<String>,
+    // 是否有已连接的有线网络，还没有检测过时为 None
+    current_ethernet_connected: Option<bool>,
+    // 有线网络断开的时间，持续断开 WIRED_LOSS_DEBOUNCE 后应用备用配置
+    wired_lost_at: Option<Instant>,
     current_network_config: Option<NetworkConfig>,
+    // 服务状态（后台刷新时更新），没有记录的服务视为可以应用
+    service_states: HashMap<String, ServiceState>,
     network_services: Vec<String>,
     selected_service_idx: usize,
 
     // 编辑状态
     editing_config: Option<NetworkConfig>,
+    edit_history: EditHistory,
+    // 保存时名称与其他配置冲突，等待用户确认
+    confirm_overwrite: bool,
+    // 因权限不足应用失败，等待确认以管理员身份重试：(配置, 失败的服务, 是否自动触发)
+    confirm_elevated: Option<(NetworkConfig, Vec<String>, bool)>,
+    // 首次运行时当前用户不是管理员，提示如何获取权限
+    show_permission_guide: bool,
+    // 静态 IP 已被占用，等待确认是否仍然应用：(配置, 冲突的服务, 是否自动触发, 冲突说明)
+    confirm_conflict: Option<(NetworkConfig, Vec<String>, bool, String)>,
+    // 等待确认把当前服务恢复为 DHCP
+    confirm_reset_dhcp: bool,
+    // 等待确认删除的配置 id
+    confirm_delete: Option<String>,
+    // 删除锁定的配置时已勾选确认（未设置启动密码时）
+    confirm_delete_locked: bool,
+    // 正在编辑的锁定配置是否已解锁，未解锁时编辑面板只读
+    edit_unlocked: bool,
+    // 解锁锁定配置时输入的启动密码
+    unlock_password_input: String,
+    unlock_password_error: bool,
+    // 刚删除的配置和删除时间，撤销提示期间可以恢复
+    recently_deleted: Option<(NetworkConfig, Instant)>,
+    validation_errors: Vec<String>,
     new_dns_input: String,
-    status_message: String,
+    new_search_domain_input: String,
+    new_extra_ssid_input: String,
+    new_tag_input: String,
+    new_profile_name: String,
+    new_preset_name: String,
+    // 设置中编辑的忽略 DNS 前缀（逗号分隔），失去焦点时写入配置
+    dns_ignore_input: String,
+    // 配置列表筛选文本（仅保存在内存中）
+    config_filter: String,
+    tag_filter: TagFilter,
+    // 配置列表按最近使用排序（默认按名称）
+    sort_by_recent: bool,
+    // 配置列表按路由器 MAC 分组显示（仅保存在内存中）
+    group_by_router: bool,
+    // 紧凑模式下拉框选中的配置，为空时选中匹配当前网络的配置
+    compact_selected: Option<String>,
+    // 提示消息，按时间先后排列，过期后自动移除
+    toasts: Vec<Toast>,
     show_add_dialog: bool,
+    // 命令预览窗口：(配置名称, 命令行列表, 每个服务与当前设置不同的字段)
+    preview_commands: Option<(String, Vec<String>, ServiceDiffs)>,
     new_config_name: String,
     new_ssid_input: String,
     bind_router_mac: bool,
@@ -38,28 +281,142 @@ pub struct NetworkSwitcherApp {
     // 自动检测
     last_check: Instant,
     last_applied_key: Option<String>,
+    // 上次检查时处于生效时间段内的定时配置
+    last_active_schedules: Vec<String>,
 
     // 后台刷新状态
     bg_state: Arc<Mutex<NetworkState>>,
     is_refreshing: bool,
+    // 最近一次刷新请求的编号和服务，只接受这次请求的结果
+    refresh_generation: u64,
+    refreshing_service: String,
+    // 切换服务后正在单独读取该服务的设置
+    is_refreshing_service: bool,
+    // 后台应用配置状态
+    apply_state: Arc<Mutex<ApplyState>>,
+    is_applying: bool,
+    // 后台扫描并自动连接 WiFi 的状态
+    auto_join_state: Arc<Mutex<Option<AutoJoinOutcome>>>,
+    is_auto_joining: bool,
+    // 后台生成诊断报告的结果：保存的路径或错误
+    diagnostics_state: Arc<Mutex<Option<Result<PathBuf, String>>>>,
+    is_dumping_diagnostics: bool,
+    last_wifi_scan: Instant,
+    // 上次自动连接的时间，冷却期内不再自动连接，避免在两个网络之间来回切换
+    last_auto_join: Option<Instant>,
+    // 临时暂停自动切换到这个时间（不保存，到期自动恢复）
+    auto_switch_paused_until: Option<Instant>,
+    // 后台联网检测状态，结果写入 bg_state
+    is_probing: bool,
+    last_reachability_check: Instant,
+    is_fetching_public_ip: bool,
+    // 编辑面板中的 DNS 测试结果（后台线程写入）
+    dns_test_results: Arc<Mutex<DnsTestResults>>,
 
     // 密码验证
     is_authenticated: bool,
     password_input: String,
     password_error: bool,
+
+    // 设置/修改密码
+    show_password_setup: bool,
+    show_change_password: bool,
+    new_password_input: String,
+    confirm_password_input: String,
+    password_setup_error: Option<String>,
+
+    // 菜单栏图标（仅 macOS）
+    tray: Option<Tray>,
+    // 状态 socket（expose_socket 开启时）
+    status_server: Option<StatusServer>,
+    // 局域网同步服务（sync_enabled 开启时）
+    sync_server: Option<SyncServer>,
+    // 从另一台机器拉取配置：地址、令牌和后台拉取结果
+    sync_peer: String,
+    sync_peer_token: String,
+    sync_pull: Arc<Mutex<Option<PullResult>>>,
+    is_pulling: bool,
+    // 拉取后内容不同的配置，bool 表示是否用对方的覆盖本地
+    sync_conflicts: Option<Vec<(NetworkConfig, bool)>>,
+
+    // 应用历史
+    show_history: bool,
+    history_entries: Vec<network::ApplyLogEntry>,
+
+    // 自动应用冲突检查面板
+    show_conflicts: bool,
+
+    // 从 macOS 位置导入窗口：可选的位置列表
+    location_import: Option<Vec<String>>,
 }
 
+/// 警告提示的颜色
+const WARNING_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 160, 50);
+
+/// 成功和普通提示消息的显示时间
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+
+/// 同时显示的提示消息数量，超出时移除最早的
+const TOAST_LIMIT: usize = 5;
+
+/// 删除配置后可以撤销的时间
+const UNDO_DELETE_TIMEOUT: Duration = Duration::from_secs(8);
+
+/// 自动连接时扫描 WiFi 的间隔
+const WIFI_SCAN_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 自动连接后的冷却时间
+const AUTO_JOIN_COOLDOWN: Duration = Duration::from_secs(300);
+
+/// 有线网络断开后需要持续的时间，插拔抖动时不应用备用配置
+const WIRED_LOSS_DEBOUNCE: Duration = Duration::from_secs(10);
+
+/// 临时暂停自动切换的时长
+const AUTO_SWITCH_PAUSE: Duration = Duration::from_secs(30 * 60);
+
+/// 历史面板显示的最大条数
+const HISTORY_LIMIT: usize = 50;
+
+/// 编辑面板最多可以撤销的步数
+const EDIT_UNDO_LIMIT: usize = 50;
+
 impl Default for NetworkSwitcherApp {
     fn default() -> Self {
-        let config = AppConfig::load();
-        let services = network::get_network_services();
+        // 配置文件不存在说明是首次运行
+        let is_first_run = !AppConfig::config_path().exists();
+        let mut config = AppConfig::load();
+        // 旧版本保存在配置文件中的密码移到钥匙串
+        if config.move_secrets_to_keychain() {
+            let _ = config.save();
+        }
+        let has_password = config.has_password();
+        // 首次运行时检查当前用户能否修改网络设置，不能时提示如何获取权限
+        // 这里只记在内存中，设置或跳过密码后才保存，否则退出后下次启动不再提示设置密码
+        let mut show_permission_guide = false;
+        if !config.first_run_completed {
+            if network::current_user_is_admin() {
+                config.first_run_completed = true;
+            } else {
+                show_permission_guide = true;
+            }
+        }
+        network::set_apply_retries(config.apply_retries);
+        network::set_dns_ignore_prefixes(&config.dns_ignore_prefixes);
+        network::set_allow_hooks(config.allow_hooks);
+        let dns_ignore_input = config.dns_ignore_prefixes.join(", ");
+        i18n::set_lang(config.lang.unwrap_or_else(i18n::system_lang));
+        let toasts = match &config.recovered_from {
+            Some(path) => vec![Toast::new(ToastLevel::Warning, tf("config_restored_from", &[&path.display()]))],
+            None => Vec::new(),
+        };
+        let services = network::backend().network_services();
         let selected_idx = services
             .iter()
             .position(|s| s == &config.network_service)
             .unwrap_or(0);
 
         let current_config = if !services.is_empty() {
-            Some(network::get_current_config(&services[selected_idx]))
+            network::backend().current_config(&services[selected_idx]).ok()
         } else {
             None
         };
@@ -67,14 +424,53 @@ impl Default for NetworkSwitcherApp {
         Self {
             config,
             current_ssid: None,
+            current_wifi_status: None,
+            current_wifi_device: None,
             current_router_mac: None,
+            current_bssid: None,
+            current_adapter_mac: None,
+            current_vpn_active: false,
+            current_proxy_enabled: false,
+            current_link_speed: None,
+            current_reachability: None,
+            current_public_ip: None,
+            previous_public_ip: None,
+            current_portal_url: None,
+            current_ethernet_connected: None,
+            wired_lost_at: None,
             current_network_config: current_config,
+            service_states: HashMap::new(),
             network_services: services,
             selected_service_idx: selected_idx,
             editing_config: None,
+            edit_history: EditHistory::default(),
+            confirm_overwrite: false,
+            confirm_elevated: None,
+            show_permission_guide,
+            confirm_conflict: None,
+            confirm_reset_dhcp: false,
+            confirm_delete: None,
+            confirm_delete_locked: false,
+            edit_unlocked: false,
+            unlock_password_input: String::new(),
+            unlock_password_error: false,
+            recently_deleted: None,
+            validation_errors: Vec::new(),
             new_dns_input: String::new(),
-            status_message: String::new(),
+            new_search_domain_input: String::new(),
+            new_extra_ssid_input: String::new(),
+            new_tag_input: String::new(),
+            new_profile_name: String::new(),
+            new_preset_name: String::new(),
+            dns_ignore_input,
+            config_filter: String::new(),
+            tag_filter: TagFilter::All,
+            sort_by_recent: false,
+            group_by_router: false,
+            compact_selected: None,
+            toasts,
             show_add_dialog: false,
+            preview_commands: None,
             new_config_name: String::new(),
             new_ssid_input: String::new(),
             bind_router_mac: true,
@@ -82,11 +478,46 @@ impl Default for NetworkSwitcherApp {
             add_service_idx: selected_idx,
             last_check: Instant::now() - std::time::Duration::from_secs(10),
             last_applied_key: None,
+            last_active_schedules: Vec::new(),
             bg_state: Arc::new(Mutex::new(NetworkState::default())),
             is_refreshing: false,
-            is_authenticated: false,
+            refresh_generation: 0,
+            refreshing_service: String::new(),
+            is_refreshing_service: false,
+            apply_state: Arc::new(Mutex::new(ApplyState::default())),
+            is_applying: false,
+            auto_join_state: Arc::new(Mutex::new(None)),
+            is_auto_joining: false,
+            diagnostics_state: Arc::new(Mutex::new(None)),
+            is_dumping_diagnostics: false,
+            last_wifi_scan: Instant::now(),
+            last_auto_join: None,
+            auto_switch_paused_until: None,
+            is_probing: false,
+            last_reachability_check: Instant::now(),
+            is_fetching_public_ip: false,
+            dns_test_results: Arc::new(Mutex::new(Vec::new())),
+            // 没有设置密码时跳过锁屏
+            is_authenticated: !has_password && !is_first_run,
             password_input: String::new(),
             password_error: false,
+            show_password_setup: is_first_run,
+            show_change_password: false,
+            new_password_input: String::new(),
+            confirm_password_input: String::new(),
+            password_setup_error: None,
+            tray: None,
+            status_server: None,
+            sync_server: None,
+            sync_peer: String::new(),
+            sync_peer_token: String::new(),
+            sync_pull: Arc::new(Mutex::new(None)),
+            is_pulling: false,
+            sync_conflicts: None,
+            show_history: false,
+            history_entries: Vec::new(),
+            show_conflicts: false,
+            location_import: None,
         }
     }
 }
@@ -95,7 +526,54 @@ impl NetworkSwitcherApp {
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
         // 加载中文字体
         Self::setup_fonts(&cc.egui_ctx);
-        Self::default()
+        let mut app = Self {
+            tray: Tray::new(&cc.egui_ctx),
+            ..Self::default()
+        };
+        // 在第一帧之前应用主题，避免启动时闪烁
+        apply_theme(&cc.egui_ctx, app.config.theme);
+        app.sync_status_server();
+        app.sync_lan_server();
+        app
+    }
+
+    /// 按 expose_socket 开关启动或停止状态 socket
+    fn sync_status_server(&mut self) {
+        if !self.config.expose_socket {
+            self.status_server = None;
+            return;
+        }
+        if self.status_server.is_some() {
+            return;
+        }
+        let status = AppStatus {
+            last_applied: None,
+            auto_switch: self.config.auto_switch,
+        };
+        match StatusServer::start(status) {
+            Ok(server) => self.status_server = Some(server),
+            Err(e) => self.push_toast(ToastLevel::Error, tf("socket_failed", &[&e])),
+        }
+    }
+
+    /// 按 sync_enabled 开关启动或停止局域网同步服务，未开启时不监听任何端口
+    fn sync_lan_server(&mut self) {
+        if !self.config.sync_enabled {
+            self.sync_server = None;
+            return;
+        }
+        if self.sync_server.as_ref().is_some_and(|server| server.port() == self.config.sync_port) {
+            return;
+        }
+        // 先停止旧端口上的服务
+        self.sync_server = None;
+        if self.config.sync_token.is_empty() {
+            self.config.sync_token = uuid::Uuid::new_v4().simple().to_string();
+        }
+        match SyncServer::start(self.config.sync_port, self.config.sync_token.clone()) {
+            Ok(server) => self.sync_server = Some(server),
+            Err(e) => self.push_toast(ToastLevel::Error, tf("sync_server_failed", &[&e])),
+        }
     }
 
     fn setup_fonts(ctx: &egui::Context) {
@@ -140,52 +618,149 @@ impl NetworkSwitcherApp {
         }
     }
 
-    /// 在后台线程刷新网络状态
-    fn refresh_in_background(&mut self, service: String) {
-        if self.is_refreshing {
+    /// 在后台线程刷新网络状态，force 为 true 时忽略网络标识缓存
+    /// 切换了服务或 force 为 true 时不等待进行中的刷新，之前请求的结果会被丢弃
+    fn refresh_in_background(&mut self, service: String, force: bool) {
+        if self.is_refreshing && self.refreshing_service == service && !force {
             return;
         }
         self.is_refreshing = true;
+        self.refresh_generation += 1;
+        self.refreshing_service = service.clone();
 
         let bg_state = Arc::clone(&self.bg_state);
+        let generation = self.refresh_generation;
 
-        // 先标记正在加载
-        if let Ok(mut state) = bg_state.lock() {
-            state.is_loading = true;
+        // 当前服务和所有配置的目标服务都需要检查状态
+        let mut state_services = vec![service.clone()];
+        for cfg in self.config.configs.values() {
+            for target in cfg.resolve_target_services(&service) {
+                if !state_services.contains(&target) {
+                    state_services.push(target);
+                }
+            }
         }
 
         thread::spawn(move || {
             // 获取网络标识信息
-            let identity = network::get_network_identity();
-            let config = network::get_current_config(&service);
+            let identity = network::get_network_identity(force);
+            let config = network::backend().current_config(&service).ok();
+            let link_speed = network::backend().link_speed(&service);
+            let service_states = state_services
+                .into_iter()
+                .map(|name| {
+                    let state = network::backend().service_state(&name);
+                    (name, state)
+                })
+                .collect();
 
             if let Ok(mut state) = bg_state.lock() {
+                // 更新的请求已经先返回了
+                if state.generation > generation {
+                    return;
+                }
                 state.ssid = if identity.is_wired {
                     identity.service_name.map(|s| format!("[有线] {}", s))
                 } else {
                     identity.ssid
                 };
+                state.wifi_status = Some(identity.wifi_status);
+                state.wifi_device = identity.wifi_device;
                 state.router_mac = identity.router_mac;
-                state.config = Some(config);
-                state.is_loading = false;
+                state.bssid = identity.bssid;
+                state.adapter_mac = identity.adapter_mac;
+                state.vpn_active = identity.vpn_active;
+                state.proxy_enabled = identity.proxy_enabled;
+                state.ethernet_connected = identity.ethernet_service.is_some();
+                state.link_speed = link_speed;
+                state.portal_url = identity.portal_url.filter(|_| identity.captive_portal);
+                state.config = config;
+                state.service_states = service_states;
+                state.generation = generation;
+            }
+        });
+    }
+
+    /// 只读取单个服务的设置、链路速度和状态（不检测 SSID、路由器 MAC 等网络标识），用于切换服务
+    fn refresh_service_config(&mut self, service: String) {
+        // 清空上一个服务的设置，避免读取完成前显示错误的 IP
+        self.current_network_config = None;
+        self.current_link_speed = None;
+        self.is_refreshing_service = true;
+
+        let bg_state = Arc::clone(&self.bg_state);
+        thread::spawn(move || {
+            let config = network::backend().current_config(&service).ok();
+            let link_speed = network::backend().link_speed(&service);
+            let state = network::backend().service_state(&service);
+            if let Ok(mut bg) = bg_state.lock() {
+                bg.service_config = Some(ServiceConfig { service, config, link_speed, state });
             }
         });
     }
 
-    /// 检查后台刷新结果并应用
+    /// 取回单独读取的服务设置
+    fn check_service_config(&mut self) {
+        if !self.is_refreshing_service {
+            return;
+        }
+        let Some(result) = self.bg_state.lock().ok().and_then(|mut state| state.service_config.take()) else {
+            return;
+        };
+        // 之前选中的服务的结果，继续等待当前服务的
+        if result.service != self.network_services[self.selected_service_idx] {
+            return;
+        }
+        self.is_refreshing_service = false;
+        self.current_network_config = result.config;
+        self.current_link_speed = result.link_speed;
+        self.service_states.insert(result.service, result.state);
+    }
+
+    /// 检查后台刷新结果并应用，只接受最近一次请求的结果
     fn check_bg_state(&mut self) -> bool {
         let mut network_changed = false;
-        if let Ok(state) = self.bg_state.lock() {
-            if !state.is_loading && self.is_refreshing {
-                // 检测网络是否变化（SSID 或 MAC）
-                if self.current_ssid != state.ssid || self.current_router_mac != state.router_mac {
-                    network_changed = true;
-                }
-                self.current_ssid = state.ssid.clone();
-                self.current_router_mac = state.router_mac.clone();
+        if let Ok(state) = self.bg_state.lock()
+            && state.generation == self.refresh_generation
+            && self.is_refreshing
+        {
+            // 检测网络是否变化（SSID 或 MAC）
+            if self.current_ssid != state.ssid
+                || self.current_router_mac != state.router_mac
+                || self.current_bssid != state.bssid
+                || self.current_adapter_mac != state.adapter_mac
+            {
+                debug!(
+                    "网络标识变化: SSID {:?} -> {:?}，路由器 MAC {:?} -> {:?}，BSSID {:?} -> {:?}，网卡 MAC {:?} -> {:?}",
+                    self.current_ssid, state.ssid, self.current_router_mac, state.router_mac, self.current_bssid, state.bssid,
+                    self.current_adapter_mac, state.adapter_mac
+                );
+                network_changed = true;
+            }
+            self.current_ssid = state.ssid.clone();
+            self.current_wifi_status = state.wifi_status.clone();
+            self.current_wifi_device = state.wifi_device.clone();
+            self.current_router_mac = state.router_mac.clone();
+            self.current_bssid = state.bssid.clone();
+            self.current_adapter_mac = state.adapter_mac.clone();
+            self.current_vpn_active = state.vpn_active;
+            self.current_proxy_enabled = state.proxy_enabled;
+            self.current_portal_url = state.portal_url.clone();
+            // 有线网络从已连接变为断开时开始计时（同时连着 Wi-Fi 也一样），期间重新连上有线则取消
+            if self.current_ethernet_connected == Some(true) && !state.ethernet_connected {
+                info!("有线网络已断开");
+                self.wired_lost_at = Some(Instant::now());
+            } else if state.ethernet_connected {
+                self.wired_lost_at = None;
+            }
+            self.current_ethernet_connected = Some(state.ethernet_connected);
+            // 刷新期间切换了服务时，新服务的设置由 refresh_service_config 读取
+            if self.refreshing_service == self.network_services[self.selected_service_idx] {
+                self.current_link_speed = state.link_speed.clone();
                 self.current_network_config = state.config.clone();
-                self.is_refreshing = false;
             }
+            self.service_states = state.service_states.clone();
+            self.is_refreshing = false;
         }
         network_changed
     }
@@ -193,51 +768,305 @@ impl NetworkSwitcherApp {
     /// 当网络变化时自动应用配置
     fn try_auto_apply(&mut self) {
         if !self.config.auto_switch {
+            debug!("自动切换已关闭，不自动应用");
+            return;
+        }
+        if self.auto_switch_paused() {
+            debug!("自动切换已临时暂停，不自动应用");
             return;
         }
 
         // 获取当前网络信息
         let ssid = match &self.current_ssid {
             Some(s) => s.clone(),
-            None => return,
+            None => {
+                debug!("还没有获取到当前网络，不自动应用");
+                return;
+            }
         };
         let router_mac = self.current_router_mac.as_deref();
 
         // 查找自动应用的配置
-        if let Some(cfg) = self.config.find_auto_apply_config(&ssid, router_mac).cloned() {
+        let now = chrono::Local::now().naive_local();
+        let bssid = self.current_bssid.as_deref();
+        let adapter_mac = self.current_adapter_mac.as_deref();
+        if let Some(cfg) = self.config.find_auto_apply_config(&ssid, router_mac, bssid, adapter_mac, now).cloned() {
             let key = cfg.config_key();
             // 如果已经应用过相同配置，跳过
             if self.last_applied_key.as_ref() == Some(&key) {
+                debug!("配置 {} 已经应用过，跳过", cfg.name);
                 return;
             }
-            self.apply_config_internal(&cfg);
+            info!("网络 {} 匹配配置 {}，自动应用", ssid, cfg.name);
+            self.apply_config_internal(&cfg, true);
         } else {
+            debug!(
+                "没有匹配网络 {} (路由器 MAC {:?}, BSSID {:?}, 网卡 MAC {:?}) 的自动应用配置",
+                ssid, router_mac, bssid, adapter_mac
+            );
             // 没有匹配的自动配置，清除上次应用记录
             self.last_applied_key = None;
         }
     }
 
-    /// 内部应用配置
-    fn apply_config_internal(&mut self, cfg: &NetworkConfig) {
-        let target_service = cfg.target_service
-            .as_ref()
-            .unwrap_or(&self.network_services[self.selected_service_idx])
-            .clone();
+    /// 有线网络持续断开 WIRED_LOSS_DEBOUNCE 后应用备用配置
+    /// 当前网络有匹配的自动应用配置时以它为准，不应用备用配置
+    fn try_wired_loss_fallback(&mut self) {
+        let Some(lost_at) = self.wired_lost_at else {
+            return;
+        };
+        if lost_at.elapsed() < WIRED_LOSS_DEBOUNCE || self.is_applying {
+            return;
+        }
+        self.wired_lost_at = None;
+        if !self.config.auto_switch || self.auto_switch_paused() {
+            debug!("自动切换已关闭或暂停，不应用有线断开的备用配置");
+            return;
+        }
 
-        match network::apply_config(&target_service, cfg) {
-            Ok(_) => {
-                self.status_message = format!(
-                    "✅ 已应用配置: {} -> {}",
-                    cfg.name, target_service
-                );
-                self.last_applied_key = Some(cfg.config_key());
-                // 刷新当前配置显示
-                self.refresh_in_background(target_service);
+        let now = chrono::Local::now().naive_local();
+        let ssid = self.current_ssid.clone().unwrap_or_default();
+        let router_mac = self.current_router_mac.as_deref();
+        let bssid = self.current_bssid.as_deref();
+        let adapter_mac = self.current_adapter_mac.as_deref();
+        if let Some(cfg) = self.config.find_auto_apply_config(&ssid, router_mac, bssid, adapter_mac, now) {
+            debug!("有线网络断开，当前网络匹配自动应用配置 {}，不应用备用配置", cfg.name);
+            return;
+        }
+        let Some(cfg) = self.config.wired_loss_fallback(now).cloned() else {
+            return;
+        };
+        if self.last_applied_key.as_ref() == Some(&cfg.config_key()) {
+            debug!("备用配置 {} 已经应用过，跳过", cfg.name);
+            return;
+        }
+        info!("有线网络断开，应用备用配置 {}", cfg.name);
+        self.apply_config_internal(&cfg, true);
+    }
+
+    /// 内部应用配置，automatic 表示由自动切换触发
+    /// 已停用或不存在的目标服务会被跳过
+    fn apply_config_internal(&mut self, cfg: &NetworkConfig, automatic: bool) {
+        let (services, unavailable): (Vec<String>, Vec<String>) = cfg
+            .resolve_target_services(&self.network_services[self.selected_service_idx])
+            .into_iter()
+            .partition(|service| self.service_state(service).is_none_or(ServiceState::can_apply));
+
+        if services.is_empty() {
+            let states: Vec<String> = unavailable
+                .iter()
+                .map(|service| format!("{} {}", service, self.service_state(service).map_or("", ServiceState::label)))
+                .collect();
+            warn!("无法应用 {}: 目标服务不可用（{}）", cfg.name, states.join("、"));
+            self.push_toast(ToastLevel::Error, tf("targets_unavailable", &[&cfg.name, &states.join("、")]));
+            return;
+        }
+        self.apply_to_services(cfg, services, automatic);
+        if !unavailable.is_empty() {
+            debug!("应用 {} 时跳过不可用的服务: {}", cfg.name, unavailable.join("、"));
+            self.push_toast(ToastLevel::Warning, tf("skipped_unavailable", &[&unavailable.join("、")]));
+        }
+    }
+
+    /// 服务的状态，还没有检查过时为 None
+    fn service_state(&self, service: &str) -> Option<ServiceState> {
+        self.service_states.get(service).copied()
+    }
+
+    /// 在后台线程把配置应用到指定的服务，每个步骤的进度写入 apply_state
+    /// 同一时间只应用一个配置，结果由 check_apply_state 汇总
+    fn apply_to_services(&mut self, cfg: &NetworkConfig, services: Vec<String>, automatic: bool) {
+        if self.is_applying {
+            debug!("正在应用其他配置，{} 暂不应用", cfg.name);
+            if !automatic {
+                self.push_toast(ToastLevel::Info, t("apply_busy"));
             }
-            Err(e) => {
-                self.status_message = format!("❌ 应用失败: {}", e);
+            return;
+        }
+        self.is_applying = true;
+
+        let apply_state = Arc::clone(&self.apply_state);
+        let cfg = cfg.clone();
+        let trigger = if automatic { "auto" } else { "manual" };
+
+        thread::spawn(move || {
+            // 企业网络的描述文件需要管理员授权或用户确认，只在手动应用时安装
+            let profile = cfg.profile_path.as_deref().filter(|_| !automatic).map(|path| {
+                if let Ok(mut state) = apply_state.lock() {
+                    state.progress = Some((cfg.name.clone(), t("installing_profile").to_string()));
+                }
+                network::install_profile(path)
+            });
+
+            // 逐个服务应用，汇总成功的服务和每个服务的问题
+            let mut outcome = ApplyOutcome {
+                config: cfg,
+                automatic,
+                applied: Vec::new(),
+                problems: Vec::new(),
+                denied: Vec::new(),
+                conflicted: Vec::new(),
+                conflict_detail: String::new(),
+                profile,
+            };
+            let cfg = &outcome.config;
+            for service in services {
+                let report = |step: &str| {
+                    if let Ok(mut state) = apply_state.lock() {
+                        state.progress = Some((service.clone(), step.to_string()));
+                    }
+                };
+                let result = network::apply_config_with_rollback(&service, cfg, &report);
+                network::log_apply(cfg, trigger, &service, &result);
+                match result {
+                    Ok(_) => {
+                        report(t("verifying"));
+                        if let Err(fields) = network::verify_config(&service, cfg) {
+                            outcome.problems.push(tf("not_effective", &[&service, &fields.join("、")]));
+                        }
+                        if cfg.post_apply_command.is_some() {
+                            report(t("post_apply_running"));
+                        }
+                        if let Some(Err(e)) = network::run_post_apply_hook(&service, cfg) {
+                            outcome.problems.push(tf("post_apply_failed", &[&service, &e]));
+                        }
+                        outcome.applied.push(service);
+                    }
+                    Err(e) => {
+                        outcome.problems.push(tf("apply_failed", &[&error_icon(&e), &service, &e]));
+                        match e.root() {
+                            NetworkError::PermissionDenied { .. } => outcome.denied.push(service),
+                            NetworkError::AddressInUse { .. } => {
+                                outcome.conflict_detail = e.to_string();
+                                outcome.conflicted.push(service);
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+
+            if let Ok(mut state) = apply_state.lock() {
+                state.progress = None;
+                state.outcome = Some(outcome);
+            }
+        });
+    }
+
+    /// 检查后台应用是否完成，完成后汇总结果并处理需要确认的失败
+    fn check_apply_state(&mut self) {
+        if !self.is_applying {
+            return;
+        }
+        let Some(outcome) = self.apply_state.lock().ok().and_then(|mut state| state.outcome.take()) else {
+            return;
+        };
+        self.is_applying = false;
+
+        let cfg = outcome.config;
+        for problem in &outcome.problems {
+            warn!("应用 {}: {}", cfg.name, problem);
+        }
+        match outcome.profile {
+            Some(Ok(install)) => {
+                let level = match install {
+                    ProfileInstall::Installed => ToastLevel::Success,
+                    ProfileInstall::PendingApproval => ToastLevel::Info,
+                    ProfileInstall::Declined => ToastLevel::Warning,
+                };
+                self.push_toast(level, install.label());
+            }
+            Some(Err(e)) => self.push_toast(ToastLevel::Error, tf("install_profile_failed", &[&error_icon(&e), &e])),
+            None => {}
+        }
+        if !outcome.denied.is_empty() {
+            self.confirm_elevated = Some((cfg.clone(), outcome.denied, outcome.automatic));
+        }
+        if !outcome.conflicted.is_empty() {
+            self.confirm_conflict = Some((cfg.clone(), outcome.conflicted, outcome.automatic, outcome.conflict_detail));
+        }
+        self.finish_apply(&cfg, outcome.automatic, outcome.applied, outcome.problems);
+    }
+
+    /// 以管理员身份重新应用之前因权限不足失败的服务
+    fn apply_elevated(&mut self, cfg: &NetworkConfig, services: Vec<String>, automatic: bool) {
+        let trigger = if automatic { "auto" } else { "manual" };
+        let mut applied = Vec::new();
+        let mut problems = Vec::new();
+        for service in services {
+            let result = network::apply_config_elevated(&service, cfg);
+            network::log_apply(cfg, trigger, &service, &result);
+            match result {
+                Ok(_) => {
+                    // 以管理员身份应用时无法自动回滚，检测失败只提示
+                    if cfg.probe_after_apply
+                        && let Err(e) = network::probe_connectivity(&service)
+                    {
+                        problems.push(tf("probe_failed", &[&service, &e]));
+                    }
+                    if let Some(Err(e)) = network::run_post_apply_hook(&service, cfg) {
+                        problems.push(tf("post_apply_failed", &[&service, &e]));
+                    }
+                    applied.push(service);
+                }
+                Err(e) => problems.push(tf("apply_failed", &[&error_icon(&e), &service, &e])),
             }
         }
+        self.finish_apply(cfg, automatic, applied, problems);
+    }
+
+    /// 汇总应用结果：更新状态栏、记录上次应用的配置并发送通知
+    fn finish_apply(&mut self, cfg: &NetworkConfig, automatic: bool, mut applied: Vec<String>, problems: Vec<String>) {
+        if self.show_history {
+            self.history_entries = network::read_apply_log(HISTORY_LIMIT);
+        }
+
+        if problems.is_empty() && cfg.probe_after_apply {
+            self.push_toast(ToastLevel::Success, tf("applied_probed", &[&cfg.name, &applied.join(", ")]));
+        } else if problems.is_empty() {
+            self.push_toast(ToastLevel::Success, tf("applied", &[&cfg.name, &applied.join(", ")]));
+        } else {
+            // 部分服务应用成功时只算警告
+            let level = if applied.is_empty() { ToastLevel::Error } else { ToastLevel::Warning };
+            self.push_toast(level, problems.join("；"));
+        }
+
+        if applied.is_empty() {
+            return;
+        }
+        // 应用后公网 IP 可能变化（如切换了出口网关）
+        self.start_public_ip_fetch();
+        self.last_applied_key = Some(cfg.config_key());
+        if let Some(stored) = self.config.configs.get_mut(&cfg.config_key()) {
+            stored.last_applied = Some(chrono::Local::now().timestamp());
+            let _ = self.config.save();
+        }
+        if let Some(server) = &self.status_server {
+            server.update(|status| status.last_applied = Some(cfg.name.clone()));
+        }
+        if self.config.notifications_enabled
+            && (automatic || self.config.notify_on_manual_apply)
+        {
+            notify::send_notification(
+                "Network Switcher",
+                &tf("applied_notification", &[&cfg.name, &applied.join(", ")]),
+            );
+        }
+        // 刷新当前配置显示
+        self.refresh_in_background(applied.swap_remove(0), true);
+    }
+
+    /// 先连接配置对应的 WiFi，成功后再应用配置
+    fn join_and_apply(&mut self, cfg: &NetworkConfig) {
+        let target_service = cfg.target_service
+            .clone()
+            .unwrap_or_else(|| self.network_services[self.selected_service_idx].clone());
+        let password = cfg.resolve_wifi_password();
+
+        match network::backend().join_wifi(&target_service, &cfg.ssid, password.as_deref()) {
+            Ok(()) => self.apply_config_internal(cfg, false),
+            Err(e) => self.push_toast(ToastLevel::Error, tf("join_failed", &[&cfg.ssid, &e.trim()])),
+        }
     }
 
     /// 检查网络变化并自动应用配置
@@ -247,311 +1076,2567 @@ impl NetworkSwitcherApp {
         // 检查后台状态更新，如果 SSID 变化则立即尝试应用配置
         let ssid_changed = self.check_bg_state();
         if ssid_changed {
+            debug!("网络变化，尝试自动应用");
             self.try_auto_apply();
         }
 
+        self.try_wired_loss_fallback();
+
+        // 网络变化或刚开启时查询公网 IP
+        if ssid_changed || self.current_public_ip.is_none() {
+            self.start_public_ip_fetch();
+        }
+
+        // 暂停到期后恢复自动切换
+        if self.auto_switch_paused_until.is_some() && !self.auto_switch_paused() {
+            self.resume_auto_switch();
+        }
+
+        // 联网检测有自己的间隔
+        self.start_reachability_check();
+
         // 每5秒检查一次
         if self.last_check.elapsed() < Duration::from_secs(5) {
             return;
         }
         self.last_check = Instant::now();
 
+        // 定时规则进入或离开生效时间段时也触发自动应用
+        let active_schedules = self.active_schedules();
+        if active_schedules != self.last_active_schedules {
+            debug!("生效的定时配置变化: {:?} -> {:?}，尝试自动应用", self.last_active_schedules, active_schedules);
+            self.last_active_schedules = active_schedules;
+            self.try_auto_apply();
+        }
+
         // 在后台线程更新网络信息
         let service = self.network_services[self.selected_service_idx].clone();
-        self.refresh_in_background(service);
+        self.refresh_in_background(service, false);
+
+        // 信号范围内有优先级更高的网络时自动连接
+        self.start_auto_join();
 
         // 请求重绘以更新状态
         ctx.request_repaint_after(Duration::from_millis(500));
     }
 
-    /// 渲染密码输入界面
-    fn render_password_screen(&mut self, ctx: &egui::Context) {
-        egui::CentralPanel::default().show(ctx, |ui| {
-            ui.vertical_centered(|ui| {
-                ui.add_space(80.0);
-                ui.heading("🔐 Network Switcher");
-                ui.add_space(20.0);
-                ui.label("请输入密码 / Enter Password");
-                ui.add_space(10.0);
-
-                let response = ui.add(
-                    egui::TextEdit::singleline(&mut self.password_input)
-                        .password(true)
-                        .hint_text("密码 / Password")
-                        .desired_width(200.0)
-                );
-
-                // 回车提交
-                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-                    self.verify_password();
-                }
-
-                ui.add_space(10.0);
+    /// 在后台扫描 WiFi，连接范围内优先级高于当前网络配置的 auto_join 配置
+    fn start_auto_join(&mut self) {
+        if !self.config.auto_switch || self.auto_switch_paused() || self.is_auto_joining || self.is_applying
+            || self.last_wifi_scan.elapsed() < WIFI_SCAN_INTERVAL
+            || self.last_auto_join.is_some_and(|joined_at| joined_at.elapsed() < AUTO_JOIN_COOLDOWN)
+        {
+            return;
+        }
 
-                if ui.button("🔓 解锁 / Unlock").clicked() {
-                    self.verify_password();
-                }
+        let now = chrono::Local::now().naive_local();
+        let ssid = self.current_ssid.clone().unwrap_or_default();
+        let router_mac = self.current_router_mac.as_deref();
+        let bssid = self.current_bssid.as_deref();
+        let adapter_mac = self.current_adapter_mac.as_deref();
+        let current_priority = self.config
+            .find_auto_apply_config(&ssid, router_mac, bssid, adapter_mac, now)
+            .map(|cfg| cfg.priority);
+        let candidates: Vec<NetworkConfig> = self.config
+            .auto_join_candidates(current_priority, now)
+            .into_iter()
+            .filter(|cfg| !cfg.matches_network(&ssid, router_mac, bssid, adapter_mac))
+            .cloned()
+            .collect();
+        if candidates.is_empty() {
+            return;
+        }
 
-                if self.password_error {
-                    ui.add_space(10.0);
-                    ui.colored_label(egui::Color32::RED, "❌ 密码错误 / Wrong Password");
-                }
+        self.last_wifi_scan = Instant::now();
+        self.is_auto_joining = true;
+        let fallback_service = self.network_services[self.selected_service_idx].clone();
+        let auto_join_state = Arc::clone(&self.auto_join_state);
+        thread::spawn(move || {
+            let in_range = network::scan_wifi();
+            // 绑定了 BSSID 的配置只连接对应的接入点（扫描结果没有 BSSID 时不限制）
+            let target = candidates.into_iter().find(|cfg| {
+                in_range.iter().any(|network| {
+                    network.ssid == cfg.ssid
+                        && cfg.bssid.as_ref().is_none_or(|b| network.bssid.as_ref().is_none_or(|n| n.eq_ignore_ascii_case(b)))
+                })
+            });
+            let outcome = target.map(|cfg| {
+                let service = cfg.target_service.clone().unwrap_or(fallback_service);
+                let password = cfg.resolve_wifi_password();
+                let result = network::backend().join_wifi(&service, &cfg.ssid, password.as_deref());
+                (cfg, result)
             });
+            if let Ok(mut state) = auto_join_state.lock() {
+                *state = Some(outcome);
+            }
         });
     }
 
-    /// 验证密码
-    fn verify_password(&mut self) {
-        const PASSWORD: &str = "Legna";
-        if self.password_input == PASSWORD {
-            self.is_authenticated = true;
-            self.password_error = false;
-            // 密码验证成功后立即刷新网络状态
-            let service = self.network_services[self.selected_service_idx].clone();
-            self.refresh_in_background(service);
-        } else {
-            self.password_error = true;
-            self.password_input.clear();
+    /// 自动连接完成后应用对应的配置
+    fn check_auto_join(&mut self) {
+        if !self.is_auto_joining {
+            return;
         }
-    }
-}
+        let Some(outcome) = self.auto_join_state.lock().ok().and_then(|mut state| state.take()) else {
+            return;
+        };
+        self.is_auto_joining = false;
 
-impl eframe::App for NetworkSwitcherApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // 如果未验证密码，显示密码输入界面
-        if !self.is_authenticated {
-            self.render_password_screen(ctx);
+        let Some((cfg, result)) = outcome else {
             return;
+        };
+        self.last_auto_join = Some(Instant::now());
+        match result {
+            Ok(()) => self.apply_config_internal(&cfg, true),
+            Err(e) => self.push_toast(ToastLevel::Error, tf("auto_join_failed", &[&cfg.ssid, &e.trim()])),
         }
+    }
+
+    /// 选择保存位置后在后台生成诊断报告（需要运行多个系统命令）
+    fn dump_diagnostics(&mut self) {
+        if self.is_dumping_diagnostics {
+            return;
+        }
+        let file_name = format!("network-switcher-diagnostics-{}.txt", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Text", &["txt"])
+            .set_file_name(file_name)
+            .save_file()
+        else {
+            return;
+        };
+
+        self.is_dumping_diagnostics = true;
+        let diagnostics_state = Arc::clone(&self.diagnostics_state);
+        thread::spawn(move || {
+            let report = network::diagnostics_report();
+            let result = fs::write(&path, report).map(|_| path).map_err(|e| e.to_string());
+            if let Ok(mut state) = diagnostics_state.lock() {
+                *state = Some(result);
+            }
+        });
+    }
+
+    /// 取回后台生成诊断报告的结果
+    fn check_diagnostics(&mut self) {
+        if !self.is_dumping_diagnostics {
+            return;
+        }
+        let Some(result) = self.diagnostics_state.lock().ok().and_then(|mut state| state.take()) else {
+            return;
+        };
+        self.is_dumping_diagnostics = false;
+        match result {
+            Ok(path) => self.push_toast(ToastLevel::Success, tf("diagnostics_saved", &[&path.display()])),
+            Err(e) => self.push_toast(ToastLevel::Error, tf("diagnostics_failed", &[&e])),
+        }
+    }
+
+    /// 显示一条提示消息，超出数量上限时移除最早的
+    fn push_toast(&mut self, level: ToastLevel, text: impl Into<String>) {
+        self.toasts.push(Toast::new(level, text));
+        if self.toasts.len() > TOAST_LIMIT {
+            self.toasts.remove(0);
+        }
+    }
+
+    /// 自动切换是否处于临时暂停中
+    fn auto_switch_paused(&self) -> bool {
+        self.auto_switch_paused_until.is_some_and(|until| Instant::now() < until)
+    }
+
+    /// 临时暂停自动切换，不修改保存的 auto_switch 设置
+    fn pause_auto_switch(&mut self) {
+        info!("临时暂停自动切换 {} 分钟", AUTO_SWITCH_PAUSE.as_secs() / 60);
+        self.auto_switch_paused_until = Some(Instant::now() + AUTO_SWITCH_PAUSE);
+    }
+
+    /// 结束临时暂停，并按当前网络重新自动应用（暂停期间可能手动改过设置）
+    fn resume_auto_switch(&mut self) {
+        info!("恢复自动切换");
+        self.auto_switch_paused_until = None;
+        self.last_applied_key = None;
+        self.try_auto_apply();
+    }
+
+    /// 按设置的间隔在后台检测联网状态，间隔为 0 时不检测
+    fn start_reachability_check(&mut self) {
+        let interval = self.config.reachability_interval;
+        if interval == 0 {
+            self.current_reachability = None;
+            return;
+        }
+        if self.is_probing || self.last_reachability_check.elapsed() < Duration::from_secs(interval) {
+            return;
+        }
+
+        self.last_reachability_check = Instant::now();
+        self.is_probing = true;
+        let router = self.current_network_config.as_ref().and_then(|cfg| cfg.router.clone());
+        let bg_state = Arc::clone(&self.bg_state);
+        thread::spawn(move || {
+            let reachability = network::check_reachability(router.as_deref());
+            if let Ok(mut state) = bg_state.lock() {
+                state.reachability = Some(reachability);
+            }
+        });
+    }
+
+    /// 取回后台联网检测的结果
+    fn check_reachability(&mut self) {
+        if !self.is_probing {
+            return;
+        }
+        let Some(reachability) = self.bg_state.lock().ok().and_then(|mut state| state.reachability.take()) else {
+            return;
+        };
+        self.is_probing = false;
+        // 检测期间关闭了检测
+        if self.config.reachability_interval > 0 {
+            self.current_reachability = Some(reachability);
+        }
+    }
+
+    /// 在后台查询公网 IP，未开启时清除之前的结果
+    fn start_public_ip_fetch(&mut self) {
+        if !self.config.public_ip_enabled {
+            self.current_public_ip = None;
+            self.previous_public_ip = None;
+            return;
+        }
+        if self.is_fetching_public_ip {
+            return;
+        }
+
+        self.is_fetching_public_ip = true;
+        let url = self.config.public_ip_url.clone();
+        let bg_state = Arc::clone(&self.bg_state);
+        thread::spawn(move || {
+            let public_ip = network::fetch_public_ip(&url);
+            if let Ok(mut state) = bg_state.lock() {
+                state.public_ip = Some(public_ip);
+            }
+        });
+    }
+
+    /// 取回后台查询的公网 IP，与之前不同时记录之前的 IP
+    fn check_public_ip(&mut self) {
+        if !self.is_fetching_public_ip {
+            return;
+        }
+        let Some(public_ip) = self.bg_state.lock().ok().and_then(|mut state| state.public_ip.take()) else {
+            return;
+        };
+        self.is_fetching_public_ip = false;
+        // 查询期间关闭了显示
+        if !self.config.public_ip_enabled {
+            return;
+        }
+        match (&self.current_public_ip, &public_ip) {
+            (Some(Ok(old)), Ok(new)) if old != new => {
+                info!("公网 IP 变化: {} -> {}", old, new);
+                self.previous_public_ip = Some(old.clone());
+            }
+            (_, Err(e)) => debug!("查询公网 IP 失败: {}", e),
+            _ => {}
+        }
+        self.current_public_ip = Some(public_ip);
+    }
+
+    /// 当前处于生效时间段内的定时配置
+    fn active_schedules(&self) -> Vec<String> {
+        let now = chrono::Local::now().naive_local();
+        let mut keys: Vec<String> = self.config.configs
+            .values()
+            .filter(|cfg| cfg.auto_apply && cfg.schedule.as_ref().is_some_and(|s| s.is_active(now)))
+            .map(|cfg| cfg.config_key())
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    /// 同步菜单栏菜单并处理菜单栏操作
+    fn handle_tray(&mut self) {
+        let Some(tray) = self.tray.as_mut() else {
+            return;
+        };
+
+        let header = match (&self.current_ssid, &self.current_router_mac) {
+            (Some(ssid), Some(mac)) => format!("{} ({})", ssid, mac),
+            (Some(ssid), None) => ssid.clone(),
+            _ => t("not_connected").to_string(),
+        };
+        let mut configs: Vec<(String, String)> = self
+            .config
+            .configs
+            .values()
+            .map(|c| (c.config_key(), c.name.clone()))
+            .collect();
+        configs.sort_by(|a, b| a.1.cmp(&b.1));
+        tray.update_menu(&header, &configs);
+
+        for action in tray.poll() {
+            match action {
+                TrayAction::Show => {
+                    let service = self.network_services[self.selected_service_idx].clone();
+                    self.refresh_in_background(service, true);
+                }
+                TrayAction::Apply(key) => {
+                    if let Some(cfg) = self.config.configs.get(&key).cloned() {
+                        self.apply_config_internal(&cfg, false);
+                    }
+                }
+            }
+        }
+    }
+
+    /// 键盘快捷键：Cmd+R 刷新，Cmd+N 添加配置，Esc 关闭对话框或取消编辑
+    /// 文本框有焦点时不处理，以免输入内容时误触发
+    fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+
+        let (refresh, add, escape, undo, redo) = ctx.input(|i| {
+            let z = i.modifiers.command && i.key_pressed(egui::Key::Z);
+            (
+                i.modifiers.command && i.key_pressed(egui::Key::R),
+                i.modifiers.command && i.key_pressed(egui::Key::N),
+                i.key_pressed(egui::Key::Escape),
+                z && !i.modifiers.shift,
+                z && i.modifiers.shift,
+            )
+        });
+
+        if refresh {
+            self.refresh_now();
+        }
+        if add {
+            self.open_add_dialog();
+        }
+        if escape {
+            // 先关闭弹出的窗口，没有窗口时再取消编辑
+            if self.confirm_delete.is_some() {
+                self.confirm_delete = None;
+            } else if self.show_add_dialog || self.preview_commands.is_some() || self.show_history {
+                self.show_add_dialog = false;
+                self.preview_commands = None;
+                self.show_history = false;
+            } else if self.editing_config.is_some() {
+                self.cancel_editing();
+            }
+        }
+        // 文本框有焦点时由文本框自己处理撤销
+        if undo {
+            self.undo_edit();
+        }
+        if redo {
+            self.redo_edit();
+        }
+    }
+
+    /// 撤销编辑面板中的上一批修改
+    fn undo_edit(&mut self) {
+        if let Some(editing) = self.editing_config.as_mut()
+            && self.edit_history.undo(editing)
+        {
+            self.validation_errors.clear();
+        }
+    }
+
+    /// 重做被撤销的修改
+    fn redo_edit(&mut self) {
+        if let Some(editing) = self.editing_config.as_mut()
+            && self.edit_history.redo(editing)
+        {
+            self.validation_errors.clear();
+        }
+    }
+
+    /// 立即刷新当前服务的网络状态（忽略缓存）
+    fn refresh_now(&mut self) {
+        if self.is_refreshing {
+            return;
+        }
+        let service = self.network_services[self.selected_service_idx].clone();
+        self.refresh_in_background(service, true);
+        self.start_public_ip_fetch();
+    }
+
+    /// 开启自动切换且有菜单栏图标时，关闭窗口改为隐藏到菜单栏
+    fn hide_to_tray_on_close(&mut self, ctx: &egui::Context) {
+        if self.tray.is_none() || !self.config.auto_switch {
+            return;
+        }
+        if ctx.input(|i| i.viewport().close_requested()) {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            ctx.send_viewport_cmd(egui::ViewportCommand::Visible(false));
+        }
+    }
+
+    /// 记录当前窗口大小和位置，退出时写入配置
+    fn track_window_geometry(&mut self, ctx: &egui::Context) {
+        let (inner, outer) = ctx.input(|i| (i.viewport().inner_rect, i.viewport().outer_rect));
+        if let Some(rect) = inner {
+            self.config.window_width = rect.width();
+            self.config.window_height = rect.height();
+        }
+        if let Some(rect) = outer {
+            self.config.window_x = Some(rect.min.x);
+            self.config.window_y = Some(rect.min.y);
+        }
+    }
+
+    /// 渲染密码输入界面
+    fn render_password_screen(&mut self, ctx: &egui::Context) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(80.0);
+                ui.heading("🔐 Network Switcher");
+                ui.add_space(20.0);
+                ui.label(t("enter_password"));
+                ui.add_space(10.0);
+
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.password_input)
+                        .password(true)
+                        .hint_text(t("password"))
+                        .desired_width(200.0)
+                );
+
+                // 回车提交
+                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    self.verify_password();
+                }
+
+                ui.add_space(10.0);
+
+                if ui.button(t("unlock")).clicked() {
+                    self.verify_password();
+                }
+
+                if self.password_error {
+                    ui.add_space(10.0);
+                    ui.colored_label(egui::Color32::RED, t("wrong_password"));
+                }
+            });
+        });
+    }
+
+    /// 渲染首次运行的设置密码界面
+    fn render_password_setup_screen(&mut self, ctx: &egui::Context) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(80.0);
+                ui.heading("🔐 Network Switcher");
+                ui.add_space(20.0);
+                ui.label(t("set_password_title"));
+                ui.add_space(10.0);
+
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.new_password_input)
+                        .password(true)
+                        .hint_text(t("new_password"))
+                        .desired_width(200.0)
+                );
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.confirm_password_input)
+                        .password(true)
+                        .hint_text(t("confirm_password"))
+                        .desired_width(200.0)
+                );
+
+                ui.add_space(10.0);
+
+                if ui.button(t("set_password")).clicked() && self.submit_new_password() {
+                    self.show_password_setup = false;
+                    self.unlock();
+                }
+
+                if ui.button(t("skip")).clicked() {
+                    // 保存一次配置，下次启动不再视为首次运行
+                    let _ = self.config.save();
+                    self.show_password_setup = false;
+                    self.unlock();
+                }
+
+                if let Some(ref err) = self.password_setup_error {
+                    ui.add_space(10.0);
+                    ui.colored_label(egui::Color32::RED, format!("❌ {}", err));
+                }
+            });
+        });
+    }
+
+    /// 渲染修改密码窗口
+    fn render_change_password_window(&mut self, ctx: &egui::Context) {
+        if !self.show_change_password {
+            return;
+        }
+
+        let mut should_close = false;
+        egui::Window::new(t("change_password"))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(t("new_password_label"));
+                    ui.add(egui::TextEdit::singleline(&mut self.new_password_input).password(true));
+                });
+                ui.horizontal(|ui| {
+                    ui.label(t("confirm_password_label"));
+                    ui.add(egui::TextEdit::singleline(&mut self.confirm_password_input).password(true));
+                });
+                ui.label(t("password_clear_hint"));
+
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    if ui.button(t("save")).clicked() && self.submit_new_password() {
+                        if self.config.has_password() {
+                            self.push_toast(ToastLevel::Success, t("password_changed"));
+                        } else {
+                            self.push_toast(ToastLevel::Success, t("password_removed"));
+                        }
+                        should_close = true;
+                    }
+                    if ui.button(t("cancel")).clicked() {
+                        should_close = true;
+                    }
+                });
+
+                if let Some(ref err) = self.password_setup_error {
+                    ui.colored_label(egui::Color32::RED, format!("❌ {}", err));
+                }
+            });
+
+        if should_close {
+            self.show_change_password = false;
+            self.new_password_input.clear();
+            self.confirm_password_input.clear();
+            self.password_setup_error = None;
+        }
+    }
+
+    /// 校验两次输入一致后保存新密码，成功返回 true
+    fn submit_new_password(&mut self) -> bool {
+        if self.new_password_input != self.confirm_password_input {
+            self.password_setup_error = Some(t("password_mismatch").to_string());
+            return false;
+        }
+
+        let result = self
+            .config
+            .set_password(&self.new_password_input)
+            .and_then(|_| self.config.save());
+        match result {
+            Ok(_) => {
+                self.new_password_input.clear();
+                self.confirm_password_input.clear();
+                self.password_setup_error = None;
+                true
+            }
+            Err(e) => {
+                self.password_setup_error = Some(e);
+                false
+            }
+        }
+    }
+
+    /// 解锁并立即刷新网络状态
+    fn unlock(&mut self) {
+        self.is_authenticated = true;
+        let service = self.network_services[self.selected_service_idx].clone();
+        self.refresh_in_background(service, false);
+    }
+
+    /// 验证密码
+    fn verify_password(&mut self) {
+        if self.config.verify_password(&self.password_input) {
+            self.password_error = false;
+            // 密码验证成功后立即刷新网络状态
+            self.unlock();
+        } else {
+            self.password_error = true;
+            self.password_input.clear();
+        }
+    }
+}
+
+impl eframe::App for NetworkSwitcherApp {
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        info!("程序退出");
+        // 退出时保存窗口大小和位置（首次运行未完成设置时不写入，下次仍提示设置密码）
+        if !self.show_password_setup
+            && let Err(e) = self.config.save()
+        {
+            warn!("退出时保存配置失败: {}", e);
+        }
+        // 停止状态 socket 并删除 socket 文件
+        self.status_server = None;
+        self.sync_server = None;
+        // 删除本次运行生成的临时文件
+        AppConfig::remove_temp_files();
+        network::backend().cleanup_temp_files();
+        log::logger().flush();
+    }
+
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.track_window_geometry(ctx);
+
+        // 首次运行，显示设置密码界面
+        if self.show_password_setup {
+            self.render_password_setup_screen(ctx);
+            return;
+        }
+
+        // 如果未验证密码，显示密码输入界面
+        if !self.is_authenticated {
+            self.render_password_screen(ctx);
+            return;
+        }
+
+        self.hide_to_tray_on_close(ctx);
+        self.handle_tray();
+        self.handle_shortcuts(ctx);
+
+        // 汇总后台应用的结果
+        self.check_apply_state();
+        self.check_sync_pull();
+        self.check_auto_join();
+        self.check_reachability();
+        self.check_public_ip();
+        self.check_service_config();
+        self.check_diagnostics();
 
         // 自动检查和应用网络配置（后台执行）
         self.check_and_auto_apply(ctx);
 
-        // 请求持续刷新以支持自动检测
-        ctx.request_repaint_after(std::time::Duration::from_secs(1));
+        // 请求持续刷新以支持自动检测
+        ctx.request_repaint_after(std::time::Duration::from_secs(1));
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.heading(t("app_title"));
+                if ui.button(t("history_button")).clicked() {
+                    self.show_history = !self.show_history;
+                    if self.show_history {
+                        self.history_entries = network::read_apply_log(HISTORY_LIMIT);
+                    }
+                }
+                let layout = if self.config.compact { t("layout_full") } else { t("layout_compact") };
+                if ui.button(layout).clicked() {
+                    self.config.compact = !self.config.compact;
+                    let _ = self.config.save();
+                }
+            });
+            if self.config.load_failed {
+                ui.horizontal(|ui| {
+                    ui.colored_label(
+                        egui::Color32::RED,
+                        t("config_load_failed"),
+                    );
+                    if ui.button(t("overwrite_config_file")).clicked() {
+                        self.config.load_failed = false;
+                        if let Err(e) = self.config.save() {
+                            self.push_toast(ToastLevel::Error, tf("save_failed", &[&e]));
+                        }
+                    }
+                });
+            }
+            if self.config.secrets_in_file() {
+                ui.colored_label(
+                    WARNING_COLOR,
+                    t("keychain_unavailable"),
+                );
+            }
+            ui.add_space(10.0);
+
+            // 自动切换临时暂停中
+            if self.config.auto_switch
+                && let Some(until) = self.auto_switch_paused_until.filter(|_| self.auto_switch_paused())
+            {
+                let remaining = until.saturating_duration_since(Instant::now()).as_secs();
+                ui.horizontal(|ui| {
+                    ui.colored_label(
+                        WARNING_COLOR,
+                        tf("auto_switch_paused", &[&format!("{:02}:{:02}", remaining / 60, remaining % 60)]),
+                    );
+                    if ui.button(t("resume_now")).clicked() {
+                        self.resume_auto_switch();
+                    }
+                });
+            }
+
+            // 需要登录门户
+            if let Some(url) = self.current_portal_url.clone() {
+                ui.horizontal(|ui| {
+                    ui.colored_label(WARNING_COLOR, t("captive_portal"));
+                    if ui.button(t("open_login_page")).clicked() {
+                        open_in_browser(&url);
+                        // 登录后重新检测
+                        network::reset_captive_check();
+                    }
+                });
+            }
+
+            if self.config.compact {
+                self.render_compact_status(ui);
+                ui.add_space(5.0);
+                self.render_compact_configs(ui);
+            } else {
+                self.render_status_panel(ui);
+                ui.add_space(10.0);
+                self.render_settings(ui);
+                ui.add_space(10.0);
+                self.render_config_list(ui);
+                ui.add_space(10.0);
+                self.render_edit_panel(ui);
+            }
+            
+            // 应用进度
+            if self.is_applying {
+                let progress = self.apply_state.lock().ok().and_then(|state| state.progress.clone());
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    match progress {
+                        Some((service, step)) => ui.label(format!("{}: {}...", service, step)),
+                        None => ui.label(t("applying")),
+                    };
+                });
+            }
+
+            // 提示消息
+            self.toasts.retain(|toast| !toast.expired());
+            if !self.toasts.is_empty() {
+                ui.add_space(10.0);
+                for toast in &self.toasts {
+                    ui.colored_label(toast.level.color(), &toast.text);
+                }
+                // 到期后重绘以移除消息
+                ctx.request_repaint_after(Duration::from_secs(1));
+            }
+        });
+        
+        self.render_add_dialog(ctx);
+        self.render_preview_window(ctx);
+        self.render_change_password_window(ctx);
+        self.render_history_window(ctx);
+        self.render_location_import_window(ctx);
+        self.render_overwrite_dialog(ctx);
+        self.render_elevated_dialog(ctx);
+        self.render_permission_guide(ctx);
+        self.render_reset_dhcp_dialog(ctx);
+        self.render_conflict_dialog(ctx);
+        self.render_sync_conflict_window(ctx);
+        self.render_delete_dialog(ctx);
+        self.render_undo_delete(ctx);
+    }
+}
+
+impl NetworkSwitcherApp {
+    /// 当前网络状态：网络、服务、IP 设置和联网状态
+    fn render_status_panel(&mut self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.label(t("current_status"));
+                if self.is_refreshing {
+                    ui.spinner();
+                }
+                if ui.button(t("refresh")).clicked() {
+                    self.refresh_now();
+                }
+                if ui.button(t("reset_dhcp_button")).clicked() {
+                    self.confirm_reset_dhcp = true;
+                }
+            });
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.label(t("network_label"));
+                match (&self.current_ssid, &self.current_wifi_status) {
+                    (Some(network), _) if network.starts_with("[有线]") => ui.strong(format!("🔌 {}", network.trim_start_matches("[有线] "))),
+                    (Some(network), _) => ui.strong(format!("📶 {}", network)),
+                    (None, Some(SsidStatus::PoweredOff)) => ui.weak(t("wifi_off")),
+                    (None, Some(status)) => ui.weak(format!("📶 {}", status.label())),
+                    (None, None) => ui.weak(t("loading")),
+                };
+                if self.current_vpn_active {
+                    ui.colored_label(WARNING_COLOR, "🔒 VPN");
+                }
+                if self.current_proxy_enabled {
+                    ui.colored_label(WARNING_COLOR, "🌐 Proxy");
+                }
+            });
+
+            // 显示路由器 MAC（用于唯一标识）和接入点 BSSID
+            if self.current_router_mac.is_some() || self.current_bssid.is_some() {
+                ui.horizontal(|ui| {
+                    if let Some(ref mac) = self.current_router_mac {
+                        ui.label(t("router_mac_label"));
+                        ui.strong(mac);
+                    }
+                    if let Some(ref bssid) = self.current_bssid {
+                        ui.label("BSSID:");
+                        ui.strong(bssid);
+                    }
+                    if let Some(ref device) = self.current_wifi_device {
+                        ui.label(t("device_label"));
+                        ui.strong(device);
+                    }
+                });
+            }
+            if let Some(ref mac) = self.current_adapter_mac {
+                ui.horizontal(|ui| {
+                    ui.label(t("adapter_mac_label"));
+                    ui.strong(mac);
+                });
+            }
+
+            let mut service_changed: Option<String> = None;
+            ui.horizontal(|ui| {
+                ui.label(t("service_label"));
+                egui::ComboBox::from_id_salt("service_select")
+                    .selected_text(&self.network_services[self.selected_service_idx])
+                    .show_ui(ui, |ui| {
+                        for (i, service) in self.network_services.iter().enumerate() {
+                            if ui.selectable_value(&mut self.selected_service_idx, i, service).clicked() {
+                                service_changed = Some(service.clone());
+                            }
+                        }
+                    });
+                if let Some(state) = self.service_state(&self.network_services[self.selected_service_idx]) {
+                    ui.label(state.label());
+                }
+            });
+            if let Some(service) = service_changed {
+                self.config.network_service = service.clone();
+                self.refresh_service_config(service);
+                let _ = self.config.save();
+            }
+
+            // 显示当前配置信息
+            if let Some(ref cfg) = self.current_network_config {
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    ui.label("IP:");
+                    ui.strong(cfg.ip_address.as_deref().unwrap_or("N/A"));
+                });
+                ui.horizontal(|ui| {
+                    ui.label(t("subnet_label"));
+                    ui.strong(cfg.subnet_mask.as_deref().unwrap_or("N/A"));
+                });
+                ui.horizontal(|ui| {
+                    ui.label(t("router_label"));
+                    ui.strong(cfg.router.as_deref().unwrap_or("N/A"));
+                });
+                if let Some(ref ipv6) = cfg.ipv6_address {
+                    ui.horizontal(|ui| {
+                        ui.label("IPv6:");
+                        match cfg.ipv6_prefix {
+                            Some(prefix) => ui.strong(format!("{}/{}", ipv6, prefix)),
+                            None => ui.strong(ipv6),
+                        };
+                    });
+                }
+                ui.horizontal(|ui| {
+                    ui.label("DNS:");
+                    if cfg.dns_servers.is_empty() {
+                        ui.strong(t("dns_auto"));
+                    } else {
+                        ui.strong(cfg.dns_servers.join(", "));
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label(t("mode_label"));
+                    ui.strong(if cfg.use_dhcp { "DHCP" } else { t("static_mode") });
+                });
+                if let Some(mtu) = cfg.mtu {
+                    ui.horizontal(|ui| {
+                        ui.label("MTU:");
+                        ui.strong(mtu.to_string());
+                    });
+                }
+            }
+            if let Some(ref speed) = self.current_link_speed {
+                ui.horizontal(|ui| {
+                    ui.label(t("link_speed_label"));
+                    ui.strong(speed);
+                });
+            }
+            if self.config.public_ip_enabled {
+                ui.horizontal(|ui| {
+                    ui.label(t("public_ip_label"));
+                    match &self.current_public_ip {
+                        Some(Ok(ip)) => {
+                            ui.strong(ip);
+                        }
+                        Some(Err(e)) => {
+                            ui.weak(t("fetch_failed")).on_hover_text(e);
+                        }
+                        None => {}
+                    }
+                    if self.is_fetching_public_ip {
+                        ui.spinner();
+                    }
+                    if let Some(ref previous) = self.previous_public_ip {
+                        ui.colored_label(WARNING_COLOR, tf("public_ip_changed", &[&previous]));
+                    }
+                });
+            }
+            match self.current_reachability {
+                Some(Reachability::Online(latency)) => {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(100, 200, 100),
+                        tf("online", &[&latency.as_millis()]),
+                    );
+                }
+                Some(Reachability::RouterOnly) => {
+                    ui.colored_label(WARNING_COLOR, t("router_only"));
+                }
+                Some(Reachability::Offline) => {
+                    ui.colored_label(egui::Color32::RED, t("offline"));
+                }
+                None => {}
+            }
+        });
+    }
+
+    /// 紧凑模式的状态：一行显示网络、IP 和联网状态
+    fn render_compact_status(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if self.is_refreshing {
+                ui.spinner();
+            }
+            match &self.current_ssid {
+                Some(network) if network.starts_with("[有线]") => ui.strong(format!("🔌 {}", network.trim_start_matches("[有线] "))),
+                Some(network) => ui.strong(format!("📶 {}", network)),
+                None => ui.weak(t("disconnected")),
+            };
+            if let Some(ref cfg) = self.current_network_config {
+                ui.label(cfg.ip_address.as_deref().unwrap_or("N/A"));
+                ui.weak(if cfg.use_dhcp { "DHCP" } else { t("static_mode") });
+            }
+            match self.current_reachability {
+                Some(Reachability::Online(latency)) => {
+                    ui.colored_label(egui::Color32::from_rgb(100, 200, 100), format!("✅ {}ms", latency.as_millis()));
+                }
+                Some(Reachability::RouterOnly) => {
+                    ui.colored_label(WARNING_COLOR, "⚠️").on_hover_text(t("router_only_hint"));
+                }
+                Some(Reachability::Offline) => {
+                    ui.colored_label(egui::Color32::RED, "❌").on_hover_text(t("offline_hint"));
+                }
+                None => {}
+            }
+            if self.current_vpn_active {
+                ui.colored_label(WARNING_COLOR, "🔒 VPN");
+            }
+            if ui.small_button("🔄").on_hover_text(t("refresh_hint")).clicked() {
+                self.refresh_now();
+            }
+        });
+    }
+
+    /// 紧凑模式的配置选择：下拉框加应用按钮，默认选中匹配当前网络的配置
+    fn render_compact_configs(&mut self, ui: &mut egui::Ui) {
+        let mut configs: Vec<_> = self.config.configs.values().cloned().collect();
+        configs.sort_by(|a, b| a.name.cmp(&b.name));
+        let matching: Vec<String> = configs
+            .iter()
+            .filter(|cfg| {
+                cfg.matches_network(
+                    self.current_ssid.as_deref().unwrap_or(""),
+                    self.current_router_mac.as_deref(),
+                    self.current_bssid.as_deref(),
+                    self.current_adapter_mac.as_deref(),
+                )
+            })
+            .map(NetworkConfig::config_key)
+            .collect();
+
+        let selected = self
+            .compact_selected
+            .clone()
+            .filter(|key| self.config.configs.contains_key(key))
+            .or_else(|| matching.first().cloned());
+        let selected_cfg = selected.as_ref().and_then(|key| self.config.configs.get(key)).cloned();
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("compact_config")
+                .selected_text(selected_cfg.as_ref().map_or(t("select_config"), |cfg| cfg.name.as_str()))
+                .show_ui(ui, |ui| {
+                    for cfg in &configs {
+                        let marker = if matching.contains(&cfg.config_key()) { "●" } else { " " };
+                        let is_selected = selected.as_ref() == Some(&cfg.config_key());
+                        if ui.selectable_label(is_selected, format!("{} {}", marker, cfg.display_name())).clicked() {
+                            self.compact_selected = Some(cfg.config_key());
+                        }
+                    }
+                });
+            if ui.add_enabled(selected_cfg.is_some() && !self.is_applying, egui::Button::new(t("apply"))).clicked()
+                && let Some(ref cfg) = selected_cfg
+            {
+                self.apply_config_internal(cfg, false);
+            }
+        });
+    }
+
+    /// 应用级设置（不属于某个配置的选项），修改后立即保存
+    fn render_settings(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(t("settings"))
+            .id_salt("settings")
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.checkbox(&mut self.config.auto_switch, t("auto_switch")).changed() {
+                        let _ = self.config.save();
+                        if let Some(server) = &self.status_server {
+                            let auto_switch = self.config.auto_switch;
+                            server.update(|status| status.auto_switch = auto_switch);
+                        }
+                    }
+                    if self.config.auto_switch
+                        && !self.auto_switch_paused()
+                        && ui.button(tf("pause_auto_switch", &[&(AUTO_SWITCH_PAUSE.as_secs() / 60)]))
+                            .on_hover_text(t("pause_auto_switch_hint"))
+                            .clicked()
+                    {
+                        self.pause_auto_switch();
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    if ui.checkbox(&mut self.config.notifications_enabled, t("notifications")).changed() {
+                        let _ = self.config.save();
+                    }
+                    if self.config.notifications_enabled
+                        && ui.checkbox(&mut self.config.notify_on_manual_apply, t("notify_manual")).changed()
+                    {
+                        let _ = self.config.save();
+                    }
+                });
+
+                if ui.checkbox(&mut self.config.expose_socket, t("status_socket"))
+                    .on_hover_text(StatusServer::socket_path().display().to_string())
+                    .changed()
+                {
+                    self.sync_status_server();
+                    let _ = self.config.save();
+                }
+
+                self.render_sync_settings(ui);
+
+                ui.horizontal(|ui| {
+                    ui.label(t("apply_retries_label"));
+                    let response = ui.add(egui::DragValue::new(&mut self.config.apply_retries).range(0..=5))
+                        .on_hover_text(t("apply_retries_hint"));
+                    if response.changed() {
+                        network::set_apply_retries(self.config.apply_retries);
+                        let _ = self.config.save();
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label(t("reachability_interval_label"));
+                    let response = ui.add(egui::DragValue::new(&mut self.config.reachability_interval).range(0..=600))
+                        .on_hover_text(t("reachability_interval_hint"));
+                    if response.changed() {
+                        let _ = self.config.save();
+                    }
+                    if self.config.reachability_interval == 0 {
+                        ui.weak(t("disabled"));
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label(t("dns_ignore_label"));
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut self.dns_ignore_input)
+                            .hint_text(config::DEFAULT_DNS_IGNORE_PREFIXES.join(", "))
+                    )
+                    .on_hover_text(t("dns_ignore_hint"));
+                    if response.lost_focus() {
+                        self.config.dns_ignore_prefixes = self.dns_ignore_input
+                            .split(',')
+                            .map(|prefix| prefix.trim().to_string())
+                            .filter(|prefix| !prefix.is_empty())
+                            .collect();
+                        self.dns_ignore_input = self.config.dns_ignore_prefixes.join(", ");
+                        network::set_dns_ignore_prefixes(&self.config.dns_ignore_prefixes);
+                        let _ = self.config.save();
+                    }
+                });
+
+                if ui.checkbox(&mut self.config.allow_hooks, t("allow_hooks"))
+                    .on_hover_text(t("allow_hooks_hint"))
+                    .changed()
+                {
+                    network::set_allow_hooks(self.config.allow_hooks);
+                    let _ = self.config.save();
+                }
+
+                if ui.checkbox(&mut self.config.public_ip_enabled, t("public_ip_enabled"))
+                    .on_hover_text(t("public_ip_hint"))
+                    .changed()
+                {
+                    self.start_public_ip_fetch();
+                    let _ = self.config.save();
+                }
+                if self.config.public_ip_enabled {
+                    ui.horizontal(|ui| {
+                        ui.label(t("public_ip_url_label"));
+                        let response = ui.add(
+                            egui::TextEdit::singleline(&mut self.config.public_ip_url)
+                                .hint_text(config::DEFAULT_PUBLIC_IP_URL)
+                        );
+                        if response.lost_focus() {
+                            if self.config.public_ip_url.trim().is_empty() {
+                                self.config.public_ip_url = config::DEFAULT_PUBLIC_IP_URL.to_string();
+                            }
+                            let _ = self.config.save();
+                            self.start_public_ip_fetch();
+                        }
+                    });
+                }
+
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(!self.is_dumping_diagnostics, egui::Button::new(t("dump_diagnostics")))
+                        .on_hover_text(t("dump_diagnostics_hint"))
+                        .clicked()
+                    {
+                        self.dump_diagnostics();
+                    }
+                    if self.is_dumping_diagnostics {
+                        ui.spinner();
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label(t("theme_label"));
+                    egui::ComboBox::from_id_salt("theme_select")
+                        .selected_text(self.config.theme.label())
+                        .show_ui(ui, |ui| {
+                            for theme in Theme::ALL {
+                                if ui.selectable_value(&mut self.config.theme, theme, theme.label()).clicked() {
+                                    apply_theme(ui.ctx(), theme);
+                                    let _ = self.config.save();
+                                }
+                            }
+                        });
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label(t("lang_label"));
+                    egui::ComboBox::from_id_salt("lang_select")
+                        .selected_text(self.config.lang.map_or(t("lang_system"), Lang::label))
+                        .show_ui(ui, |ui| {
+                            let mut changed = ui.selectable_value(&mut self.config.lang, None, t("lang_system")).clicked();
+                            for lang in Lang::ALL {
+                                changed |= ui.selectable_value(&mut self.config.lang, Some(lang), lang.label()).clicked();
+                            }
+                            if changed {
+                                i18n::set_lang(self.config.lang.unwrap_or_else(i18n::system_lang));
+                                let _ = self.config.save();
+                            }
+                        });
+                });
+
+                if AppConfig::config_path().extension().is_none_or(|ext| ext != "toml")
+                    && ui.button(t("migrate_toml"))
+                        .on_hover_text(t("migrate_toml_hint"))
+                        .clicked()
+                {
+                    match self.config.migrate_to_toml() {
+                        Ok(path) => self.push_toast(ToastLevel::Success, tf("migrated", &[&path.display()])),
+                        Err(e) => self.push_toast(ToastLevel::Error, tf("migrate_failed", &[&e])),
+                    }
+                }
+
+                if ui.button(t("change_password_button")).clicked() {
+                    self.show_change_password = true;
+                    self.password_setup_error = None;
+                }
+            });
+    }
+
+    /// 局域网同步：提供本机配置，或从另一台机器拉取
+    fn render_sync_settings(&mut self, ui: &mut egui::Ui) {
+        if ui.checkbox(&mut self.config.sync_enabled, t("lan_sync"))
+            .on_hover_text(t("lan_sync_hint"))
+            .changed()
+        {
+            self.sync_lan_server();
+            let _ = self.config.save();
+        }
+        if self.config.sync_enabled {
+            ui.horizontal(|ui| {
+                ui.label(t("port_label"));
+                let response = ui.add(egui::DragValue::new(&mut self.config.sync_port).range(1024..=65535));
+                // 拖动结束后才重新监听
+                if response.drag_stopped() || response.lost_focus() {
+                    self.sync_lan_server();
+                    let _ = self.config.save();
+                }
+                ui.label(t("token_label"));
+                ui.monospace(&self.config.sync_token);
+                if ui.small_button("📋").on_hover_text(t("copy_token")).clicked() {
+                    ui.ctx().copy_text(self.config.sync_token.clone());
+                }
+                if ui.small_button("🔄").on_hover_text(t("regenerate_token")).clicked() {
+                    self.config.sync_token = uuid::Uuid::new_v4().simple().to_string();
+                    if let Some(server) = &self.sync_server {
+                        server.set_token(self.config.sync_token.clone());
+                    }
+                    let _ = self.config.save();
+                }
+            });
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(t("pull_from_label"));
+            ui.add(egui::TextEdit::singleline(&mut self.sync_peer).hint_text("192.168.1.2:47800").desired_width(140.0));
+            ui.add(egui::TextEdit::singleline(&mut self.sync_peer_token).hint_text(t("token")).password(true).desired_width(120.0));
+            if self.is_pulling {
+                ui.spinner();
+            } else if ui.add_enabled(!self.sync_peer.trim().is_empty(), egui::Button::new(t("pull"))).clicked() {
+                self.start_sync_pull();
+            }
+        });
+    }
+
+    /// 在后台线程拉取另一台机器的配置
+    fn start_sync_pull(&mut self) {
+        self.is_pulling = true;
+        let peer = self.sync_peer.clone();
+        let token = self.sync_peer_token.trim().to_string();
+        let sync_pull = Arc::clone(&self.sync_pull);
+        thread::spawn(move || {
+            let result = sync::pull(&peer, &token);
+            if let Ok(mut pull) = sync_pull.lock() {
+                *pull = Some(result);
+            }
+        });
+    }
+
+    /// 拉取完成后合并新配置，内容不同的配置交给用户确认
+    fn check_sync_pull(&mut self) {
+        if !self.is_pulling {
+            return;
+        }
+        let Some(result) = self.sync_pull.lock().ok().and_then(|mut pull| pull.take()) else {
+            return;
+        };
+        self.is_pulling = false;
+
+        match result {
+            Ok(remote) => {
+                let (added, conflicts) = self.config.merge_remote(remote);
+                let _ = self.config.save();
+                self.push_toast(ToastLevel::Success, tf("sync_done", &[&added, &conflicts.len()]));
+                if !conflicts.is_empty() {
+                    self.sync_conflicts = Some(conflicts.into_iter().map(|cfg| (cfg, false)).collect());
+                }
+            }
+            Err(e) => self.push_toast(ToastLevel::Error, tf("pull_failed", &[&e])),
+        }
+    }
+
+    /// 配置集选择：切换、新建和删除配置集
+    fn render_profile_picker(&mut self, ui: &mut egui::Ui) {
+        let mut switch_to: Option<String> = None;
+        let mut delete: Option<String> = None;
+        let profiles = self.config.list_profiles();
+
+        ui.horizontal(|ui| {
+            ui.label(t("profile_label"));
+            egui::ComboBox::from_id_salt("profile_select")
+                .selected_text(&self.config.active_profile)
+                .show_ui(ui, |ui| {
+                    for profile in &profiles {
+                        if ui.selectable_label(profile == &self.config.active_profile, profile).clicked() {
+                            switch_to = Some(profile.clone());
+                        }
+                    }
+                });
+            ui.add(
+                egui::TextEdit::singleline(&mut self.new_profile_name)
+                    .hint_text(t("new_profile_hint"))
+                    .desired_width(100.0)
+            );
+            if ui.add_enabled(!self.new_profile_name.trim().is_empty(), egui::Button::new(t("new_profile"))).clicked() {
+                switch_to = Some(self.new_profile_name.trim().to_string());
+            }
+            if profiles.len() > 1 {
+                ui.menu_button("🗑", |ui| {
+                    for profile in profiles.iter().filter(|p| *p != &self.config.active_profile) {
+                        if ui.button(tf("delete_profile", &[profile])).clicked() {
+                            delete = Some(profile.clone());
+                            ui.close();
+                        }
+                    }
+                });
+            }
+        });
+
+        if let Some(name) = switch_to {
+            self.switch_profile(&name);
+        }
+        if let Some(name) = delete {
+            match self.config.delete_profile(&name) {
+                Ok(()) => self.push_toast(ToastLevel::Success, tf("profile_deleted", &[&name])),
+                Err(e) => self.push_toast(ToastLevel::Error, tf("delete_profile_failed", &[&e])),
+            }
+        }
+    }
+
+    /// 切换配置集，之后自动切换只考虑新配置集中的配置
+    fn switch_profile(&mut self, name: &str) {
+        if let Err(e) = self.config.switch_profile(name) {
+            self.push_toast(ToastLevel::Error, tf("switch_profile_failed", &[&e]));
+            return;
+        }
+        self.new_profile_name.clear();
+        self.editing_config = None;
+        self.recently_deleted = None;
+        self.last_applied_key = None;
+        self.last_active_schedules = self.active_schedules();
+        self.push_toast(ToastLevel::Info, tf("profile_switched", &[&self.config.active_profile]));
+        // 按新配置集的规则重新检查自动切换
+        self.try_auto_apply();
+    }
+
+    fn render_config_list(&mut self, ui: &mut egui::Ui) {
+        ui.group(|ui| {
+            self.render_profile_picker(ui);
+            ui.horizontal(|ui| {
+                ui.label(t("saved_configs"));
+                if ui.button(t("add")).clicked() {
+                    self.open_add_dialog();
+                }
+                if ui.button(t("export")).clicked() {
+                    self.export_configs();
+                }
+                if ui.button(t("import")).clicked() {
+                    self.import_configs();
+                }
+                if ui.button(t("paste_config")).clicked() {
+                    self.paste_config();
+                }
+                if cfg!(not(target_os = "linux")) && ui.button(t("import_locations")).clicked() {
+                    self.location_import = Some(network::backend().list_locations());
+                }
+                if ui.button(t("check_conflicts")).clicked() {
+                    self.show_conflicts = !self.show_conflicts;
+                }
+                ui.menu_button(t("restore_backup"), |ui| {
+                    let backups = AppConfig::list_backups();
+                    if backups.is_empty() {
+                        ui.label(t("no_backups"));
+                    }
+                    for (n, modified) in backups {
+                        let time = chrono::DateTime::<chrono::Local>::from(modified).format("%Y-%m-%d %H:%M:%S");
+                        if ui.button(format!("#{}  {}", n, time)).clicked() {
+                            self.restore_backup(n);
+                            ui.close();
+                        }
+                    }
+                });
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("🔍");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.config_filter)
+                        .hint_text(t("filter_hint"))
+                );
+                if !self.config_filter.is_empty() && ui.button("✖").clicked() {
+                    self.config_filter.clear();
+                }
+                ui.selectable_value(&mut self.sort_by_recent, false, t("sort_by_name"));
+                ui.selectable_value(&mut self.sort_by_recent, true, t("sort_by_recent"));
+                ui.toggle_value(&mut self.group_by_router, t("group_by_router"))
+                    .on_hover_text(t("group_by_router_hint"));
+            });
+
+            // 当前环境：匹配当前网络的配置的标签
+            let active_tags = self.config.active_tags(
+                self.current_ssid.as_deref().unwrap_or(""),
+                self.current_router_mac.as_deref(),
+                self.current_bssid.as_deref(),
+                self.current_adapter_mac.as_deref(),
+            );
+            let all_tags = self.config.all_tags();
+            if !all_tags.is_empty() {
+                ui.horizontal(|ui| {
+                    ui.label("🏷");
+                    let selected = match &self.tag_filter {
+                        TagFilter::All => t("all_tags").to_string(),
+                        TagFilter::Current => t("current_context").to_string(),
+                        TagFilter::Tag(tag) => tag.clone(),
+                    };
+                    egui::ComboBox::from_id_salt("tag_filter")
+                        .selected_text(selected)
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.tag_filter, TagFilter::All, t("all_tags"));
+                            ui.selectable_value(&mut self.tag_filter, TagFilter::Current, t("current_context"));
+                            for tag in &all_tags {
+                                ui.selectable_value(&mut self.tag_filter, TagFilter::Tag(tag.clone()), tag);
+                            }
+                        });
+                    if active_tags.is_empty() {
+                        ui.weak(t("current_context_none"));
+                    } else {
+                        ui.weak(tf("current_context_tags", &[&active_tags.join(", ")]));
+                    }
+                });
+            }
+
+            // 自动应用配置之间的冲突
+            let conflicts = self.config.auto_apply_conflicts();
+            if self.show_conflicts {
+                self.render_conflicts(ui, &conflicts);
+            }
+
+            ui.separator();
+
+            // 按名称或最近使用排序显示，从未应用过的排在最后
+            let mut configs: Vec<_> = self.config.configs
+                .values()
+                .filter(|cfg| cfg.matches_filter(&self.config_filter))
+                .filter(|cfg| match &self.tag_filter {
+                    TagFilter::All => true,
+                    TagFilter::Current => cfg.tags.iter().any(|tag| active_tags.contains(tag)),
+                    TagFilter::Tag(tag) => cfg.tags.contains(tag),
+                })
+                .cloned()
+                .collect();
+            if self.sort_by_recent {
+                configs.sort_by(|a, b| b.last_applied.cmp(&a.last_applied).then_with(|| a.name.cmp(&b.name)));
+            } else {
+                configs.sort_by(|a, b| a.name.cmp(&b.name));
+            }
+            let now = chrono::Local::now().timestamp();
+
+            let current_ssid = self.current_ssid.clone();
+            let current_mac = self.current_router_mac.clone();
+            let current_bssid = self.current_bssid.clone();
+            let current_adapter_mac = self.current_adapter_mac.clone();
+
+            // 按路由器分组时：当前网络的组在前，其余按 MAC 排列，未绑定路由器的在最后；组内保持原有排序
+            let current_group = current_mac.as_deref().map(config::normalize_mac);
+            let mut group_sizes: HashMap<Option<String>, usize> = HashMap::new();
+            if self.group_by_router {
+                configs.sort_by_cached_key(|cfg| {
+                    let group = cfg.router_mac.as_deref().map(config::normalize_mac);
+                    (group.is_none(), group != current_group, group)
+                });
+                for cfg in &configs {
+                    *group_sizes.entry(cfg.router_mac.as_deref().map(config::normalize_mac)).or_default() += 1;
+                }
+            }
+            let mut last_group: Option<Option<String>> = None;
+
+            for cfg in configs {
+                if self.group_by_router {
+                    let group = cfg.router_mac.as_deref().map(config::normalize_mac);
+                    if last_group.as_ref() != Some(&group) {
+                        let count = group_sizes.get(&group).copied().unwrap_or(0);
+                        let header = match &group {
+                            Some(mac) => tf("router_group", &[&config::short_mac(mac), &count]),
+                            None => tf("no_router_group", &[&count]),
+                        };
+                        if last_group.is_some() {
+                            ui.add_space(4.0);
+                        }
+                        if group.is_some() && group == current_group {
+                            ui.label(egui::RichText::new(tf("current_network_group", &[&header])).strong().color(egui::Color32::from_rgb(100, 200, 100)));
+                        } else {
+                            let response = ui.label(egui::RichText::new(header).weak());
+                            if let Some(mac) = &group {
+                                response.on_hover_text(mac);
+                            }
+                        }
+                        last_group = Some(group);
+                    }
+                }
+
+                // 没有目标服务的配置应用到当前选择的服务
+                let target = cfg.resolve_target_services(&self.network_services[self.selected_service_idx]).join(", ");
+
+                // 检查是否匹配当前网络
+                let is_matching = cfg.matches_network(
+                    current_ssid.as_deref().unwrap_or(""),
+                    current_mac.as_deref(),
+                    current_bssid.as_deref(),
+                    current_adapter_mac.as_deref(),
+                );
+
+                ui.horizontal(|ui| {
+                    // 显示配置名称和信息
+                    let display = cfg.display_name();
+                    let mut text = if is_matching {
+                        egui::RichText::new(format!("● {}", display)).strong()
+                    } else {
+                        egui::RichText::new(format!("  {}", display))
+                    };
+                    // 与其他自动应用配置冲突的用警告色标出
+                    if conflicts.iter().any(|c| c.involves(&cfg.config_key())) {
+                        text = text.color(WARNING_COLOR);
+                    }
+                    let label = ui.label(text);
+                    if !cfg.notes.trim().is_empty() {
+                        label.on_hover_text(&cfg.notes);
+                    }
+                    // 标签，点击按该标签筛选
+                    for tag in &cfg.tags {
+                        if ui.small_button(format!("🏷 {}", tag)).on_hover_text(t("filter_by_tag")).clicked() {
+                            self.tag_filter = TagFilter::Tag(tag.clone());
+                        }
+                    }
+
+                    ui.label(format!("→ {}", target));
+                    // 目标服务不可用时提示，应用时会跳过这些服务
+                    let selected_service = &self.network_services[self.selected_service_idx];
+                    for service in cfg.resolve_target_services(selected_service) {
+                        if let Some(state) = self.service_state(&service).filter(|state| !state.can_apply()) {
+                            ui.colored_label(WARNING_COLOR, format!("{} {}", service, state.label()))
+                                .on_hover_text(t("service_skipped_hint"));
+                        }
+                    }
+
+                    if let Some(applied_at) = cfg.last_applied {
+                        ui.weak(tf("last_applied", &[&format_relative_time(applied_at, now)]));
+                    }
+
+                    if ui.button(t("edit")).clicked() {
+                        self.start_editing(cfg.clone());
+                    }
+
+                    if ui.button(t("duplicate")).clicked() {
+                        self.duplicate_config(&cfg);
+                    }
+
+                    if ui.button(t("copy_json")).clicked() {
+                        self.copy_config_json(ui.ctx(), &cfg);
+                    }
+
+                    // 只应用到当前服务时，显示与当前设置不同的字段数
+                    let selected_service = &self.network_services[self.selected_service_idx];
+                    let current = self
+                        .current_network_config
+                        .as_ref()
+                        .filter(|_| cfg.resolve_target_services(selected_service) == [selected_service.clone()]);
+                    let changed = current.map(|current| current.diff(&cfg).len());
+                    // 匹配当前网络但设置被手动改过时，可以把当前设置写回配置
+                    let drift = current
+                        .filter(|_| is_matching)
+                        .map(|current| cfg.drift_from(current))
+                        .unwrap_or_default();
+                    let apply_button = match changed {
+                        Some(0) => ui.button(t("apply")).on_hover_text(t("same_as_current")),
+                        Some(n) => ui.button(tf("apply_with_diff", &[&n])).on_hover_text(tf("diff_count", &[&n])),
+                        None => ui.button(t("apply")),
+                    };
+                    if apply_button.clicked() {
+                        self.apply_config_internal(&cfg, false);
+                    }
+
+                    if !drift.is_empty() {
+                        let details: Vec<String> = drift
+                            .iter()
+                            .map(|d| format!("{}: {} → {}", d.field.label(), d.from, d.to))
+                            .collect();
+                        if ui.add_enabled(!cfg.locked, egui::Button::new(t("update_from_current")))
+                            .on_hover_text(tf("drift_details", &[&details.join("\n")]))
+                            .on_disabled_hover_text(t("locked_hint"))
+                            .clicked()
+                        {
+                            self.update_config_from_current(&cfg);
+                        }
+                    }
+
+                    if cfg.config_type == ConfigType::Wifi
+                        && !cfg.ssid.is_empty()
+                        && ui.button(t("join_and_apply")).clicked()
+                    {
+                        self.join_and_apply(&cfg);
+                    }
+
+                    if ui.button(t("preview")).clicked() {
+                        let services = cfg.resolve_target_services(&self.network_services[self.selected_service_idx]);
+                        let mut lines = Vec::new();
+                        let mut diffs = Vec::new();
+                        for service in &services {
+                            if let Some(current) = self.current_config_for(service) {
+                                diffs.push((service.clone(), current.diff(&cfg)));
+                            }
+                            // 多个目标服务时用注释行分隔
+                            if services.len() > 1 {
+                                lines.push(format!("# {}", service));
+                            }
+                            lines.extend(
+                                network::backend().plan_config(service, &cfg)
+                                    .iter()
+                                    .map(|argv| network::format_command(argv))
+                            );
+                        }
+                        self.preview_commands = Some((cfg.name.clone(), lines, diffs));
+                    }
+
+                    if ui.button("🗑").clicked() {
+                        self.confirm_delete = Some(cfg.config_key());
+                        self.confirm_delete_locked = false;
+                        self.unlock_password_input.clear();
+                        self.unlock_password_error = false;
+                    }
+                });
+            }
+
+            if self.config.configs.is_empty() {
+                ui.label(t("no_configs"));
+                ui.add_space(5.0);
+                let button = egui::Button::new(egui::RichText::new(t("quick_save")).heading());
+                if ui.add(button).clicked() {
+                    self.capture_current_network();
+                }
+                ui.label(t("quick_save_hint"));
+            }
+        });
+    }
+
+    /// 用当前服务的实际设置更新保存的配置并保存
+    fn update_config_from_current(&mut self, cfg: &NetworkConfig) {
+        let Some(current) = self.current_network_config.clone() else {
+            return;
+        };
+        let Some(saved) = self.config.configs.get_mut(&cfg.config_key()) else {
+            return;
+        };
+        saved.update_from(&current);
+        match self.config.save() {
+            Ok(()) => self.push_toast(ToastLevel::Success, tf("updated_from_current", &[&cfg.name])),
+            Err(e) => self.push_toast(ToastLevel::Error, tf("save_failed", &[&e])),
+        }
+    }
+
+    /// 复制配置并打开编辑
+    fn duplicate_config(&mut self, cfg: &NetworkConfig) {
+        let mut copy = cfg.clone();
+        copy.id = NetworkConfig::new_id();
+        // 追加 "(副本)" 保证名称唯一
+        copy.name = tf("copy_suffix", &[&cfg.name]);
+        let mut n = 2;
+        while self.config.find_by_name(&copy.name).is_some() {
+            copy.name = tf("copy_suffix_n", &[&cfg.name, &n]);
+            n += 1;
+        }
+        // 副本与原配置匹配条件相同，不能同时自动应用
+        copy.auto_apply = false;
+        copy.last_applied = None;
+        // 副本通常用来修改，不继承锁定
+        copy.locked = false;
+        // 副本使用独立的钥匙串条目，删除其中一个不影响另一个
+        copy.wifi_password = cfg.resolve_wifi_password();
+        copy.wifi_password_account = None;
+        let _ = copy.store_wifi_password();
+
+        self.config.add_config(copy.clone());
+        let _ = self.config.save();
+        self.start_editing(copy);
+    }
+
+    /// 把单个配置以 JSON 复制到剪贴板（不包含 WiFi 密码）
+    fn copy_config_json(&mut self, ctx: &egui::Context, cfg: &NetworkConfig) {
+        let mut shared = cfg.clone();
+        shared.wifi_password = None;
+        shared.wifi_password_account = None;
+        match serde_json::to_string_pretty(&shared) {
+            Ok(json) => {
+                ctx.copy_text(json);
+                self.push_toast(ToastLevel::Success, tf("copied_json", &[&cfg.name]));
+            }
+            Err(e) => self.push_toast(ToastLevel::Error, tf("copy_failed", &[&e])),
+        }
+    }
+
+    /// 从剪贴板的 JSON 添加一个配置
+    fn paste_config(&mut self) {
+        let Some(text) = read_clipboard() else {
+            self.push_toast(ToastLevel::Error, t("clipboard_unavailable"));
+            return;
+        };
+        let mut cfg: NetworkConfig = match serde_json::from_str(text.trim()) {
+            Ok(cfg) => cfg,
+            Err(e) => {
+                self.push_toast(ToastLevel::Error, tf("clipboard_invalid", &[&e]));
+                return;
+            }
+        };
+        if let Err(errors) = config::validate(&cfg) {
+            self.push_toast(ToastLevel::Error, tf("config_invalid", &[&errors.join("；")]));
+            return;
+        }
+
+        // 不覆盖已有配置：id 或名称冲突时分配新的 id 和名称
+        if cfg.id.is_empty() || self.config.configs.contains_key(&cfg.id) {
+            cfg.id = NetworkConfig::new_id();
+        }
+        if self.config.find_by_name(&cfg.name).is_some() {
+            cfg.name = self.unique_config_name(&cfg.name);
+        }
+        cfg.wifi_password_account = None;
+
+        self.push_toast(ToastLevel::Success, tf("pasted", &[&cfg.name]));
+        self.config.add_config(cfg);
+        let _ = self.config.save();
+    }
+
+    /// 恢复第 n 个配置备份
+    fn restore_backup(&mut self, n: usize) {
+        match AppConfig::restore_backup(n) {
+            Ok(config) => {
+                self.config = config;
+                self.editing_config = None;
+                self.last_applied_key = None;
+                self.sync_status_server();
+                self.sync_lan_server();
+                self.push_toast(ToastLevel::Success, tf("backup_restored", &[&n]));
+            }
+            Err(e) => self.push_toast(ToastLevel::Error, tf("restore_backup_failed", &[&e])),
+        }
+    }
+
+    /// 导出全部配置到用户选择的文件
+    fn export_configs(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .add_filter("TOML", &["toml"])
+            .set_file_name("network-switcher-configs.json")
+            .save_file()
+        else {
+            return;
+        };
+
+        match self.config.export_to_path(&path) {
+            Ok(_) => {
+                self.push_toast(ToastLevel::Success, tf("exported", &[&self.config.configs.len()]));
+            }
+            Err(e) => {
+                self.push_toast(ToastLevel::Error, tf("export_failed", &[&e]));
+            }
+        }
+    }
+
+    /// 从用户选择的文件导入配置（按配置名合并）
+    fn import_configs(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .add_filter("TOML", &["toml"])
+            .pick_file()
+        else {
+            return;
+        };
+
+        match AppConfig::import_from_path(&path) {
+            Ok(imported) => {
+                let (added, skipped) = self.config.merge_configs(imported);
+                let _ = self.config.save();
+                self.push_toast(ToastLevel::Success, tf("imported", &[&added, &skipped]));
+            }
+            Err(e) => {
+                self.push_toast(ToastLevel::Error, tf("import_failed", &[&e]));
+            }
+        }
+    }
+
+    /// 把 macOS 位置中的每个网络服务导入为配置（名称冲突时自动改名）
+    fn import_location(&mut self, location: &str) {
+        let configs = match network::backend().location_configs(location) {
+            Ok(configs) => configs,
+            Err(e) => {
+                self.push_toast(ToastLevel::Error, tf("read_location_failed", &[&error_icon(&e), &location, &e]));
+                return;
+            }
+        };
+        if configs.is_empty() {
+            self.push_toast(ToastLevel::Warning, tf("location_empty", &[&location]));
+            return;
+        }
 
-        egui::CentralPanel::default().show(ctx, |ui| {
-            ui.heading("🌐 网络配置切换器");
-            ui.add_space(10.0);
+        let count = configs.len();
+        for mut cfg in configs {
+            if self.config.find_by_name(&cfg.name).is_some() {
+                cfg.name = self.unique_config_name(&cfg.name);
+            }
+            self.config.add_config(cfg);
+        }
+        let _ = self.config.save();
+        self.push_toast(ToastLevel::Success, tf("location_imported", &[&location, &count]));
+    }
 
-            // 当前网络状态
-            ui.group(|ui| {
-                ui.horizontal(|ui| {
-                    ui.label("📡 当前状态");
-                    if self.is_refreshing {
-                        ui.spinner();
-                    }
-                    if ui.button("🔄 刷新").clicked() && !self.is_refreshing {
-                        let service = self.network_services[self.selected_service_idx].clone();
-                        self.refresh_in_background(service);
-                    }
-                });
-                ui.separator();
+    /// 开始编辑配置
+    fn start_editing(&mut self, cfg: NetworkConfig) {
+        self.edit_history.reset(&cfg);
+        // 锁定的配置以只读方式打开，需要在编辑面板中解锁
+        self.edit_unlocked = !cfg.locked;
+        self.unlock_password_input.clear();
+        self.unlock_password_error = false;
+        self.editing_config = Some(cfg);
+        self.confirm_overwrite = false;
+        self.validation_errors.clear();
+        if let Ok(mut results) = self.dns_test_results.lock() {
+            results.clear();
+        }
+    }
 
-                ui.horizontal(|ui| {
-                    ui.label("网络连接 / Network:");
-                    let network_display = self.current_ssid.as_deref().unwrap_or("加载中... / Loading...");
-                    if network_display.starts_with("[有线]") {
-                        ui.strong(format!("🔌 {}", network_display));
-                    } else {
-                        ui.strong(format!("📶 {}", network_display));
-                    }
-                });
+    /// 在后台线程逐个测试 DNS 服务器
+    fn test_dns_in_background(&mut self, servers: Vec<String>) {
+        if let Ok(mut results) = self.dns_test_results.lock() {
+            *results = servers.iter().map(|s| (s.clone(), None)).collect();
+        }
 
-                // 显示路由器 MAC（用于唯一标识）
-                if let Some(ref mac) = self.current_router_mac {
-                    ui.horizontal(|ui| {
-                        ui.label("路由器 MAC:");
-                        ui.strong(mac);
-                    });
+        for (idx, server) in servers.into_iter().enumerate() {
+            let results = Arc::clone(&self.dns_test_results);
+            thread::spawn(move || {
+                let result = network::test_dns_server(&server);
+                if let Ok(mut results) = results.lock()
+                    && let Some(entry) = results.get_mut(idx)
+                    && entry.0 == server
+                {
+                    entry.1 = Some(result);
                 }
+            });
+        }
+    }
+
+    /// 保存正在编辑的配置，同名的其他配置会被覆盖
+    fn commit_editing(&mut self) {
+        let Some(mut editing) = self.editing_config.take() else {
+            return;
+        };
+        // WiFi 密码存入钥匙串，失败时退回到保存在配置文件中
+        if let Err(e) = editing.store_wifi_password() {
+            self.push_toast(ToastLevel::Warning, tf("password_plaintext", &[&e]));
+        }
+
+        // 编辑期间可能应用过该配置，保留最新的应用时间
+        if let Some(stored) = self.config.configs.get(&editing.config_key()) {
+            editing.last_applied = stored.last_applied;
+        }
+
+        self.config
+            .configs
+            .retain(|_, c| c.name != editing.name || c.id == editing.id);
+        let name = editing.name.clone();
+        self.config.add_config(editing);
+        match self.config.save() {
+            Ok(()) => self.push_toast(ToastLevel::Success, tf("config_saved", &[&name])),
+            Err(e) => self.push_toast(ToastLevel::Error, tf("save_failed", &[&e])),
+        }
+        self.confirm_overwrite = false;
+    }
+
+    /// 用当前网络的标识和设置创建配置，绑定路由器 MAC 并直接打开编辑
+    fn capture_current_network(&mut self) {
+        let identity = network::get_network_identity(false);
+        let selected = self.network_services[self.selected_service_idx].clone();
+
+        // 有线网络以服务名命名，WiFi 以 SSID 命名
+        let (service, name, config_type) = match (&identity.service_name, &identity.ssid) {
+            (Some(service), _) if identity.is_wired => (service.clone(), service.clone(), ConfigType::Service),
+            (_, Some(ssid)) => (selected, ssid.clone(), ConfigType::Wifi),
+            _ => {
+                self.push_toast(ToastLevel::Error, t("no_network"));
+                return;
+            }
+        };
+
+        let mut cfg = match network::backend().current_config(&service) {
+            Ok(cfg) => cfg,
+            Err(e) => {
+                self.push_toast(ToastLevel::Error, tf("get_current_failed", &[&error_icon(&e), &e]));
+                return;
+            }
+        };
+        cfg.id = NetworkConfig::new_id();
+        cfg.name = if self.config.find_by_name(&name).is_some() {
+            self.unique_config_name(&name)
+        } else {
+            name
+        };
+        cfg.ssid = identity.ssid.unwrap_or_default();
+        cfg.router_mac = identity.router_mac;
+        cfg.config_type = config_type;
+        cfg.target_service = Some(service);
+        cfg.auto_apply = false;
+        self.start_editing(cfg);
+    }
+
+    /// 生成不与已有配置冲突的名称，如 "Office 2"
+    fn unique_config_name(&self, name: &str) -> String {
+        let mut n = 2;
+        loop {
+            let candidate = format!("{} {}", name, n);
+            if self.config.find_by_name(&candidate).is_none() {
+                return candidate;
+            }
+            n += 1;
+        }
+    }
+
+    fn render_elevated_dialog(&mut self, ctx: &egui::Context) {
+        let Some((cfg, services, automatic)) = self.confirm_elevated.clone() else {
+            return;
+        };
+        let mut retry = false;
+        let mut cancel = false;
 
-                let mut service_changed: Option<String> = None;
+        egui::Window::new(t("admin_required"))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(tf("admin_retry_message", &[&cfg.name, &services.join(", ")]));
+                ui.label(t("admin_retry_question"));
+                ui.add_space(10.0);
                 ui.horizontal(|ui| {
-                    ui.label("网络服务 / Service:");
-                    egui::ComboBox::from_id_salt("service_select")
-                        .selected_text(&self.network_services[self.selected_service_idx])
-                        .show_ui(ui, |ui| {
-                            for (i, service) in self.network_services.iter().enumerate() {
-                                if ui.selectable_value(&mut self.selected_service_idx, i, service).clicked() {
-                                    service_changed = Some(service.clone());
-                                }
-                            }
-                        });
+                    if ui.button(t("admin_retry")).clicked() {
+                        retry = true;
+                    }
+                    if ui.button(t("cancel")).clicked() {
+                        cancel = true;
+                    }
                 });
-                if let Some(service) = service_changed {
-                    self.config.network_service = service.clone();
-                    self.refresh_in_background(service);
-                    let _ = self.config.save();
-                }
+            });
 
-                // 显示当前配置信息
-                if let Some(ref cfg) = self.current_network_config {
-                    ui.add_space(5.0);
-                    ui.horizontal(|ui| {
-                        ui.label("IP:");
-                        ui.strong(cfg.ip_address.as_deref().unwrap_or("N/A"));
-                    });
-                    ui.horizontal(|ui| {
-                        ui.label("子网掩码 / Subnet:");
-                        ui.strong(cfg.subnet_mask.as_deref().unwrap_or("N/A"));
-                    });
-                    ui.horizontal(|ui| {
-                        ui.label("路由器 / Router:");
-                        ui.strong(cfg.router.as_deref().unwrap_or("N/A"));
-                    });
-                    ui.horizontal(|ui| {
-                        ui.label("DNS:");
-                        if cfg.dns_servers.is_empty() {
-                            ui.strong("自动 / Auto");
-                        } else {
-                            ui.strong(cfg.dns_servers.join(", "));
-                        }
-                    });
-                    ui.horizontal(|ui| {
-                        ui.label("模式 / Mode:");
-                        ui.strong(if cfg.use_dhcp { "DHCP" } else { "静态 / Static" });
-                    });
+        if retry {
+            self.confirm_elevated = None;
+            self.apply_elevated(&cfg, services, automatic);
+        } else if cancel {
+            self.confirm_elevated = None;
+        }
+    }
+
+    /// 首次运行的权限说明，关闭后不再显示
+    fn render_permission_guide(&mut self, ctx: &egui::Context) {
+        if !self.show_permission_guide {
+            return;
+        }
+        let mut close = false;
+
+        egui::Window::new(t("admin_required"))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(t("not_admin_warning"));
+                ui.add_space(5.0);
+                if cfg!(target_os = "macos") {
+                    ui.label(t("get_permission"));
+                    ui.label(t("mac_admin_tip"));
+                    ui.label(t("mac_retry_tip"));
+                } else {
+                    ui.label(t("get_permission"));
+                    ui.label(t("linux_group_tip"));
+                    ui.label(t("linux_polkit_tip"));
                 }
-            });
-            
-            ui.add_space(10.0);
-            
-            // 自动切换开关
-            ui.horizontal(|ui| {
-                if ui.checkbox(&mut self.config.auto_switch, "自动切换配置").changed() {
-                    let _ = self.config.save();
+                ui.add_space(10.0);
+                if ui.button(t("got_it")).clicked() {
+                    close = true;
                 }
             });
-            
-            ui.add_space(10.0);
-            self.render_config_list(ui);
-            ui.add_space(10.0);
-            self.render_edit_panel(ui);
-            
-            // 状态消息
-            if !self.status_message.is_empty() {
-                ui.add_space(10.0);
-                ui.colored_label(egui::Color32::from_rgb(100, 200, 100), &self.status_message);
+
+        if close {
+            self.show_permission_guide = false;
+            self.config.first_run_completed = true;
+            // 密码设置完成或跳过时会一起保存
+            if !self.show_password_setup {
+                let _ = self.config.save();
             }
-        });
-        
-        self.render_add_dialog(ctx);
+        }
     }
-}
 
-impl NetworkSwitcherApp {
-    fn render_config_list(&mut self, ui: &mut egui::Ui) {
-        ui.group(|ui| {
-            ui.horizontal(|ui| {
-                ui.label("已保存的配置");
-                if ui.button("➕ 添加").clicked() {
-                    self.show_add_dialog = true;
-                    self.new_config_name.clear();
-                    self.new_ssid_input = self.current_ssid.clone().unwrap_or_default();
-                    self.bind_router_mac = true;
+    fn render_reset_dhcp_dialog(&mut self, ctx: &egui::Context) {
+        if !self.confirm_reset_dhcp {
+            return;
+        }
+        let service = self.network_services[self.selected_service_idx].clone();
+        let mut reset = false;
+        let mut cancel = false;
+
+        egui::Window::new(t("reset_dhcp_title"))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(tf("reset_dhcp_message", &[&service]));
+                if self.config.auto_switch {
+                    ui.label(t("reset_dhcp_auto_switch"));
                 }
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    if ui.button(t("reset")).clicked() {
+                        reset = true;
+                    }
+                    if ui.button(t("cancel")).clicked() {
+                        cancel = true;
+                    }
+                });
             });
 
-            ui.separator();
-
-            // 按名称排序显示
-            let mut configs: Vec<_> = self.config.configs.values().cloned().collect();
-            configs.sort_by(|a, b| a.name.cmp(&b.name));
+        if reset {
+            self.confirm_reset_dhcp = false;
+            self.reset_to_dhcp(service);
+        } else if cancel {
+            self.confirm_reset_dhcp = false;
+        }
+    }
 
-            let current_ssid = self.current_ssid.clone();
-            let current_mac = self.current_router_mac.clone();
+    /// 把服务恢复为 DHCP 和自动 DNS，不经过配置匹配，并关闭自动切换
+    fn reset_to_dhcp(&mut self, service: String) {
+        let disabled_auto_switch = self.config.auto_switch;
+        if disabled_auto_switch {
+            self.config.auto_switch = false;
+            let _ = self.config.save();
+            if let Some(server) = &self.status_server {
+                server.update(|status| status.auto_switch = false);
+            }
+        }
 
-            for cfg in configs {
-                let target = cfg.target_service.as_deref().unwrap_or("Wi-Fi");
+        let cfg = NetworkConfig::new("DHCP".to_string(), String::new(), Some(service.clone()), ConfigType::Service, None);
+        let result = network::backend().apply_config(&service, &cfg);
+        network::log_apply(&cfg, "manual", &service, &result);
+        match result {
+            Ok(()) if disabled_auto_switch => {
+                self.push_toast(ToastLevel::Success, tf("reset_dhcp_done_auto_off", &[&service]))
+            }
+            Ok(()) => self.push_toast(ToastLevel::Success, tf("reset_dhcp_done", &[&service])),
+            Err(e) => self.push_toast(ToastLevel::Error, tf("reset_dhcp_failed", &[&error_icon(&e), &service, &e])),
+        }
+        // 之前应用的配置已失效，重新开启自动切换时可以再次应用
+        self.last_applied_key = None;
+        self.refresh_in_background(service, true);
+    }
 
-                // 检查是否匹配当前网络
-                let is_matching = cfg.matches_network(
-                    current_ssid.as_deref().unwrap_or(""),
-                    current_mac.as_deref()
-                );
+    fn render_conflict_dialog(&mut self, ctx: &egui::Context) {
+        let Some((cfg, services, automatic, detail)) = self.confirm_conflict.clone() else {
+            return;
+        };
+        let mut force = false;
+        let mut cancel = false;
 
+        egui::Window::new(t("address_in_use"))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(tf("address_in_use_message", &[&cfg.name, &services.join(", ")]));
+                ui.colored_label(WARNING_COLOR, &detail);
+                ui.label(t("address_in_use_warning"));
+                ui.add_space(10.0);
                 ui.horizontal(|ui| {
-                    // 显示配置名称和信息
-                    let display = cfg.display_name();
+                    if ui.button(t("apply_anyway")).clicked() {
+                        force = true;
+                    }
+                    if ui.button(t("cancel")).clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+
+        if force {
+            self.confirm_conflict = None;
+            // 跳过冲突检查重新应用（只影响这一次，不修改保存的配置）
+            let mut cfg = cfg;
+            cfg.check_ip_conflict = false;
+            self.apply_to_services(&cfg, services, automatic);
+        } else if cancel {
+            self.confirm_conflict = None;
+        }
+    }
+
+    fn render_delete_dialog(&mut self, ctx: &egui::Context) {
+        let Some(key) = self.confirm_delete.clone() else {
+            return;
+        };
+        let Some((name, locked)) = self.config.configs.get(&key).map(|c| (c.name.clone(), c.locked)) else {
+            self.confirm_delete = None;
+            return;
+        };
+        let has_password = self.config.has_password();
+
+        let mut delete = false;
+        let mut cancel = false;
 
-                    if is_matching {
-                        ui.strong(format!("● {}", display));
+        egui::Window::new(t("delete_config"))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(tf("delete_confirm", &[&name]));
+                // 锁定的配置需要输入启动密码，未设置密码时需要勾选确认
+                if locked {
+                    ui.colored_label(WARNING_COLOR, t("config_locked"));
+                    if has_password {
+                        self.render_unlock_password(ui);
                     } else {
-                        ui.label(format!("  {}", display));
+                        ui.checkbox(&mut self.confirm_delete_locked, t("confirm_delete_locked"));
+                    }
+                }
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    let enabled = !locked || has_password || self.confirm_delete_locked;
+                    if ui.add_enabled(enabled, egui::Button::new(t("delete"))).clicked() {
+                        delete = !locked || self.unlock_with_password();
                     }
+                    if ui.button(t("cancel")).clicked() {
+                        cancel = true;
+                    }
+                });
+            });
 
-                    ui.label(format!("→ {}", target));
+        if delete {
+            self.confirm_delete = None;
+            self.delete_config(&key);
+        } else if cancel {
+            self.confirm_delete = None;
+        }
+    }
+
+    /// 锁定配置的启动密码输入框，回车时验证
+    fn render_unlock_password(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut entered = false;
+        ui.horizontal(|ui| {
+            ui.label(t("password"));
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.unlock_password_input)
+                    .password(true)
+                    .desired_width(120.0)
+            );
+            entered = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+        });
+        if self.unlock_password_error {
+            ui.colored_label(egui::Color32::RED, t("wrong_password"));
+        }
+        entered
+    }
 
-                    if ui.button("编辑").clicked() {
-                        self.editing_config = Some(cfg.clone());
+    /// 解锁锁定的配置：设置了启动密码时验证输入的密码，否则直接通过
+    fn unlock_with_password(&mut self) -> bool {
+        let ok = !self.config.has_password() || self.config.verify_password(&self.unlock_password_input);
+        self.unlock_password_error = !ok;
+        self.unlock_password_input.clear();
+        ok
+    }
+
+    /// 删除配置，并保留一份副本以便撤销
+    fn delete_config(&mut self, key: &str) {
+        let Some(mut removed) = self.config.configs.get(key).cloned() else {
+            return;
+        };
+        // 删除时会清除钥匙串中的 WiFi 密码，先把密码取出来随副本保留
+        removed.wifi_password = removed.resolve_wifi_password();
+        removed.wifi_password_account = None;
+
+        self.config.remove_config(key);
+        let _ = self.config.save();
+        self.recently_deleted = Some((removed, Instant::now()));
+    }
+
+    /// 同步冲突：逐个选择用对方的配置覆盖本地，还是保留本地
+    fn render_sync_conflict_window(&mut self, ctx: &egui::Context) {
+        let Some(conflicts) = &mut self.sync_conflicts else {
+            return;
+        };
+
+        let mut confirm = false;
+        let mut cancel = false;
+        egui::Window::new(t("sync_conflicts"))
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(t("sync_conflicts_message"));
+                ui.add_space(5.0);
+                for (remote, use_remote) in conflicts.iter_mut() {
+                    let local = self.config.configs.get(&remote.config_key());
+                    let changes = local.map(|local| local.diff(remote)).unwrap_or_default();
+                    let response = ui.checkbox(use_remote, &remote.name);
+                    if !changes.is_empty() {
+                        let detail: Vec<String> = changes
+                            .iter()
+                            .map(|d| format!("{}: {} → {}", d.field.label(), d.from, d.to))
+                            .collect();
+                        response.on_hover_text(detail.join("\n"));
+                    }
+                }
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    if ui.button(t("ok")).clicked() {
+                        confirm = true;
+                    }
+                    if ui.button(t("keep_all_local")).clicked() {
+                        cancel = true;
                     }
+                });
+            });
 
-                    if ui.button("应用").clicked() {
-                        self.apply_config_internal(&cfg);
+        if confirm {
+            let mut replaced = 0;
+            for (remote, use_remote) in self.sync_conflicts.take().unwrap_or_default() {
+                if use_remote {
+                    // 钥匙串中的 WiFi 密码不随同步传输，保留本地的
+                    let mut remote = remote;
+                    if let Some(local) = self.config.configs.get(&remote.config_key()) {
+                        remote.wifi_password = local.wifi_password.clone();
+                        remote.wifi_password_account = local.wifi_password_account.clone();
+                        remote.last_applied = local.last_applied;
                     }
+                    self.config.add_config(remote);
+                    replaced += 1;
+                }
+            }
+            let _ = self.config.save();
+            self.push_toast(ToastLevel::Success, tf("replaced_from_peer", &[&replaced]));
+        } else if cancel {
+            self.sync_conflicts = None;
+        }
+    }
 
-                    let key = cfg.config_key();
-                    if ui.button("🗑").clicked() {
-                        self.config.remove_config(&key);
-                        let _ = self.config.save();
+    /// 删除后的撤销提示，超时后丢弃副本
+    fn render_undo_delete(&mut self, ctx: &egui::Context) {
+        let Some((removed, deleted_at)) = &self.recently_deleted else {
+            return;
+        };
+        if deleted_at.elapsed() >= UNDO_DELETE_TIMEOUT {
+            self.recently_deleted = None;
+            return;
+        }
+
+        let name = removed.name.clone();
+        let mut undo = false;
+        egui::Area::new(egui::Id::new("undo_delete"))
+            .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -20.0))
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(tf("config_deleted", &[&name]));
+                        if ui.button(t("undo")).clicked() {
+                            undo = true;
+                        }
+                    });
+                });
+            });
+
+        if undo {
+            self.undo_delete();
+        } else {
+            // 到时间后需要重绘才能隐藏提示
+            ctx.request_repaint_after(Duration::from_millis(500));
+        }
+    }
+
+    /// 恢复刚删除的配置
+    fn undo_delete(&mut self) {
+        let Some((mut restored, _)) = self.recently_deleted.take() else {
+            return;
+        };
+        // 钥匙串不可用时密码仍保留在配置中
+        let _ = restored.store_wifi_password();
+        self.push_toast(ToastLevel::Success, tf("config_restored", &[&restored.name]));
+        self.config.add_config(restored);
+        let _ = self.config.save();
+    }
+
+    fn render_overwrite_dialog(&mut self, ctx: &egui::Context) {
+        if !self.confirm_overwrite {
+            return;
+        }
+        let Some(name) = self.editing_config.as_ref().map(|c| c.name.clone()) else {
+            self.confirm_overwrite = false;
+            return;
+        };
+
+        let suggested = self.unique_config_name(&name);
+        let mut overwrite = false;
+        let mut rename = false;
+        let mut cancel = false;
+
+        egui::Window::new(t("name_conflict"))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(tf("name_exists", &[&name]));
+                ui.label(t("overwrite_question"));
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    if ui.button(t("overwrite")).clicked() {
+                        overwrite = true;
+                    }
+                    if ui.button(tf("save_as", &[&suggested])).clicked() {
+                        rename = true;
+                    }
+                    if ui.button(t("cancel")).clicked() {
+                        cancel = true;
                     }
                 });
-            }
+            });
 
-            if self.config.configs.is_empty() {
-                ui.label("暂无保存的配置，点击「添加」创建新配置");
+        if overwrite {
+            self.commit_editing();
+        } else if rename {
+            if let Some(ref mut editing) = self.editing_config {
+                editing.name = suggested;
             }
-        });
+            self.commit_editing();
+        } else if cancel {
+            self.confirm_overwrite = false;
+        }
     }
 
     fn render_edit_panel(&mut self, ui: &mut egui::Ui) {
         let mut should_save = false;
         let mut should_cancel = false;
         let mut dns_to_remove: Option<usize> = None;
+        // (当前位置, 目标位置)
+        let mut dns_to_move: Option<(usize, usize)> = None;
         let mut dns_to_add: Option<String> = None;
+        let mut domain_to_remove: Option<usize> = None;
+        let mut domain_to_add: Option<String> = None;
+        let mut ssid_to_remove: Option<usize> = None;
+        let mut ssid_to_add: Option<String> = None;
+        let mut tag_to_remove: Option<usize> = None;
+        let mut tag_to_add: Option<String> = None;
+        let mut dns_to_test: Option<Vec<String>> = None;
+        let mut preset_to_save: Option<config::DnsPreset> = None;
+        let mut copy_from: Option<String> = None;
+        let mut should_undo = false;
+        let mut should_redo = false;
+        let can_undo = !self.edit_history.undo.is_empty();
+        let can_redo = !self.edit_history.redo.is_empty();
 
         let services_clone = self.network_services.clone();
+        let allow_hooks = self.config.allow_hooks;
+        // 可以复制路由器和 DNS 的其他配置：(key, 名称, 悬停说明)
+        let mut copy_sources: Vec<(String, String, String)> = self.config.configs
+            .values()
+            .filter(|c| c.router.is_some() || !c.dns_servers.is_empty())
+            .map(|c| {
+                let dns = if c.dns_servers.is_empty() { "-".to_string() } else { c.dns_servers.join(", ") };
+                let hint = tf(
+                    "copy_source_hint",
+                    &[&c.router.as_deref().unwrap_or("-"), &c.subnet_mask.as_deref().unwrap_or("-"), &dns],
+                );
+                (c.config_key(), c.name.clone(), hint)
+            })
+            .collect();
+        copy_sources.sort_by(|a, b| a.1.cmp(&b.1));
+
+        // 锁定的配置未解锁时只读，只能解锁或关闭
+        if self.editing_config.is_some() && !self.edit_unlocked {
+            let mut unlock = false;
+            ui.group(|ui| {
+                ui.horizontal(|ui| {
+                    ui.label(t("read_only"));
+                    if ui.button(t("close")).clicked() {
+                        should_cancel = true;
+                    }
+                });
+                let entered = self.config.has_password() && self.render_unlock_password(ui);
+                if ui.button(t("unlock")).clicked() || entered {
+                    unlock = true;
+                }
+            });
+            if unlock {
+                self.edit_unlocked = self.unlock_with_password();
+            }
+        }
+        let read_only = !self.edit_unlocked;
 
         if let Some(ref mut editing) = self.editing_config {
             ui.group(|ui| {
-                ui.label("📝 编辑配置");
+                if read_only {
+                    ui.disable();
+                }
+                ui.horizontal(|ui| {
+                    ui.label(t("edit_config"));
+                    if ui.add_enabled(can_undo, egui::Button::new("↩").small()).on_hover_text(t("undo_hint")).clicked() {
+                        should_undo = true;
+                    }
+                    if ui.add_enabled(can_redo, egui::Button::new("↪").small()).on_hover_text(t("redo_hint")).clicked() {
+                        should_redo = true;
+                    }
+                });
                 ui.separator();
 
-                // 配置名称
+                // 配置名称
+                ui.horizontal(|ui| {
+                    ui.label(t("name_label"));
+                    ui.text_edit_singleline(&mut editing.name);
+                    ui.checkbox(&mut editing.locked, t("lock"))
+                        .on_hover_text(t("lock_hint"));
+                });
+
+                // 备注
                 ui.horizontal(|ui| {
-                    ui.label("配置名称 / Name:");
-                    ui.text_edit_singleline(&mut editing.name);
+                    ui.label(t("notes_label"));
+                    ui.add(
+                        egui::TextEdit::multiline(&mut editing.notes)
+                            .desired_rows(2)
+                            .hint_text(t("notes_example"))
+                    );
+                });
+
+                // 标签
+                ui.horizontal_wrapped(|ui| {
+                    ui.label(t("tags_label"));
+                    for (i, tag) in editing.tags.iter().enumerate() {
+                        if ui.small_button(format!("🏷 {} ❌", tag)).clicked() {
+                            tag_to_remove = Some(i);
+                        }
+                    }
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut self.new_tag_input)
+                            .hint_text(t("tags_example"))
+                            .desired_width(80.0)
+                    );
+                    let entered = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if (ui.button(t("add_tag")).clicked() || entered) && !self.new_tag_input.trim().is_empty() {
+                        tag_to_add = Some(self.new_tag_input.trim().to_string());
+                    }
                 });
 
                 // 匹配的 SSID
                 ui.horizontal(|ui| {
-                    ui.label("匹配 SSID:");
+                    ui.label(t("match_ssid_label"));
                     ui.text_edit_singleline(&mut editing.ssid);
-                    ui.label("(留空表示不限)");
+                    ui.checkbox(&mut editing.ssid_is_pattern, t("regex"));
+                    if editing.router_mac.is_some() {
+                        ui.label(t("ssid_empty_router_only"));
+                    } else {
+                        ui.label(t("ssid_empty_any"));
+                    }
+                });
+                if let Some(Err(e)) = config::ssid_regex(&editing.ssid, editing.ssid_is_pattern) {
+                    ui.colored_label(egui::Color32::RED, tf("ssid_pattern_invalid", &[&e]));
+                }
+
+                // 同样匹配的其他 SSID
+                for (i, ssid) in editing.ssids.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("  + {}", ssid));
+                        if ui.button("❌").clicked() {
+                            ssid_to_remove = Some(i);
+                        }
+                    });
+                }
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut self.new_extra_ssid_input);
+                    if ui.button(t("add_ssid")).clicked() && !self.new_extra_ssid_input.trim().is_empty() {
+                        ssid_to_add = Some(self.new_extra_ssid_input.trim().to_string());
+                    }
+                    if let Some(ref current) = self.current_ssid
+                        && !current.starts_with("[有线]")
+                        && ui.button(t("add_current")).clicked()
+                    {
+                        ssid_to_add = Some(current.clone());
+                    }
                 });
 
                 // 路由器 MAC
                 ui.horizontal(|ui| {
-                    ui.label("路由器 MAC:");
+                    ui.label(t("router_mac_label"));
                     let mut mac = editing.router_mac.clone().unwrap_or_default();
                     if ui.text_edit_singleline(&mut mac).changed() {
                         editing.router_mac = if mac.is_empty() { None } else { Some(mac) };
                     }
-                    ui.label("(留空表示不限)");
+                    ui.label(t("router_mac_empty"));
+                });
+
+                // 接入点 BSSID
+                ui.horizontal(|ui| {
+                    ui.label("BSSID:");
+                    let mut bssid = editing.bssid.clone().unwrap_or_default();
+                    if ui.text_edit_singleline(&mut bssid).changed() {
+                        editing.bssid = if bssid.is_empty() { None } else { Some(bssid) };
+                    }
+                    if let Some(ref current) = self.current_bssid
+                        && ui.button(t("use_current")).clicked()
+                    {
+                        editing.bssid = Some(current.clone());
+                    }
                 });
 
+                // 有线网卡 MAC（区分相同型号的扩展坞）
+                if editing.config_type == ConfigType::Service {
+                    ui.horizontal(|ui| {
+                        ui.label(t("adapter_mac_label"));
+                        let mut mac = editing.adapter_mac.clone().unwrap_or_default();
+                        if ui.text_edit_singleline(&mut mac).changed() {
+                            editing.adapter_mac = if mac.is_empty() { None } else { Some(mac) };
+                        }
+                        if let Some(ref current) = self.current_adapter_mac
+                            && ui.button(t("use_current")).clicked()
+                        {
+                            editing.adapter_mac = Some(current.clone());
+                        }
+                    })
+                    .response
+                    .on_hover_text(t("adapter_mac_hint"));
+                }
+
+                // WiFi 密码（保存时写入钥匙串）
+                if editing.config_type == ConfigType::Wifi {
+                    ui.horizontal(|ui| {
+                        ui.label(t("wifi_password_label"));
+                        let mut password = editing.wifi_password.clone().unwrap_or_default();
+                        let response = ui.add(
+                            egui::TextEdit::singleline(&mut password)
+                                .password(true)
+                                .hint_text(t("keep_saved_password"))
+                        );
+                        if response.changed() {
+                            editing.wifi_password = if password.is_empty() { None } else { Some(password) };
+                        }
+                    });
+                    ui.checkbox(&mut editing.auto_join, t("auto_join"))
+                        .on_hover_text(t("auto_join_hint"));
+                }
+
                 // 自动应用开关
-                ui.checkbox(&mut editing.auto_apply, "🔄 自动应用 (连接此网络时自动使用此配置)");
+                ui.checkbox(&mut editing.auto_apply, t("auto_apply"));
+                ui.checkbox(&mut editing.apply_on_wired_loss, t("apply_on_wired_loss"))
+                    .on_hover_text(t("apply_on_wired_loss_hint"));
+
+                // 优先级
+                if editing.auto_apply {
+                    ui.horizontal(|ui| {
+                        ui.label(t("priority_label"));
+                        ui.add(egui::DragValue::new(&mut editing.priority));
+                        ui.label(t("priority_hint"));
+                    });
+
+                    // 定时规则
+                    let mut scheduled = editing.schedule.is_some();
+                    if ui.checkbox(&mut scheduled, t("schedule")).changed() {
+                        editing.schedule = scheduled.then(config::Schedule::default);
+                    }
+                    if let Some(ref mut schedule) = editing.schedule {
+                        ui.horizontal(|ui| {
+                            for (enabled, label) in schedule.days.iter_mut().zip(config::Schedule::day_labels()) {
+                                ui.checkbox(enabled, label);
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label(t("time_label"));
+                            ui.add(egui::TextEdit::singleline(&mut schedule.start).desired_width(50.0).hint_text("09:00"));
+                            ui.label("-");
+                            ui.add(egui::TextEdit::singleline(&mut schedule.end).desired_width(50.0).hint_text("18:00"));
+                        });
+                    }
+                }
 
                 ui.add_space(5.0);
 
                 // 目标网络服务选择
                 ui.horizontal(|ui| {
-                    ui.label("目标服务 / Target:");
+                    ui.label(t("target_label"));
                     let current_target = editing.target_service
                         .clone()
-                        .unwrap_or_else(|| "Wi-Fi".to_string());
+                        .unwrap_or_else(|| t("target_unset").to_string());
                     egui::ComboBox::from_id_salt("target_service_edit")
                         .selected_text(&current_target)
                         .show_ui(ui, |ui| {
@@ -566,71 +3651,427 @@ impl NetworkSwitcherApp {
                         });
                 });
 
+                // 多个目标服务（勾选后代替上面的单个目标）
+                ui.collapsing(t("multiple_targets"), |ui| {
+                    let mut targets = editing.target_services.clone().unwrap_or_default();
+                    let mut changed = false;
+                    for service in &services_clone {
+                        let mut selected = targets.contains(service);
+                        if ui.checkbox(&mut selected, service).changed() {
+                            changed = true;
+                            if selected {
+                                targets.push(service.clone());
+                            } else {
+                                targets.retain(|s| s != service);
+                            }
+                        }
+                    }
+                    if changed {
+                        editing.target_services = if targets.is_empty() { None } else { Some(targets) };
+                    }
+                    ui.label(t("targets_hint"));
+                });
+
                 ui.add_space(5.0);
-                ui.checkbox(&mut editing.use_dhcp, "使用 DHCP / Use DHCP");
+                // 从其他配置复制路由器、子网掩码和 DNS，名称和匹配条件不变
+                let editing_key = editing.config_key();
+                if copy_sources.iter().any(|(key, _, _)| *key != editing_key) {
+                    ui.horizontal(|ui| {
+                        egui::ComboBox::from_id_salt("copy_network_from")
+                            .selected_text(t("copy_network_from"))
+                            .show_ui(ui, |ui| {
+                                for (key, name, hint) in copy_sources.iter().filter(|(key, _, _)| *key != editing_key) {
+                                    if ui.selectable_label(false, name).on_hover_text(hint).clicked() {
+                                        copy_from = Some(key.clone());
+                                    }
+                                }
+                            });
+                    });
+                }
+
+                ui.checkbox(&mut editing.manage_ip, t("manage_ip"))
+                    .on_hover_text(t("manage_ip_hint"));
+                if editing.manage_ip {
+                    ui.checkbox(&mut editing.use_dhcp, t("use_dhcp"));
+
+                    if !editing.use_dhcp {
+                        ui.horizontal(|ui| {
+                            ui.label(t("ip_label"));
+                            let mut ip = editing.ip_address.clone().unwrap_or_default();
+                            if ui.text_edit_singleline(&mut ip).changed() {
+                                editing.ip_address = Some(ip);
+                            }
+                        });
+
+                        ui.horizontal(|ui| {
+                            ui.label(t("subnet_label"));
+                            let mut mask = editing.subnet_mask.clone().unwrap_or_default();
+                            if ui.text_edit_singleline(&mut mask).changed() {
+                                editing.subnet_mask = Some(mask);
+                            }
+                        });
+
+                        ui.horizontal(|ui| {
+                            ui.label(t("router_label"));
+                            let mut router = editing.router.clone().unwrap_or_default();
+                            if ui.text_edit_singleline(&mut router).changed() {
+                                editing.router = Some(router);
+                            }
+                        });
+                        if let Some(issue) = config::subnet_issue(editing) {
+                            ui.colored_label(WARNING_COLOR, format!("⚠️ {}", issue));
+                        }
+
+                        ui.checkbox(
+                            &mut editing.check_ip_conflict,
+                            t("check_ip_conflict"),
+                        );
+                    }
 
-                if !editing.use_dhcp {
                     ui.horizontal(|ui| {
-                        ui.label("IP 地址 / IP:");
-                        let mut ip = editing.ip_address.clone().unwrap_or_default();
-                        if ui.text_edit_singleline(&mut ip).changed() {
-                            editing.ip_address = Some(ip);
+                        ui.label("IPv6:");
+                        for mode in config::Ipv6Mode::ALL {
+                            ui.radio_value(&mut editing.ipv6_mode, mode, mode.label());
                         }
                     });
 
+                    // 只有手动模式才需要填写 IPv6 地址
+                    if editing.ipv6_mode == config::Ipv6Mode::Manual {
+                        ui.horizontal(|ui| {
+                            ui.label(t("ipv6_label"));
+                            let mut ipv6 = editing.ipv6_address.clone().unwrap_or_default();
+                            if ui.text_edit_singleline(&mut ipv6).changed() {
+                                editing.ipv6_address = if ipv6.is_empty() { None } else { Some(ipv6) };
+                            }
+                        });
+
+                        ui.horizontal(|ui| {
+                            ui.label(t("ipv6_prefix_label"));
+                            let mut prefix = editing.ipv6_prefix.map(|p| p.to_string()).unwrap_or_default();
+                            if ui.text_edit_singleline(&mut prefix).changed() {
+                                editing.ipv6_prefix = prefix.trim().parse().ok();
+                            }
+                        });
+
+                        ui.horizontal(|ui| {
+                            ui.label(t("ipv6_router_label"));
+                            let mut router = editing.ipv6_router.clone().unwrap_or_default();
+                            if ui.text_edit_singleline(&mut router).changed() {
+                                editing.ipv6_router = if router.is_empty() { None } else { Some(router) };
+                            }
+                        });
+                    }
+                }
+
+                // MTU（留空表示不修改）
+                ui.horizontal(|ui| {
+                    ui.label("MTU:");
+                    let mut mtu = editing.mtu.map(|m| m.to_string()).unwrap_or_default();
+                    if ui.text_edit_singleline(&mut mtu).changed() {
+                        editing.mtu = mtu.trim().parse().ok();
+                    }
+                    ui.label(t("leave_empty_unchanged"));
+                });
+
+                ui.add_space(5.0);
+                ui.checkbox(&mut editing.manage_dns, t("manage_dns"))
+                    .on_hover_text(t("manage_dns_hint"));
+                if editing.manage_dns {
                     ui.horizontal(|ui| {
-                        ui.label("子网掩码 / Subnet:");
-                        let mut mask = editing.subnet_mask.clone().unwrap_or_default();
-                        if ui.text_edit_singleline(&mut mask).changed() {
-                            editing.subnet_mask = Some(mask);
+                        ui.label("DNS:");
+                        for mode in config::DnsMode::ALL {
+                            ui.radio_value(&mut editing.dns_mode, mode, mode.label());
                         }
                     });
 
+                    // 只有手动模式才需要填写 DNS 服务器
+                    if editing.dns_mode == config::DnsMode::Manual {
+                        ui.horizontal(|ui| {
+                            ui.label(t("dns_servers_label"));
+                            if !editing.dns_servers.is_empty() && ui.button(t("test_dns")).clicked() {
+                                dns_to_test = Some(editing.dns_servers.clone());
+                            }
+                        });
+
+                        let dns_results = self.dns_test_results.lock().map(|r| r.clone()).unwrap_or_default();
+                        // 排在前面的服务器优先使用
+                        let dns_count = editing.dns_servers.len();
+                        for (i, dns) in editing.dns_servers.iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                ui.label(dns);
+                                if ui.add_enabled(i > 0, egui::Button::new("⬆")).on_hover_text(t("move_up")).clicked() {
+                                    dns_to_move = Some((i, i - 1));
+                                }
+                                if ui.add_enabled(i + 1 < dns_count, egui::Button::new("⬇")).on_hover_text(t("move_down")).clicked() {
+                                    dns_to_move = Some((i, i + 1));
+                                }
+                                if ui.button("❌").clicked() {
+                                    dns_to_remove = Some(i);
+                                }
+                                match dns_results.iter().find(|(server, _)| server == dns) {
+                                    Some((_, None)) => {
+                                        ui.spinner();
+                                    }
+                                    Some((_, Some(Ok(elapsed)))) => {
+                                        ui.colored_label(
+                                            egui::Color32::from_rgb(80, 180, 80),
+                                            format!("✅ {} ms", elapsed.as_millis()),
+                                        );
+                                    }
+                                    Some((_, Some(Err(e)))) => {
+                                        ui.colored_label(egui::Color32::RED, format!("❌ {}", e));
+                                    }
+                                    None => {}
+                                }
+                            });
+                        }
+
+                        ui.horizontal(|ui| {
+                            ui.text_edit_singleline(&mut self.new_dns_input);
+                            if ui.button(t("add_dns")).clicked() && !self.new_dns_input.is_empty() {
+                                dns_to_add = Some(self.new_dns_input.clone());
+                            }
+                        });
+
+                        // DNS 预设：选择后追加到当前列表
+                        ui.horizontal(|ui| {
+                            egui::ComboBox::from_id_salt("dns_preset_select")
+                                .selected_text(t("dns_presets"))
+                                .show_ui(ui, |ui| {
+                                    for preset in &self.config.dns_presets {
+                                        let label = format!("{} ({})", preset.name, preset.servers.join(", "));
+                                        if ui.selectable_label(false, label).clicked() {
+                                            for server in &preset.servers {
+                                                if !editing.dns_servers.contains(server) {
+                                                    editing.dns_servers.push(server.clone());
+                                                }
+                                            }
+                                        }
+                                    }
+                                });
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.new_preset_name)
+                                    .desired_width(100.0)
+                                    .hint_text(t("preset_name"))
+                            );
+                            if ui.button(t("save_preset")).clicked()
+                                && !self.new_preset_name.trim().is_empty()
+                                && !editing.dns_servers.is_empty()
+                            {
+                                preset_to_save = Some(config::DnsPreset {
+                                    name: self.new_preset_name.trim().to_string(),
+                                    servers: editing.dns_servers.clone(),
+                                });
+                            }
+                        });
+                    }
+
+                    ui.add_space(5.0);
+                    ui.label(t("search_domains_label"));
+
+                    for (i, domain) in editing.search_domains.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.label(domain);
+                            if ui.button("❌").clicked() {
+                                domain_to_remove = Some(i);
+                            }
+                        });
+                    }
+
                     ui.horizontal(|ui| {
-                        ui.label("路由器 / Router:");
-                        let mut router = editing.router.clone().unwrap_or_default();
-                        if ui.text_edit_singleline(&mut router).changed() {
-                            editing.router = Some(router);
+                        ui.text_edit_singleline(&mut self.new_search_domain_input);
+                        if ui.button(t("add_search_domain")).clicked() && !self.new_search_domain_input.is_empty() {
+                            domain_to_add = Some(self.new_search_domain_input.clone());
                         }
                     });
                 }
 
+                // 应用顺序
                 ui.add_space(5.0);
-                ui.label("DNS 服务器 / DNS Servers:");
+                ui.horizontal(|ui| {
+                    ui.label(t("apply_order_label"));
+                    let mut order = editing.apply_steps_order.join(",");
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut order)
+                            .hint_text(network::DEFAULT_APPLY_STEPS.join(", "))
+                    );
+                    if response.changed() {
+                        editing.apply_steps_order = if order.trim().is_empty() {
+                            Vec::new()
+                        } else {
+                            order.split(',').map(|s| s.to_string()).collect()
+                        };
+                    }
+                });
 
-                for (i, dns) in editing.dns_servers.iter().enumerate() {
+                // 高级：WINS 和 DoH
+                ui.collapsing(t("advanced"), |ui| {
                     ui.horizontal(|ui| {
-                        ui.label(dns);
-                        if ui.button("❌").clicked() {
-                            dns_to_remove = Some(i);
+                        ui.label("WINS:");
+                        let mut wins = editing.wins_servers.join(",");
+                        let response = ui.add(
+                            egui::TextEdit::singleline(&mut wins).hint_text(t("leave_empty_unchanged_hint"))
+                        );
+                        if response.changed() {
+                            editing.wins_servers = wins
+                                .split(',')
+                                .map(|s| s.trim().to_string())
+                                .filter(|s| !s.is_empty())
+                                .collect();
                         }
                     });
-                }
 
-                ui.horizontal(|ui| {
-                    ui.text_edit_singleline(&mut self.new_dns_input);
-                    if ui.button("添加 DNS").clicked() && !self.new_dns_input.is_empty() {
-                        dns_to_add = Some(self.new_dns_input.clone());
+                    let mut use_doh = editing.doh_template.is_some();
+                    if ui.checkbox(&mut use_doh, t("doh")).on_hover_text(t("doh_hint")).changed() {
+                        // 取消已填写的地址时先清空（应用时移除描述文件），再次取消才是不修改
+                        let had_template = editing.doh_template.as_deref().is_some_and(|template| !template.trim().is_empty());
+                        editing.doh_template = (use_doh || had_template).then(String::new);
+                    }
+                    if let Some(ref mut template) = editing.doh_template {
+                        ui.horizontal(|ui| {
+                            ui.label("DoH:");
+                            ui.add(
+                                egui::TextEdit::singleline(template)
+                                    .hint_text("https://dns.example/dns-query")
+                            );
+                        });
+                    }
+
+                    // 802.1X 等企业网络的描述文件，手动应用时安装
+                    ui.horizontal(|ui| {
+                        ui.label(t("eap_profile_label"));
+                        let mut path = editing.profile_path.clone().unwrap_or_default();
+                        let response = ui.add(
+                            egui::TextEdit::singleline(&mut path).hint_text(t("leave_empty_no_install"))
+                        );
+                        if response.changed() {
+                            editing.profile_path = (!path.trim().is_empty()).then_some(path);
+                        }
+                        if ui.button(t("choose")).clicked()
+                            && let Some(file) = rfd::FileDialog::new()
+                                .add_filter("mobileconfig", &["mobileconfig"])
+                                .pick_file()
+                        {
+                            editing.profile_path = Some(file.display().to_string());
+                        }
+                    });
+
+                    // 应用成功后执行的 shell 命令，设置中允许后才会执行
+                    ui.horizontal(|ui| {
+                        ui.label(t("post_apply_label"));
+                        let mut command = editing.post_apply_command.clone().unwrap_or_default();
+                        let response = ui.add(
+                            egui::TextEdit::singleline(&mut command).hint_text(t("post_apply_example"))
+                        )
+                        .on_hover_text(t("post_apply_hint"));
+                        if response.changed() {
+                            editing.post_apply_command = (!command.trim().is_empty()).then_some(command);
+                        }
+                    });
+                    if editing.post_apply_command.is_some() {
+                        if allow_hooks {
+                            ui.colored_label(WARNING_COLOR, t("post_apply_warning"));
+                        } else {
+                            ui.weak(t("post_apply_disabled"));
+                        }
+                    }
+                });
+
+                // 代理：留空的代理默认保持系统当前值
+                ui.collapsing(t("proxy_section"), |ui| {
+                    let proxies = [
+                        ("HTTP:", &mut editing.http_proxy, "proxy.example.com:8080"),
+                        ("HTTPS:", &mut editing.https_proxy, "proxy.example.com:8080"),
+                        ("PAC:", &mut editing.pac_url, "http://wpad.example.com/proxy.pac"),
+                    ];
+                    for (label, proxy, hint) in proxies {
+                        ui.horizontal(|ui| {
+                            ui.label(label);
+                            let mut value = proxy.clone().unwrap_or_default();
+                            if ui.add(egui::TextEdit::singleline(&mut value).hint_text(hint)).changed() {
+                                *proxy = (!value.trim().is_empty()).then(|| value.trim().to_string());
+                            }
+                        });
                     }
+                    ui.horizontal(|ui| {
+                        ui.label(t("proxy_exceptions_label"));
+                        let mut bypass = editing.proxy_bypass.join(",");
+                        let response = ui.add(
+                            egui::TextEdit::singleline(&mut bypass).hint_text("*.local,169.254/16")
+                        );
+                        if response.changed() {
+                            editing.proxy_bypass = bypass
+                                .split(',')
+                                .map(|s| s.trim().to_string())
+                                .filter(|s| !s.is_empty())
+                                .collect();
+                        }
+                    });
+                    ui.checkbox(&mut editing.clear_proxy, t("clear_proxy"))
+                        .on_hover_text(t("clear_proxy_hint"));
                 });
 
+                // 应用后检测连通性
+                ui.checkbox(
+                    &mut editing.probe_after_apply,
+                    t("probe_after_apply"),
+                );
+
                 ui.add_space(10.0);
                 ui.horizontal(|ui| {
-                    if ui.button("💾 保存").clicked() {
+                    if ui.button(t("save")).clicked() {
                         should_save = true;
                     }
-                    if ui.button("取消").clicked() {
+                    if ui.button(t("cancel")).clicked() {
                         should_cancel = true;
                     }
                 });
+
+                for err in &self.validation_errors {
+                    ui.colored_label(egui::Color32::RED, format!("❌ {}", err));
+                }
             });
         }
 
         // 处理延迟的操作
-        if let Some(idx) = dns_to_remove {
-            if let Some(ref mut editing) = self.editing_config {
-                editing.dns_servers.remove(idx);
+        if let Some(preset) = preset_to_save {
+            // 同名预设直接替换
+            self.config.dns_presets.retain(|p| p.name != preset.name);
+            self.push_toast(ToastLevel::Success, tf("preset_saved", &[&preset.name]));
+            self.config.dns_presets.push(preset);
+            self.new_preset_name.clear();
+            let _ = self.config.save();
+        }
+
+        if let Some(servers) = dns_to_test {
+            self.test_dns_in_background(servers);
+        }
+
+        if let Some(key) = copy_from
+            && let Some(source) = self.config.configs.get(&key)
+            && let Some(ref mut editing) = self.editing_config
+        {
+            editing.router = source.router.clone();
+            editing.subnet_mask = source.subnet_mask.clone();
+            editing.dns_servers = source.dns_servers.clone();
+            // 复制来的 DNS 需要手动模式才会生效
+            if !editing.dns_servers.is_empty() {
+                editing.dns_mode = config::DnsMode::Manual;
             }
+            self.push_toast(ToastLevel::Info, tf("copied_network_from", &[&source.name]));
+        }
+
+        if let Some((from, to)) = dns_to_move
+            && let Some(ref mut editing) = self.editing_config
+            && from.max(to) < editing.dns_servers.len()
+        {
+            editing.dns_servers.swap(from, to);
+        }
+
+        if let Some(idx) = dns_to_remove
+            && let Some(ref mut editing) = self.editing_config
+        {
+            editing.dns_servers.remove(idx);
         }
 
         if let Some(dns) = dns_to_add {
@@ -640,28 +4081,265 @@ impl NetworkSwitcherApp {
             self.new_dns_input.clear();
         }
 
-        if should_save {
-            if let Some(editing) = self.editing_config.take() {
-                self.config.add_config(editing);
-                let _ = self.config.save();
-                self.status_message = "配置已保存".to_string();
+        if let Some(idx) = ssid_to_remove
+            && let Some(ref mut editing) = self.editing_config
+        {
+            editing.ssids.remove(idx);
+        }
+
+        if let Some(ssid) = ssid_to_add {
+            if let Some(ref mut editing) = self.editing_config
+                && editing.ssid != ssid
+                && !editing.ssids.contains(&ssid)
+            {
+                editing.ssids.push(ssid);
+            }
+            self.new_extra_ssid_input.clear();
+        }
+
+        if let Some(idx) = tag_to_remove
+            && let Some(ref mut editing) = self.editing_config
+        {
+            editing.tags.remove(idx);
+        }
+
+        if let Some(tag) = tag_to_add {
+            if let Some(ref mut editing) = self.editing_config
+                && !editing.tags.contains(&tag)
+            {
+                editing.tags.push(tag);
+            }
+            self.new_tag_input.clear();
+        }
+
+        if let Some(idx) = domain_to_remove
+            && let Some(ref mut editing) = self.editing_config
+        {
+            editing.search_domains.remove(idx);
+        }
+
+        if let Some(domain) = domain_to_add {
+            if let Some(ref mut editing) = self.editing_config {
+                editing.search_domains.push(domain);
+            }
+            self.new_search_domain_input.clear();
+        }
+
+        if should_save
+            && let Some(ref editing) = self.editing_config
+        {
+            match config::validate(editing) {
+                Ok(_) => {
+                    self.validation_errors.clear();
+                    // 名称与另一个配置相同，需要确认是否覆盖
+                    if self.config.has_name_conflict(editing) {
+                        self.confirm_overwrite = true;
+                    } else {
+                        self.commit_editing();
+                    }
+                }
+                Err(errors) => {
+                    self.validation_errors = errors;
+                }
             }
         }
 
         if should_cancel {
-            self.editing_config = None;
+            self.cancel_editing();
+        }
+
+        if should_undo {
+            self.undo_edit();
+        } else if should_redo {
+            self.redo_edit();
+        } else if let Some(ref editing) = self.editing_config
+            && !ui.ctx().wants_keyboard_input()
+        {
+            // 文本框失去焦点后才记录，一次输入算作一批修改
+            self.edit_history.record(editing);
+        }
+    }
+
+    /// 冲突检查面板：列出会同时匹配的自动应用配置和不限网络的配置
+    fn render_conflicts(&self, ui: &mut egui::Ui, conflicts: &[config::AutoApplyConflict]) {
+        let name = |key: &str| self.config.configs.get(key).map(|c| c.name.clone()).unwrap_or_default();
+
+        ui.group(|ui| {
+            if conflicts.is_empty() {
+                ui.label(t("no_auto_apply_conflicts"));
+                return;
+            }
+            for conflict in conflicts {
+                let message = match conflict {
+                    config::AutoApplyConflict::Overlap(a, b) => {
+                        tf("overlap_conflict", &[&name(a), &name(b)])
+                    }
+                    config::AutoApplyConflict::MatchesAll(a) => {
+                        tf("matches_all_conflict", &[&name(a)])
+                    }
+                };
+                ui.colored_label(WARNING_COLOR, message);
+            }
+        });
+    }
+
+    /// 放弃正在编辑的配置
+    fn cancel_editing(&mut self) {
+        self.editing_config = None;
+        self.confirm_overwrite = false;
+        self.validation_errors.clear();
+    }
+
+    fn render_location_import_window(&mut self, ctx: &egui::Context) {
+        let Some(locations) = self.location_import.clone() else {
+            return;
+        };
+
+        let mut open = true;
+        let mut selected = None;
+        egui::Window::new(t("import_locations_title"))
+            .open(&mut open)
+            .resizable(false)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                if locations.is_empty() {
+                    ui.label(t("no_locations"));
+                    return;
+                }
+                // 系统默认只有一个「自动」位置
+                if locations.len() == 1 {
+                    ui.weak(t("only_default_location"));
+                }
+                ui.label(t("locations_import_hint"));
+                for location in &locations {
+                    ui.horizontal(|ui| {
+                        ui.label(location);
+                        if ui.button(t("import_button")).clicked() {
+                            selected = Some(location.clone());
+                        }
+                    });
+                }
+            });
+
+        if let Some(location) = selected {
+            self.import_location(&location);
+            self.location_import = None;
+        } else if !open {
+            self.location_import = None;
+        }
+    }
+
+    fn render_history_window(&mut self, ctx: &egui::Context) {
+        if !self.show_history {
+            return;
+        }
+
+        let mut open = true;
+        egui::Window::new(t("history_title"))
+            .open(&mut open)
+            .resizable(true)
+            .show(ctx, |ui| {
+                if self.history_entries.is_empty() {
+                    ui.label(t("no_history"));
+                    return;
+                }
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for entry in &self.history_entries {
+                        let trigger = match entry.trigger.as_str() {
+                            "auto" => t("trigger_auto"),
+                            "retry" => t("trigger_retry"),
+                            _ => t("trigger_manual"),
+                        };
+                        let text = format!(
+                            "{} [{}] {} -> {}",
+                            entry.timestamp, trigger, entry.config_name, entry.target_service
+                        );
+                        if entry.trigger == "retry" {
+                            ui.colored_label(
+                                WARNING_COLOR,
+                                format!("🔁 {} ({})", text, entry.error.as_deref().unwrap_or("")),
+                            );
+                        } else if entry.success {
+                            ui.label(format!("✅ {}", text));
+                        } else {
+                            ui.colored_label(
+                                egui::Color32::RED,
+                                format!("❌ {} ({})", text, entry.error.as_deref().unwrap_or("")),
+                            );
+                        }
+                    }
+                });
+            });
+
+        if !open {
+            self.show_history = false;
+        }
+    }
+
+    fn render_preview_window(&mut self, ctx: &egui::Context) {
+        let mut should_close = false;
+
+        if let Some((name, lines, diffs)) = &self.preview_commands {
+            egui::Window::new(format!("{}: {}", t("preview"), name))
+                .collapsible(false)
+                .resizable(true)
+                .show(ctx, |ui| {
+                    for (service, fields) in diffs {
+                        if fields.is_empty() {
+                            ui.label(tf("preview_unchanged", &[&service]));
+                            continue;
+                        }
+                        ui.label(tf("preview_changes", &[&service]));
+                        for diff in fields {
+                            ui.monospace(format!("{}: {} → {}", diff.field.label(), diff.from, diff.to));
+                        }
+                    }
+                    if !diffs.is_empty() {
+                        ui.separator();
+                    }
+                    ui.label(t("preview_commands"));
+                    ui.separator();
+                    for line in lines {
+                        ui.monospace(line);
+                    }
+                    ui.add_space(10.0);
+                    if ui.button(t("close")).clicked() {
+                        should_close = true;
+                    }
+                });
+        }
+
+        if should_close {
+            self.preview_commands = None;
+        }
+    }
+
+    /// 服务当前的配置，当前选中的服务使用后台刷新的结果
+    fn current_config_for(&self, service: &str) -> Option<NetworkConfig> {
+        if service == self.network_services[self.selected_service_idx] {
+            self.current_network_config.clone()
+        } else {
+            network::backend().current_config(service).ok()
         }
     }
 
+    /// 打开添加配置对话框，SSID 默认填入当前网络
+    fn open_add_dialog(&mut self) {
+        self.show_add_dialog = true;
+        self.new_config_name.clear();
+        self.new_ssid_input = self.current_ssid.clone().unwrap_or_default();
+        self.bind_router_mac = true;
+    }
+
     fn render_add_dialog(&mut self, ctx: &egui::Context) {
         if self.show_add_dialog {
-            egui::Window::new("添加新配置 / Add Config")
+            egui::Window::new(t("add_config"))
                 .collapsible(false)
                 .resizable(false)
                 .show(ctx, |ui| {
                     // 配置名称
                     ui.horizontal(|ui| {
-                        ui.label("配置名称 / Name:");
+                        ui.label(t("name_label"));
                         ui.text_edit_singleline(&mut self.new_config_name);
                     });
 
@@ -669,30 +4347,30 @@ impl NetworkSwitcherApp {
 
                     // 配置类型选择
                     ui.horizontal(|ui| {
-                        ui.label("类型 / Type:");
+                        ui.label(t("type_label"));
                         ui.radio_value(&mut self.add_config_type, ConfigType::Wifi, "📶 WiFi");
-                        ui.radio_value(&mut self.add_config_type, ConfigType::Service, "🔌 有线/服务");
+                        ui.radio_value(&mut self.add_config_type, ConfigType::Service, t("wired_service"));
                     });
 
                     ui.add_space(5.0);
 
                     // 匹配的 SSID
                     ui.horizontal(|ui| {
-                        ui.label("匹配 SSID:");
+                        ui.label(t("match_ssid_label"));
                         ui.text_edit_singleline(&mut self.new_ssid_input);
                     });
 
                     // 绑定路由器 MAC
-                    ui.checkbox(&mut self.bind_router_mac, "🔒 绑定路由器 MAC（精确匹配网络）");
-                    if self.bind_router_mac {
-                        if let Some(ref mac) = self.current_router_mac {
-                            ui.label(format!("   当前 MAC: {}", mac));
-                        }
+                    ui.checkbox(&mut self.bind_router_mac, t("bind_router_mac"));
+                    if self.bind_router_mac
+                        && let Some(ref mac) = self.current_router_mac
+                    {
+                        ui.label(tf("current_mac", &[&mac]));
                     }
 
                     // 目标服务选择
                     ui.horizontal(|ui| {
-                        ui.label("应用到服务:");
+                        ui.label(t("apply_to_service_label"));
                         egui::ComboBox::from_id_salt("add_service_select")
                             .selected_text(&self.network_services[self.add_service_idx])
                             .show_ui(ui, |ui| {
@@ -706,25 +4384,31 @@ impl NetworkSwitcherApp {
                     ui.horizontal(|ui| {
                         let can_add = !self.new_config_name.is_empty();
 
-                        if ui.button("从当前获取配置").clicked() && can_add {
+                        if ui.button(t("from_current")).clicked() && can_add {
                             let service = self.network_services[self.add_service_idx].clone();
                             let router_mac = if self.bind_router_mac {
                                 self.current_router_mac.clone()
                             } else {
                                 None
                             };
-                            let mut cfg = network::get_current_config(&service);
+                            let mut cfg = match network::backend().current_config(&service) {
+                                Ok(cfg) => cfg,
+                                Err(e) => {
+                                    self.push_toast(ToastLevel::Error, tf("get_current_failed", &[&error_icon(&e), &e]));
+                                    return;
+                                }
+                            };
                             cfg.name = self.new_config_name.clone();
                             cfg.ssid = self.new_ssid_input.clone();
                             cfg.router_mac = router_mac;
                             cfg.config_type = self.add_config_type.clone();
                             cfg.target_service = Some(service);
                             cfg.auto_apply = false;
-                            self.editing_config = Some(cfg);
+                            self.start_editing(cfg);
                             self.show_add_dialog = false;
                         }
 
-                        if ui.button("创建空白配置").clicked() && can_add {
+                        if ui.button(t("create_blank")).clicked() && can_add {
                             let service = self.network_services[self.add_service_idx].clone();
                             let router_mac = if self.bind_router_mac {
                                 self.current_router_mac.clone()
@@ -738,20 +4422,74 @@ impl NetworkSwitcherApp {
                                 self.add_config_type.clone(),
                                 router_mac
                             );
-                            self.editing_config = Some(cfg);
+                            self.start_editing(cfg);
                             self.show_add_dialog = false;
                         }
 
-                        if ui.button("取消").clicked() {
+                        if ui.button(t("cancel")).clicked() {
                             self.show_add_dialog = false;
                         }
                     });
 
                     if self.new_config_name.is_empty() {
-                        ui.colored_label(egui::Color32::RED, "⚠️ 请输入配置名称");
+                        ui.colored_label(egui::Color32::RED, t("name_required"));
                     }
                 });
         }
     }
 }
 
+/// 把时间戳格式化为相对时间，如 "3天前"
+fn format_relative_time(timestamp: i64, now: i64) -> String {
+    let elapsed = chrono::Duration::seconds(now - timestamp);
+    if elapsed.num_minutes() < 1 {
+        t("just_now").to_string()
+    } else if elapsed.num_hours() < 1 {
+        tf("minutes_ago", &[&elapsed.num_minutes()])
+    } else if elapsed.num_days() < 1 {
+        tf("hours_ago", &[&elapsed.num_hours()])
+    } else {
+        tf("days_ago", &[&elapsed.num_days()])
+    }
+}
+
+/// 应用界面主题，跟随系统时由 eframe 根据系统外观切换
+fn apply_theme(ctx: &egui::Context, theme: Theme) {
+    ctx.set_theme(match theme {
+        Theme::System => egui::ThemePreference::System,
+        Theme::Light => egui::ThemePreference::Light,
+        Theme::Dark => egui::ThemePreference::Dark,
+    });
+}
+
+/// 用默认浏览器打开网址
+fn open_in_browser(url: &str) {
+    let opener = if cfg!(target_os = "linux") { "xdg-open" } else { "open" };
+    let _ = std::process::Command::new(opener).arg(url).spawn();
+}
+
+/// 读取剪贴板中的文本（macOS 使用 pbpaste，Linux 使用 wl-paste 或 xclip）
+fn read_clipboard() -> Option<String> {
+    let commands: &[(&str, &[&str])] = if cfg!(target_os = "linux") {
+        &[("wl-paste", &["--no-newline"]), ("xclip", &["-o", "-selection", "clipboard"])]
+    } else {
+        &[("pbpaste", &[])]
+    };
+    commands.iter().find_map(|(cmd, args)| {
+        let output = std::process::Command::new(cmd).args(*args).output().ok()?;
+        output.status.success().then(|| String::from_utf8_lossy(&output.stdout).to_string())
+    })
+}
+
+/// 按错误类型选择状态栏图标
+fn error_icon(error: &NetworkError) -> &'static str {
+    match error.root() {
+        NetworkError::CommandNotFound(_) => "🔍",
+        NetworkError::PermissionDenied { .. } => "🔐",
+        NetworkError::Parse(_) => "❓",
+        NetworkError::InvalidConfig(_) => "⚠️",
+        NetworkError::AddressInUse { .. } => "⚠️",
+        NetworkError::ProbeFailed(_) => "↩️",
+        _ => "❌",
+    }
+}
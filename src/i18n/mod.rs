@@ -0,0 +1,715 @@
+//! 界面文字的多语言支持：按键查找当前语言的文字
+
+use std::env;
+use std::fmt;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+/// 界面语言
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Lang {
+    Zh,
+    En,
+}
+
+impl Lang {
+    pub const ALL: [Lang; 2] = [Lang::Zh, Lang::En];
+
+    /// 显示名称（始终用该语言本身显示）
+    pub fn label(self) -> &'static str {
+        match self {
+            Lang::Zh => "中文",
+            Lang::En => "English",
+        }
+    }
+}
+
+/// 当前界面语言（Lang 的序号）
+static LANG: AtomicU8 = AtomicU8::new(Lang::Zh as u8);
+
+/// 设置当前界面语言
+pub fn set_lang(lang: Lang) {
+    LANG.store(lang as u8, Ordering::Relaxed);
+}
+
+/// 当前界面语言
+pub fn lang() -> Lang {
+    match LANG.load(Ordering::Relaxed) {
+        n if n == Lang::En as u8 => Lang::En,
+        _ => Lang::Zh,
+    }
+}
+
+/// 系统语言：依次读取 LC_ALL、LC_MESSAGES、LANG，macOS 上再读取系统偏好的区域
+/// 中文区域使用中文，其他区域使用英文，无法判断时使用中文
+pub fn system_lang() -> Lang {
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty() && value != "C" && value != "POSIX")
+        .or_else(apple_locale);
+    match locale {
+        Some(locale) if !locale.starts_with("zh") => Lang::En,
+        _ => Lang::Zh,
+    }
+}
+
+/// macOS 系统设置中的区域（如 zh_CN、en_US），从访达启动的程序没有 LANG 环境变量
+#[cfg(target_os = "macos")]
+fn apple_locale() -> Option<String> {
+    let output = std::process::Command::new("defaults")
+        .args(["read", "-g", "AppleLocale"])
+        .output()
+        .ok()?;
+    let locale = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !locale.is_empty()).then_some(locale)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn apple_locale() -> Option<String> {
+    None
+}
+
+/// 当前语言下 key 对应的文字，没有翻译时返回 key 本身
+pub fn t(key: &'static str) -> &'static str {
+    let Some(&(_, zh, en)) = STRINGS.iter().find(|(k, _, _)| *k == key) else {
+        warn!("缺少翻译: {}", key);
+        return key;
+    };
+    match lang() {
+        Lang::Zh => zh,
+        Lang::En => en,
+    }
+}
+
+/// 当前语言下 key 对应的文字，依次用 args 替换其中的 {}
+pub fn tf(key: &'static str, args: &[&dyn fmt::Display]) -> String {
+    let mut parts = t(key).split("{}");
+    let mut text = parts.next().unwrap_or_default().to_string();
+    for (i, part) in parts.enumerate() {
+        if let Some(arg) = args.get(i) {
+            text.push_str(&arg.to_string());
+        }
+        text.push_str(part);
+    }
+    text
+}
+
+/// 翻译表：(key, 中文, 英文)
+const STRINGS: &[(&str, &str, &str)] = &[
+    // 启动密码
+    ("enter_password", "请输入密码", "Enter Password"),
+    ("password", "密码", "Password"),
+    ("unlock", "🔓 解锁", "🔓 Unlock"),
+    ("wrong_password", "❌ 密码错误", "❌ Wrong Password"),
+    ("set_password_title", "设置启动密码", "Set Password"),
+    ("new_password", "新密码", "New Password"),
+    ("confirm_password", "确认密码", "Confirm Password"),
+    ("set_password", "🔒 设置密码", "🔒 Set Password"),
+    ("skip", "跳过", "Skip"),
+    ("change_password", "修改密码", "Change Password"),
+    ("new_password_label", "新密码:", "New:"),
+    ("confirm_password_label", "确认密码:", "Confirm:"),
+    ("password_mismatch", "两次输入的密码不一致", "Passwords do not match"),
+    ("password_clear_hint", "(留空表示取消密码)", "(Leave empty to remove the password)"),
+    ("password_changed", "✅ 密码已修改", "✅ Password changed"),
+    ("password_removed", "✅ 已取消启动密码", "✅ Startup password removed"),
+    // 主界面
+    ("not_connected", "未连接", "Not Connected"),
+    ("history_button", "📜 历史", "📜 History"),
+    ("layout_full", "🗖 完整", "🗖 Full"),
+    ("layout_compact", "🗕 紧凑", "🗕 Compact"),
+    ("overwrite_config_file", "用当前配置覆盖", "Overwrite"),
+    (
+        "keychain_unavailable",
+        "⚠️ 钥匙串不可用，密码保存在配置文件中",
+        "⚠️ Keychain unavailable, secrets are stored in the config file",
+    ),
+    ("captive_portal", "⚠️ 需要登录门户", "⚠️ Captive portal"),
+    ("network_label", "网络连接:", "Network:"),
+    ("loading", "加载中...", "Loading..."),
+    ("device_label", "网卡:", "Device:"),
+    ("adapter_mac_label", "网卡 MAC:", "Adapter MAC:"),
+    ("service_label", "网络服务:", "Service:"),
+    ("subnet_label", "子网掩码:", "Subnet:"),
+    ("router_label", "路由器:", "Router:"),
+    ("mode_label", "模式:", "Mode:"),
+    ("static_mode", "静态", "Static"),
+    ("link_speed_label", "链路速度:", "Link:"),
+    ("public_ip_label", "公网 IP:", "Public IP:"),
+    ("app_title", "🌐 网络配置切换器", "🌐 Network Switcher"),
+    ("config_load_failed", "⚠️ 配置文件无法读取，当前使用默认配置，修改不会被保存", "⚠️ The config file could not be read; using defaults and changes will not be saved"),
+    ("auto_switch_paused", "⏸ 自动切换已暂停，{} 后恢复", "⏸ Auto switch paused, resumes in {}"),
+    ("resume_now", "▶ 立即恢复", "▶ Resume Now"),
+    ("open_login_page", "🌐 打开登录页面", "🌐 Open Login Page"),
+    ("current_status", "📡 当前状态", "📡 Current Status"),
+    ("refresh", "🔄 刷新", "🔄 Refresh"),
+    ("refresh_hint", "刷新", "Refresh"),
+    ("reset_dhcp_button", "🆘 恢复 DHCP", "🆘 Reset to DHCP"),
+    ("wifi_off", "📴 Wi-Fi 已关闭", "📴 Wi-Fi is off"),
+    ("router_mac_label", "路由器 MAC:", "Router MAC:"),
+    ("fetch_failed", "获取失败", "Unavailable"),
+    ("public_ip_changed", "已变化（之前 {}）", "Changed (was {})"),
+    ("online", "✅ 联网正常 ({}ms)", "✅ Online ({}ms)"),
+    ("router_only", "⚠️ 路由器可达，但无法访问互联网", "⚠️ Router reachable, but no internet access"),
+    ("router_only_hint", "路由器可达，但无法访问互联网", "Router reachable, but no internet access"),
+    ("offline", "❌ 网络不可用", "❌ Network unavailable"),
+    ("offline_hint", "网络不可用", "Network unavailable"),
+    ("disconnected", "📴 未连接", "📴 Not connected"),
+    ("select_config", "选择配置", "Select config"),
+    // 设置
+    ("settings", "⚙️ 设置", "⚙️ Settings"),
+    ("theme_label", "🎨 主题:", "🎨 Theme:"),
+    ("theme_system", "跟随系统", "System"),
+    ("theme_light", "浅色", "Light"),
+    ("theme_dark", "深色", "Dark"),
+    ("lang_label", "🌐 语言:", "🌐 Language:"),
+    ("lang_system", "跟随系统", "System"),
+    ("dns_ignore_label", "🚫 忽略的 DNS 前缀:", "🚫 Ignored DNS prefixes:"),
+    (
+        "dns_ignore_hint",
+        "读取实际使用的 DNS 时跳过这些前缀的地址，多个用逗号分隔\n默认的 198.18. 是 Clash、Surge 等代理工具接管 DNS 时使用的虚拟地址",
+        "Addresses with these prefixes are skipped when reading the DNS in use, separated by commas\nThe default 198.18. is the fake DNS used by proxy tools such as Clash and Surge",
+    ),
+    ("allow_hooks", "⚠️ 允许执行应用后命令", "⚠️ Allow post-apply commands"),
+    (
+        "allow_hooks_hint",
+        "应用配置后以当前用户身份执行配置中填写的 shell 命令，可以执行任意操作\n导入或同步来的配置也可能带有命令，只在信任所有配置来源时开启",
+        "After applying, runs the config's shell command as the current user, which can do anything\nImported or synced configs may carry commands too; only enable this if you trust every source of your configs",
+    ),
+    ("public_ip_enabled", "🌍 显示公网 IP", "🌍 Show public IP"),
+    (
+        "public_ip_hint",
+        "网络变化和应用配置后请求下面的地址查询公网 IP，会把你的 IP 暴露给该服务",
+        "Queries the address below for your public IP when the network changes or a config is applied; this reveals your IP to that service",
+    ),
+    ("dump_diagnostics", "🩺 导出诊断报告", "🩺 Export Diagnostics"),
+    (
+        "dump_diagnostics_hint",
+        "汇总网络状态、系统命令输出、配置（不含密码）和最近的日志，反馈问题时可以附上",
+        "Collects network status, system command output, configs (without passwords) and recent logs to attach to a bug report",
+    ),
+    ("diagnostics_saved", "✅ 诊断报告已保存到 {}", "✅ Diagnostics saved to {}"),
+    ("diagnostics_failed", "❌ 保存诊断报告失败: {}", "❌ Failed to save diagnostics: {}"),
+    ("auto_switch", "自动切换配置", "Auto switch"),
+    ("pause_auto_switch", "⏸ 暂停自动切换 {}分钟", "⏸ Pause auto switch for {} min"),
+    (
+        "pause_auto_switch_hint",
+        "调试网络时临时不自动应用配置，到期自动恢复，不修改保存的设置",
+        "Temporarily stop auto-applying while debugging the network; resumes automatically without changing saved settings",
+    ),
+    ("notifications", "🔔 通知", "🔔 Notifications"),
+    ("notify_manual", "手动应用也通知", "Notify on manual apply"),
+    ("status_socket", "🔌 状态 Socket", "🔌 Status socket"),
+    ("apply_retries_label", "🔁 命令失败重试次数:", "🔁 Retries on failure:"),
+    (
+        "apply_retries_hint",
+        "网卡刚连接时 networksetup 可能临时失败，权限或参数错误不会重试",
+        "networksetup may fail briefly right after an adapter connects; permission and argument errors are not retried",
+    ),
+    ("reachability_interval_label", "📶 联网检测间隔（秒）:", "📶 Connectivity check interval (s):"),
+    (
+        "reachability_interval_hint",
+        "定时 ping 公网地址和路由器，0 表示不检测以节省电量",
+        "Pings a public address and the router periodically; 0 disables checks to save power",
+    ),
+    ("disabled", "已关闭", "Off"),
+    ("public_ip_url_label", "查询地址:", "Lookup URL:"),
+    ("migrate_toml", "📝 改用 TOML 配置文件", "📝 Switch to TOML config"),
+    (
+        "migrate_toml_hint",
+        "生成可以写注释的 config.toml，之后读取和保存都使用它，原 config.json 保留不再使用",
+        "Creates a config.toml that can hold comments and uses it from now on; the old config.json is kept but no longer used",
+    ),
+    ("migrated", "✅ 已迁移到 {}", "✅ Migrated to {}"),
+    ("migrate_failed", "❌ 迁移失败: {}", "❌ Migration failed: {}"),
+    ("change_password_button", "🔑 修改密码", "🔑 Change Password"),
+    // 局域网同步与配置集
+    ("lan_sync", "🔄 局域网同步（提供本机配置）", "🔄 LAN sync (share this machine's configs)"),
+    (
+        "lan_sync_hint",
+        "在局域网端口上提供已保存的配置（不含 WiFi 密码），需要令牌才能读取",
+        "Serves saved configs (without Wi-Fi passwords) on a LAN port; reading them requires the token",
+    ),
+    ("port_label", "端口:", "Port:"),
+    ("token_label", "令牌:", "Token:"),
+    ("token", "令牌", "Token"),
+    ("copy_token", "复制令牌", "Copy token"),
+    ("regenerate_token", "重新生成令牌", "Regenerate token"),
+    ("pull_from_label", "⬇ 从其他机器拉取:", "⬇ Pull from another machine:"),
+    ("pull", "拉取", "Pull"),
+    ("sync_done", "✅ 同步完成: 新增 {} 个，{} 个与本地不同", "✅ Sync done: {} added, {} differ from local"),
+    ("pull_failed", "❌ 拉取失败: {}", "❌ Pull failed: {}"),
+    ("profile_label", "📁 配置集:", "📁 Profile:"),
+    ("new_profile_hint", "新配置集名称", "New profile name"),
+    ("new_profile", "➕ 新建", "➕ New"),
+    ("delete_profile", "删除「{}」", "Delete \"{}\""),
+    ("profile_deleted", "🗑 已删除配置集: {}", "🗑 Deleted profile: {}"),
+    ("delete_profile_failed", "❌ 删除配置集失败: {}", "❌ Failed to delete profile: {}"),
+    ("switch_profile_failed", "❌ 切换配置集失败: {}", "❌ Failed to switch profile: {}"),
+    ("profile_switched", "📁 已切换到配置集: {}", "📁 Switched to profile: {}"),
+    // 配置列表
+    ("filter_hint", "筛选名称 / SSID / 服务", "Filter by name / SSID / service"),
+    ("preview", "预览命令", "Preview"),
+    ("saved_configs", "已保存的配置", "Saved Configs"),
+    ("add", "➕ 添加", "➕ Add"),
+    ("export", "📤 导出", "📤 Export"),
+    ("import", "📥 导入", "📥 Import"),
+    ("paste_config", "📋 粘贴配置", "📋 Paste Config"),
+    ("import_locations", "🗺 从 macOS 位置导入", "🗺 Import macOS Locations"),
+    ("check_conflicts", "⚠️ 检查冲突", "⚠️ Check Conflicts"),
+    ("restore_backup", "🗂 恢复备份", "🗂 Restore Backup"),
+    ("no_backups", "暂无备份", "No backups"),
+    ("sort_by_name", "按名称", "By name"),
+    ("sort_by_recent", "按最近使用", "By recent"),
+    ("group_by_router", "🗂 按路由器分组", "🗂 Group by router"),
+    (
+        "group_by_router_hint",
+        "把绑定同一路由器 MAC 的配置归为一组，当前网络所在的组排在最前",
+        "Groups configs bound to the same router MAC; the current network's group comes first",
+    ),
+    ("all_tags", "全部标签", "All tags"),
+    ("current_context", "当前环境", "Current context"),
+    ("current_context_none", "当前环境: 无", "Current context: none"),
+    ("current_context_tags", "当前环境: {}", "Current context: {}"),
+    ("router_group", "📡 {} · {} 个配置", "📡 {} · {} configs"),
+    ("no_router_group", "未绑定路由器 · {} 个配置", "No router · {} configs"),
+    ("current_network_group", "● {} (当前网络)", "● {} (current network)"),
+    ("filter_by_tag", "按此标签筛选", "Filter by this tag"),
+    ("service_skipped_hint", "应用时会跳过此服务", "This service will be skipped when applying"),
+    ("last_applied", "上次应用: {}", "Last applied: {}"),
+    ("edit", "编辑", "Edit"),
+    ("duplicate", "复制", "Duplicate"),
+    ("copy_json", "复制 JSON", "Copy JSON"),
+    ("same_as_current", "与当前设置相同", "Same as current settings"),
+    ("apply_with_diff", "应用 ({})", "Apply ({})"),
+    ("diff_count", "与当前设置有 {} 处不同", "{} differences from current settings"),
+    ("update_from_current", "📥 用当前更新此配置", "📥 Update from current"),
+    ("drift_details", "当前设置与保存的不同:\n{}", "Current settings differ from saved:\n{}"),
+    ("locked_hint", "配置已锁定，请先在编辑面板中解锁", "This config is locked; unlock it in the edit panel first"),
+    ("join_and_apply", "连接并应用", "Join & Apply"),
+    ("no_configs", "暂无保存的配置，点击「添加」创建新配置", "No saved configs yet; click \"Add\" to create one"),
+    ("quick_save", "⚡ 一键保存当前网络", "⚡ Save Current Network"),
+    ("quick_save_hint", "用当前网络的名称、路由器 MAC 和 IP 设置创建配置", "Creates a config from the current network's name, router MAC and IP settings"),
+    ("updated_from_current", "✅ 已用当前设置更新配置: {}", "✅ Updated config from current settings: {}"),
+    ("copy_suffix", "{} (副本)", "{} (copy)"),
+    ("copy_suffix_n", "{} (副本 {})", "{} (copy {})"),
+    ("copied_json", "📋 已复制配置 JSON: {}", "📋 Copied config JSON: {}"),
+    ("copy_failed", "❌ 复制失败: {}", "❌ Copy failed: {}"),
+    ("clipboard_unavailable", "❌ 无法读取剪贴板", "❌ Could not read the clipboard"),
+    ("clipboard_invalid", "❌ 剪贴板内容不是有效的配置 JSON: {}", "❌ Clipboard does not contain a valid config JSON: {}"),
+    ("config_invalid", "❌ 配置无效: {}", "❌ Invalid config: {}"),
+    ("pasted", "✅ 已粘贴配置: {}", "✅ Pasted config: {}"),
+    ("backup_restored", "✅ 已恢复备份 #{}", "✅ Restored backup #{}"),
+    ("restore_backup_failed", "❌ 恢复备份失败: {}", "❌ Failed to restore backup: {}"),
+    ("exported", "✅ 已导出 {} 个配置", "✅ Exported {} configs"),
+    ("export_failed", "❌ 导出失败: {}", "❌ Export failed: {}"),
+    ("imported", "✅ 导入完成: 新增 {} 个，跳过 {} 个", "✅ Import done: {} added, {} skipped"),
+    ("import_failed", "❌ 导入失败: {}", "❌ Import failed: {}"),
+    ("read_location_failed", "{} 读取位置 {} 失败: {}", "{} Failed to read location {}: {}"),
+    ("location_empty", "⚠️ 位置 {} 中没有启用的网络服务", "⚠️ Location {} has no enabled network services"),
+    ("location_imported", "✅ 已从位置 {} 导入 {} 个配置", "✅ Location {}: imported {} configs"),
+    (
+        "password_plaintext",
+        "⚠️ 配置已保存，但 WiFi 密码以明文保存在配置文件中: {}",
+        "⚠️ Config saved, but the Wi-Fi password is stored in plain text in the config file: {}",
+    ),
+    ("config_saved", "✅ 配置已保存: {}", "✅ Config saved: {}"),
+    ("no_network", "❌ 当前没有连接网络", "❌ Not connected to a network"),
+    // 对话框
+    ("admin_required", "需要管理员权限", "Admin Required"),
+    ("reset_dhcp_title", "恢复 DHCP", "Reset to DHCP"),
+    ("address_in_use", "IP 地址冲突", "Address In Use"),
+    ("delete_config", "删除配置", "Delete Config"),
+    ("sync_conflicts", "同步冲突", "Sync Conflicts"),
+    ("name_conflict", "名称冲突", "Name Conflict"),
+    ("overwrite", "覆盖", "Overwrite"),
+    ("history_title", "历史", "History"),
+    ("add_config", "添加新配置", "Add Config"),
+    ("type_label", "类型:", "Type:"),
+    ("admin_retry_message", "应用配置「{}」到 {} 时权限不足。", "Not enough permission to apply \"{}\" to {}."),
+    ("admin_retry_question", "是否以管理员身份重试？系统会要求输入管理员密码。", "Retry as administrator? The system will ask for an administrator password."),
+    ("admin_retry", "🔐 以管理员身份重试", "🔐 Retry as Administrator"),
+    (
+        "not_admin_warning",
+        "当前用户不是管理员，应用配置（修改 IP、DNS 等）可能会因权限不足而失败。",
+        "The current user is not an administrator, so applying configs (changing IP, DNS, etc.) may fail for lack of permission.",
+    ),
+    ("get_permission", "可以通过以下方式获取权限：", "You can get permission by:"),
+    (
+        "mac_admin_tip",
+        "• 在「系统设置 → 用户与群组」中为当前用户打开「允许用户管理这台电脑」，然后重新登录",
+        "• Turning on \"Allow user to administer this computer\" for the current user in System Settings → Users & Groups, then logging in again",
+    ),
+    (
+        "mac_retry_tip",
+        "• 或在应用失败时选择「以管理员身份重试」，输入管理员的用户名和密码",
+        "• Or choosing \"Retry as Administrator\" when applying fails and entering an administrator's name and password",
+    ),
+    ("linux_group_tip", "• 把当前用户加入 sudo 或 wheel 组，然后重新登录", "• Adding the current user to the sudo or wheel group, then logging in again"),
+    (
+        "linux_polkit_tip",
+        "• 或让管理员通过 polkit 规则允许当前用户修改 NetworkManager 连接",
+        "• Or asking an administrator to allow the current user to modify NetworkManager connections with a polkit rule",
+    ),
+    ("got_it", "知道了", "Got it"),
+    ("reset_dhcp_message", "将把「{}」恢复为 DHCP 和自动 DNS。", "\"{}\" will be reset to DHCP and automatic DNS."),
+    ("reset_dhcp_auto_switch", "自动切换会被关闭，以免立即重新应用有问题的配置。", "Auto switch will be turned off so the broken config is not applied again right away."),
+    ("reset", "🆘 恢复", "🆘 Reset"),
+    ("reset_dhcp_done_auto_off", "✅ {} 已恢复为 DHCP，自动切换已关闭", "✅ {} reset to DHCP, auto switch turned off"),
+    ("reset_dhcp_done", "✅ {} 已恢复为 DHCP", "✅ {} reset to DHCP"),
+    ("reset_dhcp_failed", "{} {} 恢复 DHCP 失败: {}", "{} Failed to reset {} to DHCP: {}"),
+    ("address_in_use_message", "应用配置「{}」到 {} 前检测到冲突：", "Conflicts detected before applying \"{}\" to {}:"),
+    ("address_in_use_warning", "继续应用可能导致两台设备都无法正常联网。", "Applying anyway may leave both devices without a working connection."),
+    ("apply_anyway", "仍然应用", "Apply Anyway"),
+    ("delete_confirm", "确定删除配置「{}」？", "Delete config \"{}\"?"),
+    ("config_locked", "🔒 此配置已锁定", "🔒 This config is locked"),
+    ("confirm_delete_locked", "我确定要删除锁定的配置", "I want to delete this locked config"),
+    ("delete", "🗑 删除", "🗑 Delete"),
+    (
+        "sync_conflicts_message",
+        "以下配置在两台机器上内容不同，勾选的配置将使用对方的版本：",
+        "These configs differ between the two machines; checked ones will use the other machine's version:",
+    ),
+    ("ok", "✅ 确定", "✅ OK"),
+    ("keep_all_local", "保留全部本地配置", "Keep All Local"),
+    ("replaced_from_peer", "✅ 已用对方的版本覆盖 {} 个配置", "✅ Replaced {} configs with the other machine's version"),
+    ("config_deleted", "已删除配置「{}」", "Deleted config \"{}\""),
+    ("undo", "↩ 撤销", "↩ Undo"),
+    ("config_restored", "↩ 已恢复配置: {}", "↩ Restored config: {}"),
+    ("name_exists", "已存在名为「{}」的配置。", "A config named \"{}\" already exists."),
+    ("overwrite_question", "将覆盖同名配置，是否继续？", "The existing config will be overwritten. Continue?"),
+    ("save_as", "另存为「{}」", "Save as \"{}\""),
+    // 编辑配置
+    ("name_label", "配置名称:", "Name:"),
+    ("notes_label", "备注:", "Notes:"),
+    ("tags_label", "标签:", "Tags:"),
+    ("wifi_password_label", "WiFi 密码:", "Wi-Fi Password:"),
+    ("auto_join", "📡 范围内自动连接", "📡 Auto Join"),
+    ("priority_label", "优先级:", "Priority:"),
+    ("schedule", "⏰ 定时 (只在指定时间段内自动应用)", "⏰ Schedule (auto-apply only within a time range)"),
+    ("time_label", "时间:", "Time:"),
+    ("target_label", "目标服务:", "Target:"),
+    ("multiple_targets", "多个目标", "Multiple targets"),
+    ("target_unset", "未设置（使用当前选择的服务）", "Not set (uses the selected service)"),
+    ("manage_ip", "管理 IP 设置", "Manage IP"),
+    ("manage_ip_hint", "取消勾选后应用时不修改 IPv4 / IPv6 地址", "When unticked, applying leaves the IPv4 / IPv6 addresses untouched"),
+    ("use_dhcp", "使用 DHCP", "Use DHCP"),
+    ("ip_label", "IP 地址:", "IP:"),
+    ("ipv6_label", "IPv6 地址:", "IPv6:"),
+    ("ipv6_prefix_label", "IPv6 前缀:", "Prefix:"),
+    ("ipv6_router_label", "IPv6 路由器:", "IPv6 Router:"),
+    ("manage_dns", "管理 DNS 设置", "Manage DNS"),
+    ("dns_auto", "自动", "Auto"),
+    ("dns_manual", "手动", "Manual"),
+    ("dns_inherit", "保持不变", "Inherit"),
+    ("ipv6_unmanaged", "保持不变", "Unchanged"),
+    ("ipv6_automatic", "自动", "Automatic"),
+    ("ipv6_manual", "手动", "Manual"),
+    ("dns_servers_label", "DNS 服务器:", "DNS Servers:"),
+    ("test_dns", "🧪 测试解析", "🧪 Test"),
+    ("dns_presets", "预设", "Presets"),
+    ("search_domains_label", "搜索域:", "Search Domains:"),
+    ("apply_order_label", "应用顺序:", "Order:"),
+    ("advanced", "高级", "Advanced"),
+    ("proxy_section", "代理", "Proxy"),
+    ("apply_on_wired_loss", "🔌 有线断开时应用", "🔌 Apply on wired loss"),
+    (
+        "apply_on_wired_loss_hint",
+        "开启自动切换时，有线网络断开超过 10 秒且当前网络没有匹配的自动应用配置，自动应用此配置",
+        "With auto switch on, applies this config when the wired network has been down for 10 seconds and no auto-apply config matches the current network",
+    ),
+    ("post_apply_label", "应用后命令:", "Post-apply command:"),
+    ("post_apply_example", "如 dscacheutil -flushcache", "e.g. dscacheutil -flushcache"),
+    (
+        "post_apply_hint",
+        "通过 sh -c 执行，最长 30 秒；环境变量 NETWORK_SWITCHER_CONFIG、NETWORK_SWITCHER_SERVICE 为配置名称和网络服务",
+        "Runs with sh -c for at most 30 seconds; NETWORK_SWITCHER_CONFIG and NETWORK_SWITCHER_SERVICE hold the config name and service",
+    ),
+    ("post_apply_warning", "⚠️ 应用后会以当前用户身份执行此命令", "⚠️ This command runs as the current user after applying"),
+    ("post_apply_disabled", "设置中未允许执行应用后命令，不会执行", "Post-apply commands are not allowed in settings, so it will not run"),
+    ("post_apply_running", "执行应用后命令", "Running post-apply command"),
+    ("post_apply_failed", "⚠️ {} 应用后命令失败: {}", "⚠️ {} post-apply command failed: {}"),
+    ("copy_source_hint", "路由器: {}\n子网掩码: {}\nDNS: {}", "Router: {}\nSubnet: {}\nDNS: {}"),
+    ("read_only", "🔒 此配置已锁定，当前为只读", "🔒 This config is locked and read-only"),
+    ("edit_config", "📝 编辑配置", "📝 Edit Config"),
+    ("undo_hint", "撤销 (Cmd+Z)", "Undo (Cmd+Z)"),
+    ("redo_hint", "重做 (Cmd+Shift+Z)", "Redo (Cmd+Shift+Z)"),
+    ("lock", "🔒 锁定", "🔒 Locked"),
+    (
+        "lock_hint",
+        "锁定后编辑面板默认只读，删除前需要确认（设置了启动密码时需要输入密码）",
+        "Locked configs open read-only and need confirmation before deletion (or the startup password, if one is set)",
+    ),
+    ("notes_example", "如：客户 A 机房，需要固定 IP", "e.g. Client A server room, needs a static IP"),
+    ("tags_example", "如：家、公司", "e.g. home, office"),
+    ("add_tag", "添加标签", "Add Tag"),
+    ("match_ssid_label", "匹配 SSID:", "Match SSID:"),
+    ("regex", "正则", "Regex"),
+    ("ssid_empty_router_only", "(留空时只按路由器 MAC 匹配，支持 * ? 通配符)", "(Leave empty to match by router MAC only; * ? wildcards supported)"),
+    ("ssid_empty_any", "(留空表示不限，支持 * ? 通配符)", "(Leave empty to match any; * ? wildcards supported)"),
+    ("ssid_pattern_invalid", "❌ SSID 匹配规则无效: {}", "❌ Invalid SSID pattern: {}"),
+    ("add_ssid", "添加 SSID", "Add SSID"),
+    ("add_current", "添加当前", "Add Current"),
+    ("router_mac_empty", "(留空表示不限，可以不填 SSID 只按 MAC 匹配)", "(Leave empty to match any; SSID can be left empty to match by MAC only)"),
+    (
+        "adapter_mac_hint",
+        "只在这块有线网卡（如某个扩展坞）连接时自动应用，留空表示不限",
+        "Only auto-apply when this wired adapter (e.g. a particular dock) is connected; leave empty to match any",
+    ),
+    ("keep_saved_password", "留空则使用已保存的密码", "Leave empty to keep the saved password"),
+    (
+        "auto_join_hint",
+        "开启自动切换时，此 SSID 出现在信号范围内且优先级高于当前网络的配置，会自动连接并应用",
+        "With auto switch on, joins and applies this config when its SSID is in range and its priority is higher than the current network's config",
+    ),
+    ("auto_apply", "🔄 自动应用 (连接此网络时自动使用此配置)", "🔄 Auto apply (use this config when connected to this network)"),
+    ("priority_hint", "(多个配置同时匹配时，数值大的优先)", "(When several configs match, the higher value wins)"),
+    ("targets_hint", "(勾选的服务会代替上面的单个目标)", "(Checked services replace the single target above)"),
+    ("copy_network_from", "📋 从其他配置复制路由器和 DNS", "📋 Copy router and DNS from another config"),
+    ("check_ip_conflict", "🔎 应用前检查 IP 是否已被占用 (约增加 1 秒)", "🔎 Check whether the IP is in use before applying (adds about 1 second)"),
+    ("manage_dns_hint", "取消勾选后应用时不修改 DNS 服务器和搜索域", "When unticked, applying leaves the DNS servers and search domains untouched"),
+    ("move_up", "上移", "Move up"),
+    ("move_down", "下移", "Move down"),
+    ("add_dns", "添加 DNS", "Add DNS"),
+    ("preset_name", "预设名称", "Preset name"),
+    ("save_preset", "保存为预设", "Save as Preset"),
+    ("add_search_domain", "添加搜索域", "Add Search Domain"),
+    ("leave_empty_unchanged_hint", "留空表示不修改", "Leave empty to keep unchanged"),
+    ("doh", "DNS-over-HTTPS (应用时安装描述文件，留空则移除)", "DNS-over-HTTPS (installs a profile when applying; leave empty to remove)"),
+    (
+        "doh_hint",
+        "取消勾选已填写的地址会清空它，应用时移除之前安装的描述文件；地址为空时取消勾选表示不修改",
+        "Unticking a filled-in address clears it so applying removes the installed profile; unticking an empty one leaves DoH untouched",
+    ),
+    ("eap_profile_label", "802.1X 描述文件:", "802.1X profile:"),
+    ("leave_empty_no_install", "留空表示不安装", "Leave empty to skip"),
+    ("choose", "选择…", "Choose…"),
+    ("proxy_exceptions_label", "例外:", "Exceptions:"),
+    ("clear_proxy", "应用时关闭未设置的代理", "Turn off unset proxies when applying"),
+    ("clear_proxy_hint", "默认不修改留空的代理，以免覆盖公司下发的代理设置", "By default empty proxies are left alone so managed company proxy settings are not overwritten"),
+    (
+        "probe_after_apply",
+        "🩺 应用后检测连通性 (ping 路由器并解析域名，失败时自动回滚)",
+        "🩺 Check connectivity after applying (ping the router and resolve a domain; roll back on failure)",
+    ),
+    ("preset_saved", "✅ 已保存 DNS 预设: {}", "✅ Saved DNS preset: {}"),
+    ("copied_network_from", "📋 已复制「{}」的路由器和 DNS", "📋 Copied router and DNS from \"{}\""),
+    // 冲突检查、位置导入、历史和预览
+    ("no_auto_apply_conflicts", "✅ 自动应用的配置之间没有冲突", "✅ No conflicts between auto-apply configs"),
+    ("overlap_conflict", "⚠️ {} 和 {} 会在同一网络上同时匹配，只会应用其中一个", "⚠️ {} and {} both match the same network; only one will be applied"),
+    ("matches_all_conflict", "⚠️ {} 没有设置 SSID，会在任何网络上匹配", "⚠️ {} has no SSID and matches any network"),
+    ("import_locations_title", "从 macOS 位置导入", "Import macOS Locations"),
+    ("no_locations", "没有找到 macOS 位置", "No macOS locations found"),
+    (
+        "only_default_location",
+        "除默认位置外没有自定义位置，可以导入默认位置的当前设置",
+        "There are no custom locations besides the default one; you can import the default location's current settings",
+    ),
+    ("locations_import_hint", "每个启用的网络服务会导入为一个配置：", "Each enabled network service is imported as a config:"),
+    ("import_button", "导入", "Import"),
+    ("no_history", "暂无应用记录", "No history yet"),
+    ("trigger_auto", "自动", "Auto"),
+    ("trigger_retry", "重试", "Retry"),
+    ("trigger_manual", "手动", "Manual"),
+    ("preview_unchanged", "{}: 与当前设置相同", "{}: same as current settings"),
+    ("preview_changes", "{} 将发生以下变化：", "{} will change as follows:"),
+    ("preview_commands", "应用此配置将依次执行以下命令：", "Applying this config runs these commands in order:"),
+    ("wired_service", "🔌 有线/服务", "🔌 Wired/Service"),
+    ("bind_router_mac", "🔒 绑定路由器 MAC（精确匹配网络）", "🔒 Bind router MAC (match this network exactly)"),
+    ("current_mac", "   当前 MAC: {}", "   Current MAC: {}"),
+    ("apply_to_service_label", "应用到服务:", "Apply to service:"),
+    ("from_current", "从当前获取配置", "From Current Settings"),
+    ("create_blank", "创建空白配置", "Create Blank"),
+    ("name_required", "⚠️ 请输入配置名称", "⚠️ Please enter a config name"),
+    ("just_now", "刚刚", "just now"),
+    ("minutes_ago", "{}分钟前", "{} min ago"),
+    ("hours_ago", "{}小时前", "{} h ago"),
+    ("days_ago", "{}天前", "{} d ago"),
+    // 通用
+    ("save", "💾 保存", "💾 Save"),
+    ("cancel", "取消", "Cancel"),
+    ("close", "关闭", "Close"),
+    ("apply", "应用", "Apply"),
+    ("use_current", "使用当前", "Use Current"),
+    ("leave_empty_unchanged", "(留空表示不修改)", "(Leave empty to keep unchanged)"),
+    ("save_failed", "❌ 保存失败: {}", "❌ Save failed: {}"),
+    ("get_current_failed", "{} 无法获取当前配置: {}", "{} Failed to read current settings: {}"),
+    // 应用结果
+    ("config_restored_from", "⚠️ 配置文件损坏，已从 {} 恢复", "⚠️ Config file was corrupted, restored from {}"),
+    ("socket_failed", "❌ 无法创建状态 socket: {}", "❌ Failed to create the status socket: {}"),
+    ("sync_server_failed", "❌ 无法启动局域网同步: {}", "❌ Failed to start LAN sync: {}"),
+    ("targets_unavailable", "❌ 无法应用 {}: 目标服务不可用（{}）", "❌ Cannot apply {}: target services unavailable ({})"),
+    ("skipped_unavailable", "⚠️ 已跳过不可用的服务: {}", "⚠️ Skipped unavailable services: {}"),
+    ("apply_busy", "⏳ 正在应用其他配置，请稍候", "⏳ Another config is being applied, please wait"),
+    ("installing_profile", "安装描述文件", "Installing profile"),
+    ("verifying", "校验配置", "Verifying"),
+    ("not_effective", "⚠️ {} 已应用但 {} 未生效", "⚠️ {} applied but {} did not take effect"),
+    ("apply_failed", "{} {} 应用失败: {}", "{} Failed to apply {}: {}"),
+    ("install_profile_failed", "{} 安装描述文件失败: {}", "{} Failed to install profile: {}"),
+    ("probe_failed", "⚠️ {} 连通性检测失败: {}（未回滚）", "⚠️ {} connectivity check failed: {} (not rolled back)"),
+    ("applied_probed", "✅ 已应用配置: {} -> {}（连通性检测通过）", "✅ Applied {} -> {} (connectivity check passed)"),
+    ("applied", "✅ 已应用配置: {} -> {}", "✅ Applied {} -> {}"),
+    ("applied_notification", "已应用配置: {} -> {}", "Applied {} -> {}"),
+    ("join_failed", "❌ 连接 {} 失败: {}", "❌ Failed to join {}: {}"),
+    ("auto_join_failed", "❌ 自动连接 {} 失败: {}", "❌ Failed to auto-join {}: {}"),
+    ("applying", "正在应用配置...", "Applying..."),
+    // 应用步骤和进度
+    ("step_ip", "设置 IP", "Set IP"),
+    ("step_ipv6", "设置 IPv6", "Set IPv6"),
+    ("step_dns", "设置 DNS", "Set DNS"),
+    ("step_search", "设置搜索域", "Set search domains"),
+    ("step_mtu", "设置 MTU", "Set MTU"),
+    ("step_wins", "设置 WINS", "Set WINS"),
+    ("step_doh", "设置 DoH", "Set DoH"),
+    ("step_proxy", "设置代理", "Set proxy"),
+    ("step_activate", "重新激活连接", "Reactivate connection"),
+    ("step_retry", "{}（第 {} 次重试）", "{} (retry {})"),
+    ("checking_ip_conflict", "检查 IP 冲突", "Checking for IP conflicts"),
+    ("reading_current_config", "读取当前配置", "Reading current settings"),
+    ("verify_read_failed", "无法读取当前配置", "Could not read current settings"),
+    ("probing_connectivity", "检测连通性", "Checking connectivity"),
+    ("rolling_back", "回滚到之前的配置", "Rolling back to the previous settings"),
+    // 网络状态
+    ("service_active", "🟢 已连接", "🟢 Connected"),
+    ("service_disconnected", "⚪ 未连接", "⚪ Disconnected"),
+    ("service_disabled", "⛔ 已停用", "⛔ Disabled"),
+    ("service_missing", "❓ 不存在", "❓ Missing"),
+    ("wifi_not_associated", "未连接", "Not connected"),
+    ("wifi_powered_off", "Wi-Fi 已关闭", "Wi-Fi off"),
+    ("wifi_hidden", "隐藏网络", "Hidden network"),
+    ("profile_installed", "✅ 描述文件已安装", "✅ Profile installed"),
+    (
+        "profile_pending",
+        "⏳ 描述文件已在系统设置中打开，请在\"描述文件\"中确认安装",
+        "⏳ The profile is open in System Settings; confirm the install under \"Profiles\"",
+    ),
+    ("profile_declined", "⚠️ 已取消授权，描述文件未安装", "⚠️ Authorization cancelled, the profile was not installed"),
+    ("profile_not_found", "描述文件不存在: {}", "Profile not found: {}"),
+    ("profile_macos_only", "描述文件只能在 macOS 上安装", "Profiles can only be installed on macOS"),
+    ("locations_unsupported", "当前系统不支持读取位置: {}", "Reading locations is not supported on this system: {}"),
+    ("unrecognized_response", "无法识别的响应: {}", "Unrecognized response: {}"),
+    ("ping_router_failed", "无法 ping 通路由器 {}", "Cannot ping the router {}"),
+    ("resolve_failed", "无法解析 {}: {}", "Cannot resolve {}: {}"),
+    ("no_resolve_result", "{} 没有解析结果", "{} did not resolve to any address"),
+    ("dig_failed", "无法执行 dig: {}", "Failed to run dig: {}"),
+    ("dns_lookup_failed", "解析失败", "Lookup failed"),
+    ("dns_no_answer", "没有返回结果", "No answer"),
+    // 配置字段
+    ("field_mode", "模式", "Mode"),
+    ("field_ip_address", "IP 地址", "IP address"),
+    ("field_subnet_mask", "子网掩码", "Subnet mask"),
+    ("field_router", "路由器", "Router"),
+    ("field_search_domains", "搜索域", "Search domains"),
+    ("field_http_proxy", "HTTP 代理", "HTTP proxy"),
+    ("field_https_proxy", "HTTPS 代理", "HTTPS proxy"),
+    ("field_proxy_bypass", "代理例外", "Proxy exceptions"),
+    ("day_mon", "一", "Mon"),
+    ("day_tue", "二", "Tue"),
+    ("day_wed", "三", "Wed"),
+    ("day_thu", "四", "Thu"),
+    ("day_fri", "五", "Fri"),
+    ("day_sat", "六", "Sat"),
+    ("day_sun", "日", "Sun"),
+    // 配置文件
+    ("err_config_unreadable_save", "配置文件无法读取，为避免覆盖原文件已停止保存", "The config file could not be read; saving is stopped so it is not overwritten"),
+    ("err_config_unreadable_migrate", "配置文件无法读取，无法迁移", "The config file could not be read, so it cannot be migrated"),
+    ("err_already_toml", "已经在使用 config.toml", "config.toml is already in use"),
+    ("err_profile_name_invalid", "配置集名称无效: {}", "Invalid profile name: {}"),
+    ("err_profile_corrupted", "配置集 {} 已损坏: {}", "Profile {} is corrupted: {}"),
+    ("err_delete_current_profile", "不能删除当前配置集", "The current profile cannot be deleted"),
+    ("err_not_json_object", "配置文件格式错误：顶层不是 JSON 对象", "Invalid config file: the top level is not a JSON object"),
+    ("err_keychain_unavailable", "钥匙串不可用", "The keychain is not available"),
+    // 配置校验
+    ("err_name_empty", "配置名称不能为空", "Config name cannot be empty"),
+    ("err_ssid_pattern", "SSID 匹配规则无效: {}（{}）", "Invalid SSID pattern: {} ({})"),
+    ("err_start_time", "开始时间格式错误（应为 HH:MM）: {}", "Invalid start time (expected HH:MM): {}"),
+    ("err_end_time", "结束时间格式错误（应为 HH:MM）: {}", "Invalid end time (expected HH:MM): {}"),
+    ("err_no_schedule_days", "定时规则至少需要选择一天", "Select at least one day for the schedule"),
+    ("err_static_missing", "静态 IP 需要填写{}", "{} is required for a static IP"),
+    ("err_static_invalid", "{}格式错误: {}", "{} is invalid: {}"),
+    ("subnet_not_contiguous", "子网掩码不连续", "Subnet mask is not contiguous"),
+    ("router_outside_subnet", "路由器不在子网内", "Router is outside the subnet"),
+    ("err_ipv6_missing", "手动 IPv6 需要填写地址", "Manual IPv6 needs an address"),
+    ("err_ipv6_invalid", "IPv6 地址格式错误: {}", "Invalid IPv6 address: {}"),
+    ("err_mtu_range", "MTU 超出范围: {}", "MTU out of range: {}"),
+    ("err_mtu_device_range", "MTU {} 超出设备允许范围 {}-{}", "MTU {} is outside the device's range {}-{}"),
+    ("err_ipv6_prefix_range", "IPv6 前缀长度超出范围: {}", "IPv6 prefix length out of range: {}"),
+    ("err_manual_dns_empty", "手动 DNS 需要至少填写一个服务器", "Manual DNS needs at least one server"),
+    ("err_dns_invalid", "DNS 格式错误: {}", "Invalid DNS server: {}"),
+    ("err_wins_invalid", "WINS 服务器格式错误: {}", "Invalid WINS server: {}"),
+    ("err_doh_https", "DoH 地址需要以 https:// 开头: {}", "DoH URL must start with https://: {}"),
+    ("err_proxy_invalid", "代理格式错误（应为 主机:端口）: {}", "Invalid proxy (expected host:port): {}"),
+    ("err_pac_url", "PAC 地址需要以 http:// 或 https:// 开头: {}", "PAC URL must start with http:// or https://: {}"),
+    ("err_profile_ext", "描述文件需要是 .mobileconfig 文件: {}", "Profile must be a .mobileconfig file: {}"),
+    // 网络错误
+    ("err_command_not_found", "找不到命令 {}", "Command not found: {}"),
+    ("err_needs_authorization", "修改网络设置需要管理员授权", "changing network settings needs administrator authorization"),
+    ("err_not_admin", "当前用户不是管理员", "the current user is not an administrator"),
+    (
+        "err_permission_denied",
+        "权限不足：{}。请以管理员身份运行本程序，或为当前用户授予管理员权限（{}）",
+        "Permission denied: {}. Run this app as an administrator, or give the current user administrator rights ({})",
+    ),
+    ("err_command_failed_code", "命令执行失败（退出码 {}）: {}", "Command failed (exit code {}): {}"),
+    ("err_command_failed", "命令执行失败: {}", "Command failed: {}"),
+    ("err_parse_output", "无法解析命令输出: {}", "Could not parse command output: {}"),
+    ("err_address_in_use", "IP 地址 {} 已被 MAC 为 {} 的设备占用", "IP address {} is already used by the device with MAC {}"),
+    ("err_probe_failed", "连通性检测失败: {}", "Connectivity check failed: {}"),
+    ("err_rolled_back", "{}（已回滚到之前的配置）", "{} (rolled back to the previous settings)"),
+    ("err_rollback_failed", "{}（回滚失败: {}）", "{} (rollback failed: {})"),
+    // 应用后命令和局域网同步
+    ("hook_spawn_failed", "无法执行: {}", "Failed to run: {}"),
+    ("hook_timeout", "超过 {} 秒未结束，已终止", "Killed after running for more than {} s"),
+    ("hook_signal", "信号", "signal"),
+    ("hook_exit_code", "退出码 {}: {}", "Exit code {}: {}"),
+    ("sync_invalid_address", "无效的地址 {}: {}", "Invalid address {}: {}"),
+    ("sync_unresolved", "无法解析地址 {}", "Cannot resolve address {}"),
+    ("sync_bad_response", "响应格式错误", "Malformed response"),
+    ("sync_parse_failed", "无法解析配置: {}", "Could not parse configs: {}"),
+    ("sync_bad_token", "令牌不正确", "Wrong token"),
+    ("sync_peer_status", "对方返回 {}", "Peer returned {}"),
+];
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use regex::Regex;
+
+    use super::STRINGS;
+
+    /// 界面代码中用到的 key 都要在翻译表中，缺少时 t() 每帧都会输出警告
+    #[test]
+    fn used_keys_exist() {
+        let sources = [
+            include_str!("../gui/mod.rs"),
+            include_str!("../config/mod.rs"),
+            include_str!("../network/mod.rs"),
+            include_str!("../network/error.rs"),
+            include_str!("../network/hook.rs"),
+            include_str!("../sync/mod.rs"),
+        ];
+        let call = Regex::new(r#"\btf?\(\s*"(\w+)""#).unwrap();
+        let known: HashSet<&str> = STRINGS.iter().map(|(key, _, _)| *key).collect();
+        let missing: Vec<&str> = sources
+            .iter()
+            .flat_map(|source| call.captures_iter(source))
+            .map(|caps| caps.get(1).unwrap().as_str())
+            .filter(|key| !known.contains(key))
+            .collect();
+        assert!(missing.is_empty(), "缺少翻译: {:?}", missing);
+    }
+
+    #[test]
+    fn keys_are_unique_and_placeholders_match() {
+        let mut seen = HashSet::new();
+        for (key, zh, en) in STRINGS {
+            assert!(seen.insert(key), "重复的 key: {}", key);
+            assert_eq!(zh.matches("{}").count(), en.matches("{}").count(), "{} 的占位符数量不一致", key);
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+/// 钥匙串中使用的服务名
+const KEYCHAIN_SERVICE: &str = "network-switcher";
+
+/// 系统钥匙串是否可用（security 命令存在且可执行），结果只检测一次
+pub fn is_available() -> bool {
+    static AVAILABLE: OnceLock<bool> = OnceLock::new();
+    *AVAILABLE.get_or_init(|| {
+        Command::new("security")
+            .arg("list-keychains")
+            .output()
+            .is_ok_and(|output| output.status.success())
+    })
+}
+
+/// 保存密码到钥匙串（已存在时覆盖）
+/// -w 放在最后时 security 会提示输入两次密码，从标准输入写入，避免密码出现在命令行参数中被其他进程看到
+pub fn set_password(account: &str, password: &str) -> Result<(), String> {
+    let mut child = Command::new("security")
+        .args(["add-generic-password", "-U", "-s", KEYCHAIN_SERVICE, "-a", account, "-w"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("无法执行 security: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(format!("{password}\n{password}\n").as_bytes())
+            .map_err(|e| format!("无法写入密码: {}", e))?;
+    }
+    let output = child.wait_with_output().map_err(|e| format!("无法执行 security: {}", e))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// 从钥匙串读取密码，不存在或无法访问时返回 None
+pub fn get_password(account: &str) -> Option<String> {
+    let password = run_security(&["find-generic-password", "-s", KEYCHAIN_SERVICE, "-a", account, "-w"]).ok()?;
+    let password = password.trim_end_matches('\n');
+    if password.is_empty() { None } else { Some(password.to_string()) }
+}
+
+/// 从钥匙串删除密码，不存在时忽略
+pub fn delete_password(account: &str) {
+    let _ = run_security(&["delete-generic-password", "-s", KEYCHAIN_SERVICE, "-a", account]);
+}
+
+/// 执行 security 命令，返回标准输出
+fn run_security(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("security")
+        .args(args)
+        .output()
+        .map_err(|e| format!("无法执行 security: {}", e))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
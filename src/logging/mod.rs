@@ -0,0 +1,81 @@
+//! 运行日志：输出到标准错误，同时写入配置目录下的 app.log
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use log::LevelFilter;
+
+use crate::config::AppConfig;
+
+/// app.log 超过这个大小时轮转为 app.log.1（只保留一份旧日志）
+const MAX_LOG_SIZE: u64 = 1024 * 1024;
+
+/// 日志文件路径（与 config.json 同目录）
+pub fn log_path() -> PathBuf {
+    AppConfig::config_path().with_file_name("app.log")
+}
+
+/// 初始化日志，默认记录本程序 info 及以上级别
+/// 设置了 RUST_LOG 时按 RUST_LOG 过滤，verbose 为 true 时本程序的日志提高到 debug
+pub fn init(verbose: bool) {
+    let env = env_logger::Env::default().default_filter_or("network_switcher=info");
+    let mut builder = env_logger::Builder::from_env(env);
+    if verbose {
+        builder.filter_module("network_switcher", LevelFilter::Debug);
+    }
+    builder.target(env_logger::Target::Pipe(Box::new(TeeWriter::open(log_path()))));
+    let _ = builder.try_init();
+}
+
+/// 同时写入标准错误和日志文件，日志文件无法打开时只写标准错误
+struct TeeWriter {
+    path: PathBuf,
+    file: Option<File>,
+    size: u64,
+}
+
+impl TeeWriter {
+    fn open(path: PathBuf) -> Self {
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path).ok();
+        let size = file.as_ref().and_then(|f| f.metadata().ok()).map_or(0, |m| m.len());
+        let mut writer = Self { path, file, size };
+        if writer.size > MAX_LOG_SIZE {
+            writer.rotate();
+        }
+        writer
+    }
+
+    /// 当前日志改名为 app.log.1，重新打开一个空的 app.log
+    fn rotate(&mut self) {
+        self.file = None;
+        let _ = fs::rename(&self.path, self.path.with_extension("log.1"));
+        self.file = OpenOptions::new().create(true).append(true).open(&self.path).ok();
+        self.size = 0;
+    }
+}
+
+impl Write for TeeWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let _ = io::stderr().write_all(buf);
+        if self.size + buf.len() as u64 > MAX_LOG_SIZE {
+            self.rotate();
+        }
+        if let Some(file) = self.file.as_mut()
+            && file.write_all(buf).is_ok()
+        {
+            self.size += buf.len() as u64;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if let Some(file) = self.file.as_mut() {
+            file.flush()?;
+        }
+        io::stderr().flush()
+    }
+}
@@ -1,14 +1,40 @@
+mod cli;
 mod config;
 mod gui;
+mod i18n;
+mod keychain;
+mod logging;
 mod network;
+mod notify;
+mod status;
+mod sync;
+mod tray;
 
 use eframe::egui;
 
 fn main() -> eframe::Result<()> {
+    // --verbose 可以和其他参数一起使用，去掉后剩下的参数交给命令行模式处理
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let verbose = args.iter().any(|arg| arg == "--verbose" || arg == "-v");
+    args.retain(|arg| arg != "--verbose" && arg != "-v");
+    logging::init(verbose);
+
+    // 带参数时以命令行模式运行，不启动 GUI
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+
+    // 恢复上次的窗口大小和位置
+    let app_config = config::AppConfig::load();
+    let mut viewport = egui::ViewportBuilder::default()
+        .with_inner_size(app_config.window_size())
+        .with_min_inner_size(config::MIN_WINDOW_SIZE);
+    if let Some(position) = app_config.window_position() {
+        viewport = viewport.with_position(position);
+    }
+
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_inner_size([500.0, 600.0])
-            .with_min_inner_size([400.0, 400.0]),
+        viewport,
         ..Default::default()
     };
 
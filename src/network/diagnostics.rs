@@ -0,0 +1,69 @@
+//! 诊断报告：汇总网络标识、系统命令输出、配置和日志，便于反馈问题时附上
+
+use std::fmt::Write;
+use std::fs;
+
+use super::{backend, format_command, get_network_identity, runner};
+use crate::config::AppConfig;
+use crate::logging;
+
+/// 报告中包含的日志行数
+const LOG_TAIL_LINES: usize = 200;
+
+/// 生成诊断报告（纯文本）
+/// 配置中的启动密码、同步令牌和 WiFi 密码会被去掉，DNS、IP 等设置原样保留
+pub fn diagnostics_report() -> String {
+    let mut report = String::new();
+    let _ = writeln!(report, "# Network Switcher 诊断报告");
+    let _ = writeln!(report, "版本: {}", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(report, "时间: {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S"));
+    let _ = writeln!(report, "系统: {} {}", std::env::consts::OS, std::env::consts::ARCH);
+
+    let _ = writeln!(report, "\n## 网络标识\n{:#?}", get_network_identity(true));
+
+    let _ = writeln!(report, "\n## 命令输出");
+    for argv in backend().diagnostic_commands() {
+        let args: Vec<&str> = argv[1..].iter().map(String::as_str).collect();
+        let _ = writeln!(report, "\n$ {}", format_command(&argv));
+        match runner::run(&argv[0], &args) {
+            Ok(output) => {
+                report.push_str(output.stdout.trim_end());
+                report.push('\n');
+                if !output.stderr.trim().is_empty() {
+                    let _ = writeln!(report, "[stderr] {}", output.stderr.trim_end());
+                }
+                if !output.success() {
+                    let _ = writeln!(report, "[退出码] {:?}", output.code);
+                }
+            }
+            Err(e) => {
+                let _ = writeln!(report, "[执行失败] {}", e);
+            }
+        }
+    }
+
+    let _ = writeln!(report, "\n## 配置（已去除密码）");
+    match serde_json::to_string_pretty(&AppConfig::load().redacted()) {
+        Ok(json) => report.push_str(&json),
+        Err(e) => {
+            let _ = write!(report, "[无法序列化配置] {}", e);
+        }
+    }
+    report.push('\n');
+
+    let _ = writeln!(report, "\n## 日志（最后 {} 行）", LOG_TAIL_LINES);
+    match fs::read_to_string(logging::log_path()) {
+        Ok(log) => {
+            let lines: Vec<&str> = log.lines().collect();
+            for line in &lines[lines.len().saturating_sub(LOG_TAIL_LINES)..] {
+                report.push_str(line);
+                report.push('\n');
+            }
+        }
+        Err(e) => {
+            let _ = writeln!(report, "[无法读取日志] {}", e);
+        }
+    }
+
+    report
+}
@@ -0,0 +1,87 @@
+use std::fmt;
+use std::io;
+
+use crate::i18n::{t, tf};
+
+/// 网络操作的错误
+#[derive(Debug)]
+pub enum NetworkError {
+    /// 找不到命令（如 networksetup / nmcli 不存在）
+    CommandNotFound(String),
+    /// 权限不足，admin 表示当前用户是否属于管理员组
+    PermissionDenied { admin: bool, detail: String },
+    /// 命令执行失败，code 为退出码（被信号终止时为空）
+    CommandFailed { code: Option<i32>, stderr: String },
+    /// 无法解析命令输出
+    Parse(String),
+    /// 配置本身不合法（如 MTU 超出设备允许范围）
+    InvalidConfig(String),
+    /// 静态 IP 已被其他设备占用，mac 为占用该地址的设备
+    AddressInUse { ip: String, mac: String },
+    /// 应用成功但之后的连通性检测失败
+    ProbeFailed(String),
+    /// 应用失败后已尝试回滚，rollback_error 为回滚本身的错误
+    RolledBack {
+        error: Box<NetworkError>,
+        rollback_error: Option<Box<NetworkError>>,
+    },
+}
+
+impl NetworkError {
+    /// 执行命令时的 IO 错误
+    pub fn from_io(command: &str, err: io::Error) -> Self {
+        if err.kind() == io::ErrorKind::NotFound {
+            NetworkError::CommandNotFound(command.to_string())
+        } else {
+            NetworkError::CommandFailed { code: None, stderr: err.to_string() }
+        }
+    }
+
+    /// 是否像是可以重试的临时错误（如网卡刚连接时系统配置服务忙）
+    /// 权限、参数等错误重试也不会成功，不算临时错误
+    pub fn is_transient(&self) -> bool {
+        let NetworkError::CommandFailed { code: Some(_), stderr } = self else {
+            return false;
+        };
+        let stderr = stderr.to_lowercase();
+        ["busy", "timed out", "timeout", "try again", "temporarily", "unable to commit", "be completed", "not ready"]
+            .iter()
+            .any(|pattern| stderr.contains(pattern))
+    }
+
+    /// 去掉回滚信息后的原始错误，用于区分错误类型
+    pub fn root(&self) -> &NetworkError {
+        match self {
+            NetworkError::RolledBack { error, .. } => error.root(),
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetworkError::CommandNotFound(command) => write!(f, "{}", tf("err_command_not_found", &[command])),
+            NetworkError::PermissionDenied { admin, detail } => {
+                let reason = if *admin { t("err_needs_authorization") } else { t("err_not_admin") };
+                write!(f, "{}", tf("err_permission_denied", &[&reason, detail]))
+            }
+            NetworkError::CommandFailed { code: Some(code), stderr } => {
+                write!(f, "{}", tf("err_command_failed_code", &[code, &stderr.trim()]))
+            }
+            NetworkError::CommandFailed { code: None, stderr } => write!(f, "{}", tf("err_command_failed", &[&stderr.trim()])),
+            NetworkError::Parse(message) => write!(f, "{}", tf("err_parse_output", &[message])),
+            NetworkError::InvalidConfig(message) => write!(f, "{}", message),
+            NetworkError::AddressInUse { ip, mac } => write!(f, "{}", tf("err_address_in_use", &[ip, mac])),
+            NetworkError::ProbeFailed(message) => write!(f, "{}", tf("err_probe_failed", &[message])),
+            NetworkError::RolledBack { error, rollback_error: None } => {
+                write!(f, "{}", tf("err_rolled_back", &[error]))
+            }
+            NetworkError::RolledBack { error, rollback_error: Some(rollback_error) } => {
+                write!(f, "{}", tf("err_rollback_failed", &[error, rollback_error]))
+            }
+        }
+    }
+}
+
+impl std::error::Error for NetworkError {}
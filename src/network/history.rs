@@ -0,0 +1,83 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::NetworkError;
+use crate::config::{AppConfig, NetworkConfig};
+
+/// 一条配置应用记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplyLogEntry {
+    /// 本地时间，格式 "2024-01-01 14:32:05"
+    pub timestamp: String,
+    pub config_name: String,
+    pub target_service: String,
+    /// 触发方式："manual"、"auto"，或 "retry" 表示某个步骤失败后的重试
+    pub trigger: String,
+    pub success: bool,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// 历史记录文件路径（与 config.json 同目录）
+pub fn history_path() -> PathBuf {
+    AppConfig::config_path().with_file_name("history.log")
+}
+
+/// 追加一条应用记录（每行一个 JSON），写入失败时静默忽略
+pub fn log_apply(config: &NetworkConfig, trigger: &str, target_service: &str, result: &Result<(), NetworkError>) {
+    append_entry(ApplyLogEntry {
+        timestamp: now(),
+        config_name: config.name.clone(),
+        target_service: target_service.to_string(),
+        trigger: trigger.to_string(),
+        success: result.is_ok(),
+        error: result.as_ref().err().map(|e| e.to_string()),
+    });
+}
+
+/// 记录一次步骤重试，error 为导致重试的错误
+pub fn log_retry(config: &NetworkConfig, target_service: &str, step: &str, attempt: u32, error: &NetworkError) {
+    append_entry(ApplyLogEntry {
+        timestamp: now(),
+        config_name: config.name.clone(),
+        target_service: target_service.to_string(),
+        trigger: "retry".to_string(),
+        success: false,
+        error: Some(format!("{}第 {} 次重试: {}", step, attempt, error)),
+    });
+}
+
+fn now() -> String {
+    chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+fn append_entry(entry: ApplyLogEntry) {
+    let Ok(line) = serde_json::to_string(&entry) else {
+        return;
+    };
+
+    let path = history_path();
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(&path) {
+        let _ = writeln!(file, "{}", line);
+    }
+}
+
+/// 读取最近的 limit 条记录，最新的在前
+pub fn read_apply_log(limit: usize) -> Vec<ApplyLogEntry> {
+    let Ok(content) = fs::read_to_string(history_path()) else {
+        return Vec::new();
+    };
+
+    content
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .take(limit)
+        .collect()
+}
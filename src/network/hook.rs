@@ -0,0 +1,135 @@
+//! 应用配置成功后执行配置中的自定义命令（需要在设置中允许）
+
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+
+use crate::config::NetworkConfig;
+use crate::i18n::{t, tf};
+
+/// 是否允许执行应用后命令（来自 AppConfig::allow_hooks）
+static ALLOW_HOOKS: AtomicBool = AtomicBool::new(false);
+
+/// 应用后命令的最长执行时间，超时后终止，避免卡住切换
+const HOOK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 设置是否允许执行应用后命令
+pub fn set_allow_hooks(allow: bool) {
+    ALLOW_HOOKS.store(allow, Ordering::Relaxed);
+}
+
+/// 执行配置的应用后命令，输出写入日志
+/// 没有设置命令或设置中未允许时返回 None；成功时返回命令的输出，失败或超时时返回错误信息
+pub fn run_post_apply_hook(service: &str, config: &NetworkConfig) -> Option<Result<String, String>> {
+    let command = config.post_apply_command.as_deref().map(str::trim).filter(|c| !c.is_empty())?;
+    if !ALLOW_HOOKS.load(Ordering::Relaxed) {
+        warn!("配置 {} 设置了应用后命令，但设置中未允许执行，已跳过", config.name);
+        return None;
+    }
+
+    info!("执行应用后命令 {} -> {}: {}", config.name, service, command);
+    let result = run_hook(command, service, config, HOOK_TIMEOUT);
+    match &result {
+        Ok(output) => info!("应用后命令完成: {}", output),
+        Err(e) => warn!("应用后命令失败: {}", e),
+    }
+    Some(result)
+}
+
+/// 通过 sh -c 执行命令，超过 timeout 时终止
+/// 命令可以通过 NETWORK_SWITCHER_CONFIG、NETWORK_SWITCHER_SERVICE 环境变量读取配置名称和网络服务
+fn run_hook(command: &str, service: &str, config: &NetworkConfig, timeout: Duration) -> Result<String, String> {
+    let mut child = Command::new("sh")
+        .args(["-c", command])
+        .env("NETWORK_SWITCHER_CONFIG", &config.name)
+        .env("NETWORK_SWITCHER_SERVICE", service)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| tf("hook_spawn_failed", &[&e]))?;
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(tf("hook_timeout", &[&timeout.as_secs_f32()]));
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(50)),
+            Err(e) => return Err(e.to_string()),
+        }
+    };
+
+    // 命令在后台启动的进程（如重启的守护进程）可能一直占用输出，最多等到超时
+    let wait = |rx: mpsc::Receiver<String>| rx.recv_timeout(deadline.saturating_duration_since(Instant::now())).unwrap_or_default();
+    let output = [wait(stdout), wait(stderr)]
+        .iter()
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    if status.success() {
+        Ok(output)
+    } else {
+        let code = status.code().map(|c| c.to_string()).unwrap_or_else(|| t("hook_signal").to_string());
+        Err(tf("hook_exit_code", &[&code, &output]))
+    }
+}
+
+/// 在后台线程读取管道的全部输出，避免输出过多时填满管道导致命令阻塞
+fn read_in_background(pipe: Option<impl Read + Send + 'static>) -> mpsc::Receiver<String> {
+    let (tx, rx) = mpsc::channel();
+    if let Some(mut pipe) = pipe {
+        thread::spawn(move || {
+            let mut buf = Vec::new();
+            let _ = pipe.read_to_end(&mut buf);
+            let _ = tx.send(String::from_utf8_lossy(&buf).to_string());
+        });
+    }
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> NetworkConfig {
+        NetworkConfig { name: "办公室".to_string(), ..NetworkConfig::default() }
+    }
+
+    #[test]
+    fn hook_output_and_environment() {
+        let output = run_hook("echo $NETWORK_SWITCHER_CONFIG $NETWORK_SWITCHER_SERVICE; echo warn >&2", "Wi-Fi", &config(), HOOK_TIMEOUT);
+        assert_eq!(output.unwrap(), "办公室 Wi-Fi\nwarn");
+    }
+
+    #[test]
+    fn hook_failure_reports_exit_code() {
+        let error = run_hook("echo oops; exit 3", "Wi-Fi", &config(), HOOK_TIMEOUT).unwrap_err();
+        assert_eq!(error, "退出码 3: oops");
+    }
+
+    #[test]
+    fn hook_is_killed_after_timeout() {
+        let start = Instant::now();
+        let error = run_hook("sleep 5", "Wi-Fi", &config(), Duration::from_millis(200)).unwrap_err();
+        assert!(error.contains("已终止"));
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn hook_is_skipped_without_command() {
+        assert!(run_post_apply_hook("Wi-Fi", &config()).is_none());
+    }
+}
@@ -0,0 +1,583 @@
+use std::net::Ipv4Addr;
+use std::process::Command;
+
+use super::{NetworkBackend, NetworkError, ScanResult, ServiceState, SsidStatus, DEFAULT_MTU, DEFAULT_ROUTER, DEFAULT_STATIC_IP, DEFAULT_SUBNET_MASK, apply_steps, command_output, is_tunnel_interface};
+use crate::config::{DnsMode, Ipv6Mode, NetworkConfig};
+
+/// Linux 实现，基于 NetworkManager 的 nmcli
+/// "服务"对应 NetworkManager 的连接名（如 "Wired connection 1"）
+pub struct NmcliBackend;
+
+impl NetworkBackend for NmcliBackend {
+    fn current_ssid(&self) -> Option<String> {
+        // 格式: yes:NetworkName
+        nmcli_terse(&["-t", "-f", "ACTIVE,SSID", "device", "wifi"])
+            .into_iter()
+            .find(|fields| fields.first().map(String::as_str) == Some("yes"))
+            .and_then(|fields| fields.get(1).cloned())
+            .filter(|ssid| !ssid.is_empty())
+    }
+
+    fn ssid_status(&self) -> SsidStatus {
+        // 格式: disabled
+        let radio = Command::new("nmcli").args(["radio", "wifi"]).output();
+        if radio.is_ok_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "disabled") {
+            return SsidStatus::PoweredOff;
+        }
+
+        if let Some(ssid) = self.current_ssid() {
+            return SsidStatus::Connected(ssid);
+        }
+
+        // 格式: wifi:connected
+        let connected = nmcli_terse(&["-t", "-f", "TYPE,STATE", "device"]).iter().any(|fields| {
+            fields.first().map(String::as_str) == Some("wifi") && fields.get(1).map(String::as_str) == Some("connected")
+        });
+        if connected { SsidStatus::Hidden } else { SsidStatus::NotAssociated }
+    }
+
+    fn wifi_device(&self) -> Option<String> {
+        // 格式: wifi:wlan0
+        nmcli_terse(&["-t", "-f", "TYPE,DEVICE", "device"])
+            .into_iter()
+            .find(|fields| fields.first().map(String::as_str) == Some("wifi"))
+            .and_then(|fields| fields.get(1).cloned())
+    }
+
+    fn wifi_service(&self) -> Option<String> {
+        // 格式: Home WiFi
+        let device = self.wifi_device()?;
+        nmcli_terse(&["-t", "-g", "GENERAL.CONNECTION", "device", "show", &device])
+            .into_iter()
+            .next()
+            .and_then(|fields| fields.into_iter().next())
+            .filter(|connection| !connection.is_empty())
+    }
+
+    fn service_device(&self, service: &str) -> Option<String> {
+        connection_device(service)
+    }
+
+    fn link_up(&self, device: &str) -> Option<bool> {
+        // 网线已插入时 carrier 为 1，直接读取 sysfs，不执行命令
+        let carrier = std::fs::read_to_string(format!("/sys/class/net/{}/carrier", device)).ok()?;
+        Some(carrier.trim() == "1")
+    }
+
+    fn default_interface(&self) -> Option<String> {
+        default_route().map(|(_, device)| device)
+    }
+
+    fn network_services(&self) -> Vec<String> {
+        let services: Vec<String> = nmcli_terse(&["-t", "-f", "NAME", "connection", "show"])
+            .into_iter()
+            .filter_map(|fields| fields.into_iter().next())
+            .filter(|name| !name.is_empty())
+            .collect();
+
+        if services.is_empty() {
+            // 与 macOS 保持一致，至少返回一个服务，界面依赖非空列表
+            vec!["Wi-Fi".to_string()]
+        } else {
+            services
+        }
+    }
+
+    fn service_state(&self, service: &str) -> ServiceState {
+        // 格式: Wired connection 1:yes
+        let connections = nmcli_terse(&["-t", "-f", "NAME,ACTIVE", "connection", "show"]);
+        if connections.is_empty() {
+            return ServiceState::Active;
+        }
+        match connections.iter().find(|fields| fields.first().map(String::as_str) == Some(service)) {
+            None => ServiceState::Missing,
+            Some(fields) if fields.get(1).map(String::as_str) == Some("yes") => ServiceState::Active,
+            // 连接绑定的网卡不受 NetworkManager 管理时相当于停用
+            Some(_) if device_unmanaged(service) => ServiceState::Disabled,
+            Some(_) => ServiceState::Disconnected,
+        }
+    }
+
+    fn ethernet_service(&self) -> Option<String> {
+        // 格式: ethernet:connected:Wired connection 1
+        nmcli_terse(&["-t", "-f", "TYPE,STATE,CONNECTION", "device"])
+            .into_iter()
+            .find(|fields| {
+                fields.first().map(String::as_str) == Some("ethernet")
+                    && fields.get(1).map(String::as_str) == Some("connected")
+            })
+            .and_then(|fields| fields.get(2).cloned())
+    }
+
+    fn router_mac(&self, service: Option<&str>) -> Option<String> {
+        // 1. 获取路由器 IP：优先使用连接自身的网关，取不到时使用非隧道接口的默认路由
+        let router_ip = service
+            .and_then(|service| {
+                nmcli_terse(&["-t", "-g", "IP4.GATEWAY", "connection", "show", service])
+                    .into_iter()
+                    .next()
+                    .and_then(|fields| fields.into_iter().next())
+            })
+            .filter(|gateway| gateway.parse::<Ipv4Addr>().is_ok())
+            .or_else(|| default_route().map(|(gateway, _)| gateway))?;
+
+        // 2. 通过邻居表获取 MAC
+        self.neighbor_mac(&router_ip)
+    }
+
+    fn neighbor_mac(&self, ip: &str) -> Option<String> {
+        // 格式: 192.168.1.1 dev wlan0 lladdr aa:bb:cc:dd:ee:ff REACHABLE
+        // 没有应答的条目（FAILED / INCOMPLETE）没有 lladdr
+        let output = Command::new("ip").args(["neigh", "show", ip]).output().ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mac = token_after(stdout.lines().next()?, "lladdr")?;
+        Some(mac.to_lowercase())
+    }
+
+    fn bssid(&self) -> Option<String> {
+        // 格式: yes:AA\:BB\:CC\:DD\:EE\:FF
+        nmcli_terse(&["-t", "-f", "ACTIVE,BSSID", "device", "wifi"])
+            .into_iter()
+            .find(|fields| fields.first().map(String::as_str) == Some("yes"))
+            .and_then(|fields| fields.get(1).cloned())
+            .filter(|bssid| !bssid.is_empty())
+            .map(|bssid| bssid.to_lowercase())
+    }
+
+    fn interface_mac(&self, service: &str) -> Option<String> {
+        let device = connection_device(service)?;
+        // 格式: 00\:E0\:4C\:68\:01\:02
+        nmcli_terse(&["-t", "-g", "GENERAL.HWADDR", "device", "show", &device])
+            .into_iter()
+            .next()
+            .and_then(|fields| fields.into_iter().next())
+            .filter(|mac| !mac.is_empty())
+            .map(|mac| mac.to_lowercase())
+    }
+
+    fn vpn_active(&self) -> bool {
+        nmcli_terse(&["-t", "-f", "TYPE", "connection", "show", "--active"])
+            .into_iter()
+            .filter_map(|fields| fields.into_iter().next())
+            .any(|kind| kind == "vpn" || kind == "wireguard")
+    }
+
+    fn scan_wifi(&self) -> Vec<ScanResult> {
+        // 格式: NetworkName:AA\:BB\:CC\:DD\:EE\:FF，隐藏网络的 SSID 为空
+        nmcli_terse(&["-t", "-f", "SSID,BSSID", "device", "wifi", "list"])
+            .into_iter()
+            .filter_map(|fields| {
+                let mut fields = fields.into_iter();
+                let ssid = fields.next().filter(|ssid| !ssid.is_empty())?;
+                let bssid = fields.next().filter(|bssid| !bssid.is_empty()).map(|bssid| bssid.to_lowercase());
+                Some(ScanResult { ssid, bssid })
+            })
+            .collect()
+    }
+
+    fn diagnostic_commands(&self) -> Vec<Vec<String>> {
+        let mut commands = vec![
+            vec!["uname".to_string(), "-a".to_string()],
+            nmcli_args(&["general", "status"]),
+            nmcli_args(&["device", "status"]),
+            nmcli_args(&["connection", "show"]),
+        ];
+        for service in self.network_services() {
+            commands.push(nmcli_args(&["-f", "ipv4,ipv6,IP4,IP6", "connection", "show", &service]));
+        }
+        commands.push(vec!["ip".to_string(), "route".to_string()]);
+        commands
+    }
+
+    fn join_wifi(&self, _service: &str, ssid: &str, password: Option<&str>) -> Result<(), String> {
+        // nmcli 按 SSID 选择连接，不需要连接名；有多块无线网卡时指定 Wi-Fi 网卡
+        let device = self.wifi_device();
+        let mut args = vec!["device", "wifi", "connect", ssid];
+        if let Some(password) = password {
+            args.extend(["password", password]);
+        }
+        if let Some(device) = &device {
+            args.extend(["ifname", device.as_str()]);
+        }
+
+        let output = Command::new("nmcli")
+            .args(&args)
+            .output()
+            .map_err(|e| e.to_string())?;
+
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(String::from_utf8_lossy(&output.stderr).to_string())
+        }
+    }
+
+    fn current_config(&self, service: &str) -> Result<NetworkConfig, NetworkError> {
+        // 读取的是完整的当前设置（也用作回滚快照），IP 和 DNS 都需要管理
+        let mut config = NetworkConfig { manage_ip: true, manage_dns: true, ..NetworkConfig::default() };
+        let mut configured_dns = Vec::new();
+        let mut active_dns = Vec::new();
+
+        // 格式: ipv4.method:auto / IP4.ADDRESS[1]:192.168.1.5/24
+        let stdout = command_output("nmcli", &["-t", "connection", "show", service])?;
+        if !stdout.lines().any(|line| line.starts_with("ipv4.method:")) {
+            return Err(NetworkError::Parse(format!("nmcli 输出中缺少连接 {} 的 ipv4.method", service)));
+        }
+        for fields in stdout.lines().map(split_terse_line) {
+            let Some((key, rest)) = fields.split_first() else {
+                continue;
+            };
+            let value = rest.join(":");
+            let value = value.trim();
+            match key.as_str() {
+                "ipv4.method" => config.use_dhcp = value == "auto",
+                "ipv4.dns" => {
+                    configured_dns = value
+                        .split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect();
+                }
+                "ipv4.dns-search" => {
+                    config.search_domains = value
+                        .split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty() && s != "--")
+                        .collect();
+                }
+                "ipv6.method" => {
+                    config.ipv6_mode = match value {
+                        "auto" => Ipv6Mode::Automatic,
+                        "manual" => Ipv6Mode::Manual,
+                        // 关闭、忽略或仅本地链路时不修改
+                        _ => Ipv6Mode::Unmanaged,
+                    };
+                }
+                key if key.ends_with(".mtu") => {
+                    if let Ok(mtu) = value.parse() {
+                        config.mtu = Some(mtu);
+                    }
+                }
+                "ipv6.addresses" if config.ipv6_mode == Ipv6Mode::Manual => {
+                    if let Some((addr, prefix)) = split_cidr(value.split(',').next().unwrap_or("")) {
+                        config.ipv6_address = Some(addr.to_string());
+                        config.ipv6_prefix = prefix.parse().ok();
+                    }
+                }
+                "ipv6.gateway" if config.ipv6_mode == Ipv6Mode::Manual && !value.is_empty() && value != "--" => {
+                    config.ipv6_router = Some(value.to_string());
+                }
+                "IP4.GATEWAY" if !value.is_empty() && value != "--" => {
+                    config.router = Some(value.to_string());
+                }
+                key if key.starts_with("IP4.ADDRESS") && config.ip_address.is_none() => {
+                    if let Some((addr, prefix)) = split_cidr(value) {
+                        config.ip_address = Some(addr.to_string());
+                        config.subnet_mask = prefix.parse().ok().map(prefix_to_mask);
+                    }
+                }
+                key if key.starts_with("IP4.DNS") => active_dns.push(value.to_string()),
+                _ => {}
+            }
+        }
+
+        // 优先使用连接中配置的 DNS，否则显示实际使用的 DNS
+        if !configured_dns.is_empty() {
+            config.dns_mode = DnsMode::Manual;
+        }
+        config.dns_servers = if configured_dns.is_empty() { active_dns } else { configured_dns };
+        Ok(config)
+    }
+
+    fn plan_steps(&self, service: &str, config: &NetworkConfig) -> Vec<(&'static str, Vec<String>)> {
+        // NetworkManager 不支持设置 WINS、DoH 和系统代理，跳过这些步骤
+        let mut plan: Vec<(&'static str, Vec<String>)> = apply_steps(config)
+            .into_iter()
+            .filter(|step| !matches!(*step, "wins" | "doh" | "proxy"))
+            .map(|step| (step, plan_step(service, config, step)))
+            .collect();
+
+        // 修改连接后需要重新激活才会生效
+        plan.push(("activate", nmcli_args(&["connection", "up", service])));
+        plan
+    }
+}
+
+/// 生成单个步骤的命令
+fn plan_step(service: &str, config: &NetworkConfig, step: &str) -> Vec<String> {
+    match step {
+        "ip" => {
+            if config.use_dhcp {
+                // 使用 DHCP
+                nmcli_args(&[
+                    "connection", "modify", service,
+                    "ipv4.method", "auto", "ipv4.addresses", "", "ipv4.gateway", "",
+                ])
+            } else {
+                // 使用静态 IP
+                let ip = config.ip_address.as_deref().unwrap_or(DEFAULT_STATIC_IP);
+                let mask = config.subnet_mask.as_deref().unwrap_or(DEFAULT_SUBNET_MASK);
+                let router = config.router.as_deref().unwrap_or(DEFAULT_ROUTER);
+                let address = format!("{}/{}", ip, mask_to_prefix(mask));
+
+                nmcli_args(&[
+                    "connection", "modify", service,
+                    "ipv4.method", "manual", "ipv4.addresses", &address, "ipv4.gateway", router,
+                ])
+            }
+        }
+        "ipv6" => {
+            // 设置 IPv6，不修改时不会执行这一步
+            if config.ipv6_mode == Ipv6Mode::Manual
+                && let Some(ipv6) = config.ipv6_address.as_deref()
+            {
+                let address = format!("{}/{}", ipv6, config.ipv6_prefix.unwrap_or(64));
+                let router = config.ipv6_router.as_deref().unwrap_or("");
+                nmcli_args(&[
+                    "connection", "modify", service,
+                    "ipv6.method", "manual", "ipv6.addresses", &address, "ipv6.gateway", router,
+                ])
+            } else {
+                nmcli_args(&[
+                    "connection", "modify", service,
+                    "ipv6.method", "auto", "ipv6.addresses", "", "ipv6.gateway", "",
+                ])
+            }
+        }
+        "mtu" => {
+            // MTU 属性随连接类型不同（802-3-ethernet.mtu / 802-11-wireless.mtu）
+            let property = format!("{}.mtu", connection_type(service));
+            let mtu = config.mtu.unwrap_or(DEFAULT_MTU).to_string();
+            nmcli_args(&["connection", "modify", service, &property, &mtu])
+        }
+        "search" => {
+            // 设置搜索域
+            let domains = config.search_domains.join(",");
+            nmcli_args(&["connection", "modify", service, "ipv4.dns-search", &domains])
+        }
+        _ => {
+            // 设置 DNS，自动模式使用 DHCP 下发的 DNS
+            if config.dns_mode != DnsMode::Manual {
+                nmcli_args(&[
+                    "connection", "modify", service,
+                    "ipv4.dns", "", "ipv4.ignore-auto-dns", "no",
+                ])
+            } else {
+                let dns = config.dns_servers.join(",");
+                nmcli_args(&[
+                    "connection", "modify", service,
+                    "ipv4.dns", &dns, "ipv4.ignore-auto-dns", "yes",
+                ])
+            }
+        }
+    }
+}
+
+/// 连接绑定的网卡是否处于 unmanaged 状态（未绑定网卡时返回 false）
+fn device_unmanaged(service: &str) -> bool {
+    let Some(interface) = nmcli_terse(&["-t", "-g", "connection.interface-name", "connection", "show", service])
+        .into_iter()
+        .next()
+        .and_then(|fields| fields.into_iter().next())
+        .filter(|interface| !interface.is_empty())
+    else {
+        return false;
+    };
+
+    // 格式: eth0:unmanaged
+    nmcli_terse(&["-t", "-f", "DEVICE,STATE", "device"])
+        .iter()
+        .any(|fields| fields.first() == Some(&interface) && fields.get(1).map(String::as_str) == Some("unmanaged"))
+}
+
+/// 连接当前使用的网卡，格式: enx00e04c680102
+fn connection_device(service: &str) -> Option<String> {
+    nmcli_terse(&["-t", "-g", "GENERAL.DEVICES", "connection", "show", service])
+        .into_iter()
+        .next()
+        .and_then(|fields| fields.into_iter().next())
+        .filter(|device| !device.is_empty())
+}
+
+/// 获取默认路由的 (网关, 网卡)，跳过 VPN 等隧道接口
+/// 格式: default via 192.168.1.1 dev wlan0 proto dhcp metric 600
+fn default_route() -> Option<(String, String)> {
+    let output = Command::new("ip").args(["route", "show", "default"]).output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout.lines().find_map(|line| {
+        let device = token_after(line, "dev")?;
+        if is_tunnel_interface(&device) {
+            return None;
+        }
+        Some((token_after(line, "via")?, device))
+    })
+}
+
+/// 获取连接类型，如 "802-3-ethernet"
+fn connection_type(service: &str) -> String {
+    nmcli_terse(&["-t", "-g", "connection.type", "connection", "show", service])
+        .into_iter()
+        .next()
+        .and_then(|fields| fields.into_iter().next())
+        .unwrap_or_else(|| "802-3-ethernet".to_string())
+}
+
+fn nmcli_args(args: &[&str]) -> Vec<String> {
+    std::iter::once("nmcli")
+        .chain(args.iter().copied())
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// 执行 nmcli 并按 terse 格式（冒号分隔，"\:" 转义）拆分每一行
+fn nmcli_terse(args: &[&str]) -> Vec<Vec<String>> {
+    let Ok(output) = Command::new("nmcli").args(args).output() else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(split_terse_line)
+        .collect()
+}
+
+/// 拆分 nmcli terse 输出的一行
+fn split_terse_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            ':' => fields.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    fields.push(current);
+    fields
+}
+
+/// 取出某个关键字后面的一个词
+fn token_after(line: &str, keyword: &str) -> Option<String> {
+    let mut tokens = line.split_whitespace();
+    tokens.find(|t| *t == keyword)?;
+    tokens.next().map(|t| t.to_string())
+}
+
+/// 拆分 "地址/前缀" 格式
+fn split_cidr(value: &str) -> Option<(&str, &str)> {
+    value.trim().split_once('/')
+}
+
+/// 子网掩码转前缀长度，无法解析时按 /24 处理
+fn mask_to_prefix(mask: &str) -> u32 {
+    mask.parse::<Ipv4Addr>()
+        .map(|addr| u32::from(addr).count_ones())
+        .unwrap_or(24)
+}
+
+/// 前缀长度转子网掩码
+fn prefix_to_mask(prefix: u32) -> String {
+    let bits = if prefix == 0 { 0 } else { u32::MAX << (32 - prefix.min(32)) };
+    Ipv4Addr::from(bits).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::NmcliBackend;
+    use crate::config::{ConfigType, DnsMode, NetworkConfig};
+    use crate::network::runner::{MockRunner, with_runner};
+    use crate::network::{NetworkBackend, NetworkError};
+
+    /// 用 MockRunner 应用配置，返回执行结果和依次执行的命令行
+    fn apply(runner: MockRunner, config: &NetworkConfig) -> (Result<(), NetworkError>, Vec<String>) {
+        let runner = Rc::new(runner);
+        let result = with_runner(runner.clone(), || NmcliBackend.apply_config("Wired", config));
+        (result, runner.calls().iter().map(|argv| argv.join(" ")).collect())
+    }
+
+    fn config() -> NetworkConfig {
+        NetworkConfig::new("Office".to_string(), "Office".to_string(), None, ConfigType::Wifi, None)
+    }
+
+    #[test]
+    fn apply_dhcp_config() {
+        let (result, calls) = apply(MockRunner::new(), &config());
+        assert!(result.is_ok());
+        assert_eq!(
+            calls,
+            vec![
+                "nmcli connection modify Wired ipv4.method auto ipv4.addresses  ipv4.gateway ",
+                "nmcli connection modify Wired ipv4.dns  ipv4.ignore-auto-dns no",
+                "nmcli connection modify Wired ipv4.dns-search ",
+                "nmcli connection up Wired",
+            ]
+        );
+    }
+
+    #[test]
+    fn apply_static_config() {
+        let config = NetworkConfig {
+            use_dhcp: false,
+            ip_address: Some("10.0.0.20".to_string()),
+            subnet_mask: Some("255.255.252.0".to_string()),
+            router: Some("10.0.0.1".to_string()),
+            search_domains: vec!["corp.example".to_string()],
+            ..config()
+        };
+        let (result, calls) = apply(MockRunner::new(), &config);
+        assert!(result.is_ok());
+        assert_eq!(calls[0], "nmcli connection modify Wired ipv4.method manual ipv4.addresses 10.0.0.20/22 ipv4.gateway 10.0.0.1");
+        assert_eq!(calls[2], "nmcli connection modify Wired ipv4.dns-search corp.example");
+    }
+
+    #[test]
+    fn apply_dns_servers() {
+        // 没有 DNS 服务器时清空连接中的 DNS，改用 DHCP 下发的
+        let config = NetworkConfig { manage_ip: false, ..config() };
+        let (_, calls) = apply(MockRunner::new(), &config);
+        assert_eq!(calls[0], "nmcli connection modify Wired ipv4.dns  ipv4.ignore-auto-dns no");
+
+        let config = NetworkConfig {
+            dns_mode: DnsMode::Manual,
+            dns_servers: vec!["1.1.1.1".to_string(), "8.8.8.8".to_string()],
+            ..config
+        };
+        let (_, calls) = apply(MockRunner::new(), &config);
+        assert_eq!(calls[0], "nmcli connection modify Wired ipv4.dns 1.1.1.1,8.8.8.8 ipv4.ignore-auto-dns yes");
+    }
+
+    #[test]
+    fn apply_stops_at_failed_step() {
+        let runner = MockRunner::new().fail("nmcli connection modify Wired ipv4.dns ", 10, "Error: invalid IP address");
+        let (result, calls) = apply(runner, &config());
+        assert!(matches!(result, Err(NetworkError::CommandFailed { code: Some(10), .. })));
+        assert_eq!(calls.len(), 2);
+    }
+
+    #[test]
+    fn current_config_from_nmcli_output() {
+        let runner = MockRunner::new().respond(
+            "nmcli -t connection show Wired",
+            "ipv4.method:manual\nipv4.dns:1.1.1.1,8.8.8.8\nipv4.dns-search:corp.example\nIP4.ADDRESS[1]:10.0.0.20/22\nIP4.GATEWAY:10.0.0.1\n",
+        );
+        let config = with_runner(Rc::new(runner), || NmcliBackend.current_config("Wired")).unwrap();
+        assert!(!config.use_dhcp);
+        assert_eq!(config.ip_address.as_deref(), Some("10.0.0.20"));
+        assert_eq!(config.subnet_mask.as_deref(), Some("255.255.252.0"));
+        assert_eq!(config.router.as_deref(), Some("10.0.0.1"));
+        assert_eq!(config.dns_mode, DnsMode::Manual);
+        assert_eq!(config.dns_servers, vec!["1.1.1.1", "8.8.8.8"]);
+        assert_eq!(config.search_domains, vec!["corp.example"]);
+    }
+}
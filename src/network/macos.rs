@@ -0,0 +1,1057 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use uuid::Uuid;
+
+use super::parse::{self, parse_airport_network, parse_default_gateway, parse_ioreg_bssid, parse_ioreg_ssid, parse_system_profiler_ssid};
+use super::{NetworkBackend, NetworkError, ScanResult, ServiceState, SsidStatus, DEFAULT_MTU, DEFAULT_ROUTER, DEFAULT_STATIC_IP, DEFAULT_SUBNET_MASK, apply_steps, command_output, dns_ignore_prefixes, runner};
+use crate::config::{AppConfig, ConfigType, DnsMode, Ipv6Mode, NetworkConfig, parse_proxy_address};
+
+/// macOS 实现，基于 networksetup / ioreg / scutil
+pub struct MacBackend;
+
+impl NetworkBackend for MacBackend {
+    fn current_ssid(&self) -> Option<String> {
+        get_current_ssid()
+    }
+
+    fn quick_ssid(&self) -> Option<String> {
+        get_ssid_via_networksetup()
+    }
+
+    fn ssid_status(&self) -> SsidStatus {
+        get_ssid_status()
+    }
+
+    fn network_services(&self) -> Vec<String> {
+        get_network_services()
+    }
+
+    fn wifi_device(&self) -> Option<String> {
+        get_wifi_device()
+    }
+
+    fn wifi_service(&self) -> Option<String> {
+        get_wifi_service()
+    }
+
+    fn service_device(&self, service: &str) -> Option<String> {
+        get_device_for_service(service)
+    }
+
+    fn link_up(&self, device: &str) -> Option<bool> {
+        get_link_status(device)
+    }
+
+    fn default_interface(&self) -> Option<String> {
+        let output = runner::run("netstat", &["-rn", "-f", "inet"]).ok()?;
+        parse::parse_default_route(&output.stdout).map(|(_, interface)| interface)
+    }
+
+    fn service_state(&self, service: &str) -> ServiceState {
+        get_service_state(service)
+    }
+
+    fn ethernet_service(&self) -> Option<String> {
+        get_ethernet_status()
+    }
+
+    fn router_mac(&self, service: Option<&str>) -> Option<String> {
+        get_router_mac(service)
+    }
+
+    fn neighbor_mac(&self, ip: &str) -> Option<String> {
+        get_arp_mac(ip)
+    }
+
+    fn bssid(&self) -> Option<String> {
+        get_bssid_via_ioreg().or_else(get_bssid_via_airport)
+    }
+
+    fn interface_mac(&self, service: &str) -> Option<String> {
+        get_interface_mac(service)
+    }
+
+    fn vpn_active(&self) -> bool {
+        is_vpn_active()
+    }
+
+    fn proxy_enabled(&self, service: &str) -> bool {
+        is_proxy_enabled(service)
+    }
+
+    fn current_config(&self, service: &str) -> Result<NetworkConfig, NetworkError> {
+        get_current_config(service)
+    }
+
+    fn list_locations(&self) -> Vec<String> {
+        get_locations()
+    }
+
+    fn location_configs(&self, location: &str) -> Result<Vec<NetworkConfig>, NetworkError> {
+        let sets = read_preferences("Sets")?;
+        let services = read_preferences("NetworkServices")?;
+        parse_location_configs(&sets, &services, location)
+            .ok_or_else(|| NetworkError::InvalidConfig(format!("找不到位置: {}", location)))
+    }
+
+    fn scan_wifi(&self) -> Vec<ScanResult> {
+        scan_wifi_networks()
+    }
+
+    fn prepare_step(&self, step: &str, config: &NetworkConfig) -> Result<(), NetworkError> {
+        if step == "doh"
+            && let Some(template) = config.doh_template.as_deref().map(str::trim)
+            && !template.is_empty()
+        {
+            write_doh_profile(template).map_err(|e| NetworkError::CommandFailed {
+                code: None,
+                stderr: format!("无法写入 DoH 描述文件 {}: {}", doh_profile_path().display(), e),
+            })?;
+        }
+        Ok(())
+    }
+
+    fn cleanup_temp_files(&self) {
+        // 打开描述文件后系统设置已经读取了内容，文件本身不再需要
+        let _ = fs::remove_file(doh_profile_path());
+    }
+
+    fn diagnostic_commands(&self) -> Vec<Vec<String>> {
+        let mut commands = vec![
+            vec!["sw_vers".to_string()],
+            networksetup_args(&["-listnetworkserviceorder"]),
+        ];
+        for service in self.network_services() {
+            commands.push(networksetup_args(&["-getinfo", &service]));
+            commands.push(networksetup_args(&["-getdnsservers", &service]));
+            commands.push(networksetup_args(&["-getsearchdomains", &service]));
+        }
+        commands.push(vec!["scutil".to_string(), "--dns".to_string()]);
+        commands.push(vec!["netstat".to_string(), "-rn".to_string(), "-f".to_string(), "inet".to_string()]);
+        commands
+    }
+
+    fn join_wifi(&self, service: &str, ssid: &str, password: Option<&str>) -> Result<(), String> {
+        let device = get_device_for_service(service).unwrap_or_else(wifi_device_or_default);
+        let mut args = vec!["-setairportnetwork", device.as_str(), ssid];
+        if let Some(password) = password {
+            args.push(password);
+        }
+
+        let output = runner::run("networksetup", &args).map_err(|e| e.to_string())?;
+
+        // 连接失败时 networksetup 的退出码仍可能为 0，错误信息输出在 stdout
+        if !output.success() {
+            Err(output.stderr)
+        } else if !output.stdout.trim().is_empty() {
+            Err(output.stdout.trim().to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn link_speed(&self, service: &str) -> Option<String> {
+        get_link_speed(service)
+    }
+
+    fn mtu_range(&self, service: &str) -> Option<(u32, u32)> {
+        get_mtu_range(service)
+    }
+
+    fn plan_steps(&self, service: &str, config: &NetworkConfig) -> Vec<(&'static str, Vec<String>)> {
+        // 代理步骤需要多条命令（HTTP、HTTPS、PAC 和例外列表分别设置）
+        apply_steps(config)
+            .into_iter()
+            .flat_map(|step| {
+                let commands = if step == "proxy" {
+                    plan_proxy(service, config)
+                } else {
+                    vec![plan_step(service, config, step)]
+                };
+                commands.into_iter().map(move |argv| (step, argv))
+            })
+            .collect()
+    }
+}
+
+/// 获取当前连接的 WiFi SSID
+fn get_current_ssid() -> Option<String> {
+    // 方法1: 使用 ioreg (最可靠，不会被隐私保护遮蔽)
+    if let Some(ssid) = get_ssid_via_ioreg() {
+        return Some(ssid);
+    }
+
+    // 方法2: 使用 networksetup
+    if let Some(ssid) = get_ssid_via_networksetup() {
+        return Some(ssid);
+    }
+
+    // 方法3: 使用 system_profiler
+    if let Some(ssid) = get_ssid_via_system_profiler() {
+        return Some(ssid);
+    }
+
+    None
+}
+
+/// 获取 WiFi 连接状态
+fn get_ssid_status() -> SsidStatus {
+    // 格式: Wi-Fi Power (en0): Off
+    let device = wifi_device_or_default();
+    if let Ok(output) = runner::run("networksetup", &["-getairportpower", &device])
+        && output.stdout.trim_end().ends_with(": Off")
+    {
+        return SsidStatus::PoweredOff;
+    }
+
+    if let Some(ssid) = get_current_ssid() {
+        return SsidStatus::Connected(ssid);
+    }
+
+    // 新版 macOS 即使已连接，-getairportnetwork 也可能提示 not associated，因此以网卡状态判断是否已连接
+    if is_service_connected("Wi-Fi") {
+        SsidStatus::Hidden
+    } else {
+        SsidStatus::NotAssociated
+    }
+}
+
+/// 通过 ioreg 获取 SSID (不受隐私保护影响)
+fn get_ssid_via_ioreg() -> Option<String> {
+    // 只遍历 Wi-Fi 接口子树（IO80211Interface 类），不导出整个 I/O 注册表
+    // 如果以后 macOS 更改了 Wi-Fi 驱动的类名，需要同步修改这里
+    let output = runner::run("sh", &["-c", "ioreg -c IO80211Interface -l | grep 'IO80211SSID' | head -1"]).ok()?;
+
+    if output.success() {
+        return parse_ioreg_ssid(output.stdout.trim());
+    }
+    None
+}
+
+/// 通过 ioreg 获取 BSSID
+/// 格式: "IO80211BSSID" = <a0b1c2d3e4f5>
+fn get_bssid_via_ioreg() -> Option<String> {
+    let output = runner::run("sh", &["-c", "ioreg -c IO80211Interface -l | grep 'IO80211BSSID' | head -1"]).ok()?;
+
+    parse_ioreg_bssid(&output.stdout)
+}
+
+/// airport 工具（macOS 14.4 起已移除）
+const AIRPORT: &str = "/System/Library/PrivateFrameworks/Apple80211.framework/Versions/Current/Resources/airport";
+
+/// 通过 airport -I 获取 BSSID（较旧的 macOS）
+/// 格式:          BSSID: a0:b1:c2:d3:e4:f5
+fn get_bssid_via_airport() -> Option<String> {
+    let output = runner::run(AIRPORT, &["-I"]).ok()?;
+
+    output
+        .stdout
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("BSSID: "))
+        .map(|bssid| bssid.trim().to_lowercase())
+        .filter(|bssid| !bssid.is_empty())
+}
+
+fn get_ssid_via_networksetup() -> Option<String> {
+    let output = runner::run("networksetup", &["-getairportnetwork", &wifi_device_or_default()]).ok()?;
+
+    if !output.success() {
+        return None;
+    }
+    parse_airport_network(&output.stdout)
+}
+
+/// 扫描附近的 WiFi：优先使用 airport -s，没有 airport 时从 system_profiler 读取（没有 BSSID）
+fn scan_wifi_networks() -> Vec<ScanResult> {
+    if let Ok(output) = runner::run(AIRPORT, &["-s"])
+        && output.success()
+    {
+        let results = parse::parse_airport_scan(&output.stdout);
+        if !results.is_empty() {
+            return results;
+        }
+    }
+
+    match runner::run("system_profiler", &["SPAirPortDataType"]) {
+        Ok(output) if output.success() => parse::parse_system_profiler_networks(&output.stdout),
+        _ => Vec::new(),
+    }
+}
+
+fn get_ssid_via_system_profiler() -> Option<String> {
+    let output = runner::run("system_profiler", &["SPAirPortDataType"]).ok()?;
+
+    if !output.success() {
+        return None;
+    }
+    parse_system_profiler_ssid(&output.stdout)
+}
+
+/// 获取所有网络服务
+fn get_network_services() -> Vec<String> {
+    match runner::run("networksetup", &["-listallnetworkservices"]) {
+        // 跳过禁用的服务
+        Ok(output) if output.success() => parse::parse_network_services(&output.stdout)
+            .into_iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(service, _)| service)
+            .collect(),
+        _ => vec!["Wi-Fi".to_string()],
+    }
+}
+
+/// 系统网络偏好设置文件，保存所有位置（Sets）和网络服务（NetworkServices）
+const SYSTEM_PREFERENCES: &str = "/Library/Preferences/SystemConfiguration/preferences.plist";
+
+/// 列出所有位置
+fn get_locations() -> Vec<String> {
+    match runner::run("networksetup", &["-listlocations"]) {
+        Ok(output) if output.success() => output
+            .stdout
+            .lines()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// 读取偏好设置文件中的一个顶层字段，转换为 JSON
+fn read_preferences(key: &str) -> Result<serde_json::Value, NetworkError> {
+    let json = command_output("plutil", &["-extract", key, "json", "-o", "-", SYSTEM_PREFERENCES])?;
+    serde_json::from_str(&json).map_err(|e| NetworkError::Parse(format!("{}: {}", key, e)))
+}
+
+/// 把偏好设置中某个位置的服务设置转换为配置，找不到位置时返回 None
+/// 位置在 Sets 中通过服务 id 引用服务，服务的 IPv4 / IPv6 / DNS 设置保存在 NetworkServices 中
+/// 已停用的服务（带 __INACTIVE__ 标记）会被跳过
+fn parse_location_configs(sets: &serde_json::Value, services: &serde_json::Value, location: &str) -> Option<Vec<NetworkConfig>> {
+    let set = sets.as_object()?.values().find(|set| set["UserDefinedName"] == location)?;
+
+    let strings = |value: &serde_json::Value| -> Vec<String> {
+        value
+            .as_array()
+            .map(|items| items.iter().filter_map(|item| item.as_str().map(str::to_string)).collect())
+            .unwrap_or_default()
+    };
+
+    let mut configs = Vec::new();
+    for id in set["Network"]["Service"].as_object().into_iter().flat_map(|links| links.keys()) {
+        let service = &services[id];
+        if service.get("__INACTIVE__").is_some() {
+            continue;
+        }
+        let Some(name) = service["UserDefinedName"].as_str() else {
+            continue;
+        };
+
+        let mut config = NetworkConfig::new(
+            format!("{} - {}", location, name),
+            String::new(),
+            Some(name.to_string()),
+            ConfigType::Service,
+            None,
+        );
+
+        let ipv4 = &service["IPv4"];
+        if ipv4["ConfigMethod"] == "Manual" {
+            config.use_dhcp = false;
+            config.ip_address = strings(&ipv4["Addresses"]).into_iter().next();
+            config.subnet_mask = strings(&ipv4["SubnetMasks"]).into_iter().next();
+            config.router = ipv4["Router"].as_str().map(str::to_string);
+        }
+
+        let ipv6 = &service["IPv6"];
+        config.ipv6_mode = ipv6_mode(ipv6["ConfigMethod"].as_str());
+        if config.ipv6_mode == Ipv6Mode::Manual {
+            config.ipv6_address = strings(&ipv6["Addresses"]).into_iter().next();
+            config.ipv6_prefix = ipv6["PrefixLength"][0].as_u64().and_then(|prefix| u8::try_from(prefix).ok());
+            config.ipv6_router = ipv6["Router"].as_str().map(str::to_string);
+        }
+
+        config.dns_servers = strings(&service["DNS"]["ServerAddresses"]);
+        if !config.dns_servers.is_empty() {
+            config.dns_mode = DnsMode::Manual;
+        }
+        config.search_domains = strings(&service["DNS"]["SearchDomains"]);
+
+        configs.push(config);
+    }
+
+    configs.sort_by(|a, b| a.name.cmp(&b.name));
+    Some(configs)
+}
+
+/// 获取网络服务的状态，已停用的服务在 -listallnetworkservices 中以 * 开头
+fn get_service_state(service: &str) -> ServiceState {
+    let Ok(output) = runner::run("networksetup", &["-listallnetworkservices"]) else {
+        return ServiceState::Active;
+    };
+    if !output.success() {
+        return ServiceState::Active;
+    }
+
+    let services = parse::parse_network_services(&output.stdout);
+    match services.iter().find(|(name, _)| name == service) {
+        None => ServiceState::Missing,
+        Some((_, false)) => ServiceState::Disabled,
+        Some(_) if is_service_connected(service) => ServiceState::Active,
+        Some(_) => ServiceState::Disconnected,
+    }
+}
+
+/// 服务是否已连接：有对应网卡时看网卡状态（status: active），否则看是否有 IPv4 地址
+fn is_service_connected(service: &str) -> bool {
+    if let Some(status) = get_device_for_service(service).and_then(|device| get_link_status(&device)) {
+        return status;
+    }
+    get_current_config(service)
+        .ok()
+        .and_then(|config| config.ip_address)
+        .is_some_and(|ip| ip.parse::<std::net::Ipv4Addr>().is_ok())
+}
+
+/// 网卡的链路状态（ifconfig 中的 status: active），没有状态行的网卡返回 None
+fn get_link_status(device: &str) -> Option<bool> {
+    let output = runner::run("ifconfig", &[device]).ok()?;
+    output.stdout.contains("status: ").then(|| output.stdout.contains("status: active"))
+}
+
+/// 缓存的 Wi-Fi 设备名，硬件端口在运行期间不会变化
+static WIFI_DEVICE: OnceLock<String> = OnceLock::new();
+
+/// 获取 Wi-Fi 硬件端口对应的设备名（有外接网卡时 Wi-Fi 可能是 en1）
+fn get_wifi_device() -> Option<String> {
+    if let Some(device) = WIFI_DEVICE.get() {
+        return Some(device.clone());
+    }
+    // 较旧的 macOS 中 Wi-Fi 硬件端口名为 AirPort
+    let device = get_device_for_service("Wi-Fi").or_else(|| get_device_for_service("AirPort"))?;
+    Some(WIFI_DEVICE.get_or_init(|| device).clone())
+}
+
+/// 获取 Wi-Fi 设备所属的网络服务名（服务可能被改名，不一定是 "Wi-Fi"）
+fn get_wifi_service() -> Option<String> {
+    let device = get_wifi_device()?;
+    parse::parse_service_order(&networksetup_output(&["-listnetworkserviceorder"]))
+        .into_iter()
+        .find(|(_, service_device)| *service_device == device)
+        .map(|(service, _)| service)
+}
+
+/// Wi-Fi 设备名，无法获取时使用 en0
+fn wifi_device_or_default() -> String {
+    get_wifi_device().unwrap_or_else(|| "en0".to_string())
+}
+
+/// 获取网络服务对应的设备名（如 Wi-Fi -> en0）
+fn get_device_for_service(service: &str) -> Option<String> {
+    let output = runner::run("networksetup", &["-listallhardwareports"]).ok()?;
+
+    parse::parse_hardware_ports(&output.stdout)
+        .into_iter()
+        .find(|(port, _)| port == service)
+        .map(|(_, device)| device)
+}
+
+/// 检测有线网络连接状态
+/// 返回连接的以太网接口名称，如 "Ethernet" 或 "USB 10/100/1000 LAN"
+fn get_ethernet_status() -> Option<String> {
+    // 获取所有硬件端口
+    let output = runner::run("networksetup", &["-listallhardwareports"]).ok()?;
+
+    if !output.success() {
+        return None;
+    }
+
+    for (service, device) in parse::parse_hardware_ports(&output.stdout) {
+        // 检查是否是以太网类型（排除 Wi-Fi 和 Bluetooth）
+        let name = service.to_lowercase();
+        let is_ethernet = !name.contains("wi-fi")
+            && !name.contains("bluetooth")
+            && !name.contains("thunderbolt bridge")
+            && (name.contains("ethernet") || name.contains("lan") || name.contains("usb") || device.starts_with("en"));
+        if !is_ethernet {
+            continue;
+        }
+
+        // 检查接口是否有 IP（即已连接）
+        if let Ok(info_output) = runner::run("networksetup", &["-getinfo", &service]) {
+            let info = parse::parse_getinfo(&info_output.stdout);
+            if info.ip_address.is_some_and(|ip| !ip.is_empty() && ip != "none") {
+                return Some(service);
+            }
+        }
+    }
+
+    None
+}
+
+/// 获取路由器 MAC 地址作为网络的唯一标识
+fn get_router_mac(service: Option<&str>) -> Option<String> {
+    // 1. 先获取路由器 IP：优先使用服务自身的路由器，取不到时使用非隧道接口的默认路由
+    let router_ip = service
+        .and_then(|service| get_current_config(service).ok())
+        .and_then(|config| config.router)
+        .filter(|router| router.parse::<std::net::Ipv4Addr>().is_ok())
+        .or_else(get_default_gateway)?;
+
+    // 2. 通过 ARP 获取路由器 MAC
+    get_arp_mac(&router_ip)
+}
+
+/// 从 IPv4 路由表中获取默认网关，跳过 VPN 等隧道接口
+fn get_default_gateway() -> Option<String> {
+    let output = runner::run("netstat", &["-rn", "-f", "inet"]).ok()?;
+    if !output.success() {
+        return None;
+    }
+    parse_default_gateway(&output.stdout)
+}
+
+/// 从 ARP 缓存中查找 IP 地址对应的 MAC
+fn get_arp_mac(ip: &str) -> Option<String> {
+    let arp_output = runner::run("arp", &["-n", ip]).ok()?;
+
+    if !arp_output.success() {
+        return None;
+    }
+    parse::parse_arp_mac(&arp_output.stdout)
+}
+
+/// 检查是否有已连接的 VPN（scutil --nc list 中状态为 Connected 的服务）
+fn is_vpn_active() -> bool {
+    let Ok(output) = runner::run("scutil", &["--nc", "list"]) else {
+        return false;
+    };
+
+    // 格式: * (Connected)      XXXXXXXX-... PPP --> L2TP       "My VPN"  [PPP/L2TP]
+    output
+        .stdout
+        .lines()
+        .any(|line| line.contains("(Connected)"))
+}
+
+/// 检查服务是否启用了 HTTP 或 HTTPS 代理
+fn is_proxy_enabled(service: &str) -> bool {
+    // 格式: "Enabled: Yes"
+    ["-getwebproxy", "-getsecurewebproxy"].iter().any(|flag| {
+        networksetup_output(&[flag, service])
+            .lines()
+            .any(|line| line.trim() == "Enabled: Yes")
+    })
+}
+
+/// networksetup 和系统配置中的 IPv6 配置方式，关闭或仅本地链路时不修改
+fn ipv6_mode(method: Option<&str>) -> Ipv6Mode {
+    match method {
+        Some("Automatic") => Ipv6Mode::Automatic,
+        Some("Manual") => Ipv6Mode::Manual,
+        _ => Ipv6Mode::Unmanaged,
+    }
+}
+
+/// 获取当前网络配置
+fn get_current_config(service: &str) -> Result<NetworkConfig, NetworkError> {
+    // 读取的是完整的当前设置（也用作回滚快照），IP 和 DNS 都需要管理
+    let mut config = NetworkConfig { manage_ip: true, manage_dns: true, ..NetworkConfig::default() };
+
+    // 获取 IP 信息
+    let stdout = command_output("networksetup", &["-getinfo", service])?;
+    if stdout.trim().is_empty() {
+        return Err(NetworkError::Parse(format!("networksetup -getinfo {} 没有输出", service)));
+    }
+    let info = parse::parse_getinfo(&stdout);
+    config.use_dhcp = info.dhcp;
+    config.ip_address = info.ip_address;
+    config.subnet_mask = info.subnet_mask;
+    config.router = info.router;
+    config.ipv6_mode = ipv6_mode(info.ipv6_method.as_deref());
+    config.ipv6_address = info.ipv6_address;
+    config.ipv6_prefix = info.ipv6_prefix;
+    config.ipv6_router = info.ipv6_router;
+
+    // 优先使用服务中配置的 DNS，否则显示实际使用的 DNS（DHCP 下发的保持自动模式，回滚时不会变成静态 DNS）
+    let configured_dns = get_configured_dns_servers(service);
+    if configured_dns.is_empty() {
+        config.dns_servers = get_active_dns_servers();
+    } else {
+        config.dns_mode = DnsMode::Manual;
+        config.dns_servers = configured_dns;
+    }
+    config.search_domains = get_search_domains(service);
+    config.mtu = get_mtu(service);
+    config.wins_servers = get_wins_servers(service);
+
+    // 回滚时未启用的代理也需要关闭
+    config.http_proxy = parse::parse_web_proxy(&networksetup_output(&["-getwebproxy", service]));
+    config.https_proxy = parse::parse_web_proxy(&networksetup_output(&["-getsecurewebproxy", service]));
+    config.pac_url = parse::parse_auto_proxy_url(&networksetup_output(&["-getautoproxyurl", service]));
+    config.proxy_bypass = parse::parse_list(&networksetup_output(&["-getproxybypassdomains", service]));
+    config.clear_proxy = true;
+
+    Ok(config)
+}
+
+/// 执行 networksetup 并返回标准输出，执行失败时为空
+fn networksetup_output(args: &[&str]) -> String {
+    runner::run("networksetup", args).map(|output| output.stdout).unwrap_or_default()
+}
+
+/// 获取 WINS 服务器
+fn get_wins_servers(service: &str) -> Vec<String> {
+    parse::parse_list(&networksetup_output(&["-getwinsservers", service]))
+}
+
+/// 获取当前 MTU
+fn get_mtu(service: &str) -> Option<u32> {
+    // 格式: Active MTU: 1500 (Current Setting: 1500)
+    let stdout = networksetup_output(&["-getMTU", service]);
+    let rest = stdout.lines().find_map(|line| line.strip_prefix("Active MTU: "))?;
+    rest.split_whitespace().next()?.parse().ok()
+}
+
+/// 获取设备允许的 MTU 范围
+fn get_mtu_range(service: &str) -> Option<(u32, u32)> {
+    // 格式: Valid MTU Range: 1280-1500
+    let stdout = networksetup_output(&["-listValidMTURange", service]);
+    let range = stdout.lines().find_map(|line| line.strip_prefix("Valid MTU Range: "))?;
+    let (min, max) = range.trim().split_once('-')?;
+    Some((min.trim().parse().ok()?, max.trim().parse().ok()?))
+}
+
+/// 获取链路速度
+fn get_link_speed(service: &str) -> Option<String> {
+    // 格式: Active: 1000baseT <full-duplex>
+    networksetup_output(&["-getMedia", service])
+        .lines()
+        .find_map(|line| line.strip_prefix("Active: "))
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty() && s != "none")
+}
+
+/// 获取服务所用网卡的硬件 MAC 地址
+fn get_interface_mac(service: &str) -> Option<String> {
+    parse::parse_mac_address(&networksetup_output(&["-getmacaddress", service]))
+}
+
+/// 获取搜索域
+fn get_search_domains(service: &str) -> Vec<String> {
+    parse::parse_list(&networksetup_output(&["-getsearchdomains", service]))
+}
+
+/// 获取服务中手动配置的 DNS 服务器，使用 DHCP 下发的 DNS 时为空
+fn get_configured_dns_servers(service: &str) -> Vec<String> {
+    parse::parse_list(&networksetup_output(&["-getdnsservers", service]))
+}
+
+/// 从 scutil --dns 获取实际使用的 DNS（包括 DHCP 下发的）
+fn get_active_dns_servers() -> Vec<String> {
+    match runner::run("scutil", &["--dns"]) {
+        Ok(output) => parse::parse_scutil_dns(&output.stdout, &dns_ignore_prefixes()),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// 生成单个步骤的命令
+fn plan_step(service: &str, config: &NetworkConfig, step: &str) -> Vec<String> {
+    match step {
+        "ip" => {
+            if config.use_dhcp {
+                // 使用 DHCP
+                networksetup_args(&["-setdhcp", service])
+            } else {
+                // 使用静态 IP
+                let ip = config.ip_address.as_deref().unwrap_or(DEFAULT_STATIC_IP);
+                let mask = config.subnet_mask.as_deref().unwrap_or(DEFAULT_SUBNET_MASK);
+                let router = config.router.as_deref().unwrap_or(DEFAULT_ROUTER);
+
+                networksetup_args(&["-setmanual", service, ip, mask, router])
+            }
+        }
+        "ipv6" => {
+            // 设置 IPv6，不修改时不会执行这一步
+            if config.ipv6_mode == Ipv6Mode::Manual
+                && let Some(ipv6) = config.ipv6_address.as_deref()
+            {
+                let prefix = config.ipv6_prefix.unwrap_or(64).to_string();
+                let router = config.ipv6_router.as_deref().unwrap_or("");
+                networksetup_args(&["-setv6manual", service, ipv6, &prefix, router])
+            } else {
+                networksetup_args(&["-setv6automatic", service])
+            }
+        }
+        "mtu" => {
+            let mtu = config.mtu.unwrap_or(DEFAULT_MTU).to_string();
+            networksetup_args(&["-setMTU", service, &mtu])
+        }
+        "wins" => {
+            let mut args = vec!["-setwinsservers", service];
+            for wins in &config.wins_servers {
+                args.push(wins.as_str());
+            }
+            networksetup_args(&args)
+        }
+        "doh" => match config.doh_template.as_deref().map(str::trim) {
+            Some(template) if !template.is_empty() => {
+                // 新版 macOS 不能在命令行静默安装描述文件，打开后需要在系统设置中确认安装
+                // 描述文件在执行前由 prepare_step 写入，生成命令（预览）时不写文件
+                vec!["open".to_string(), doh_profile_path().to_string_lossy().to_string()]
+            }
+            _ => vec!["profiles".to_string(), "remove".to_string(), "-identifier".to_string(), DOH_PROFILE_ID.to_string()],
+        },
+        "search" => {
+            // 设置搜索域
+            if config.search_domains.is_empty() {
+                networksetup_args(&["-setsearchdomains", service, "Empty"])
+            } else {
+                let mut args = vec!["-setsearchdomains", service];
+                for domain in &config.search_domains {
+                    args.push(domain.as_str());
+                }
+                networksetup_args(&args)
+            }
+        }
+        _ => {
+            // 设置 DNS
+            if config.dns_mode != DnsMode::Manual {
+                networksetup_args(&["-setdnsservers", service, "Empty"])
+            } else {
+                let mut args = vec!["-setdnsservers", service];
+                for dns in &config.dns_servers {
+                    args.push(dns.as_str());
+                }
+                networksetup_args(&args)
+            }
+        }
+    }
+}
+
+/// 生成设置代理的命令，未设置的代理只在 clear_proxy 时关闭
+fn plan_proxy(service: &str, config: &NetworkConfig) -> Vec<Vec<String>> {
+    let mut commands = Vec::new();
+    let web_proxies = [
+        (&config.http_proxy, "-setwebproxy", "-setwebproxystate"),
+        (&config.https_proxy, "-setsecurewebproxy", "-setsecurewebproxystate"),
+    ];
+    for (proxy, set_flag, state_flag) in web_proxies {
+        match proxy.as_deref().and_then(parse_proxy_address) {
+            Some((host, port)) => commands.push(networksetup_args(&[set_flag, service, host, &port.to_string()])),
+            None if config.clear_proxy => commands.push(networksetup_args(&[state_flag, service, "off"])),
+            None => {}
+        }
+    }
+
+    match config.pac_url.as_deref().map(str::trim) {
+        Some(url) => commands.push(networksetup_args(&["-setautoproxyurl", service, url])),
+        None if config.clear_proxy => commands.push(networksetup_args(&["-setautoproxystate", service, "off"])),
+        None => {}
+    }
+
+    if !config.proxy_bypass.is_empty() {
+        let mut args = vec!["-setproxybypassdomains", service];
+        args.extend(config.proxy_bypass.iter().map(String::as_str));
+        commands.push(networksetup_args(&args));
+    } else if config.clear_proxy {
+        commands.push(networksetup_args(&["-setproxybypassdomains", service, "Empty"]));
+    }
+    commands
+}
+
+/// DoH 描述文件的标识，重复安装时替换之前的描述文件
+const DOH_PROFILE_ID: &str = "com.network-switcher.doh";
+
+/// DoH 描述文件的路径（配置目录下）
+fn doh_profile_path() -> PathBuf {
+    AppConfig::config_path().with_file_name("doh.mobileconfig")
+}
+
+/// 生成 DoH 描述文件并写到配置目录下的 doh_profile_path()
+/// 由 prepare_step 在执行打开文件的命令前调用，写入失败时不执行该命令
+fn write_doh_profile(template: &str) -> std::io::Result<()> {
+    let payload_id = format!("{}.dnsSettings", DOH_PROFILE_ID);
+    let profile = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>PayloadContent</key>
+    <array>
+        <dict>
+            <key>DNSSettings</key>
+            <dict>
+                <key>DNSProtocol</key>
+                <string>HTTPS</string>
+                <key>ServerURL</key>
+                <string>{url}</string>
+            </dict>
+            <key>PayloadDisplayName</key>
+            <string>Network Switcher DoH</string>
+            <key>PayloadIdentifier</key>
+            <string>{payload_id}</string>
+            <key>PayloadType</key>
+            <string>com.apple.dnsSettings.managed</string>
+            <key>PayloadUUID</key>
+            <string>{payload_uuid}</string>
+            <key>PayloadVersion</key>
+            <integer>1</integer>
+        </dict>
+    </array>
+    <key>PayloadDisplayName</key>
+    <string>Network Switcher DoH</string>
+    <key>PayloadIdentifier</key>
+    <string>{profile_id}</string>
+    <key>PayloadType</key>
+    <string>Configuration</string>
+    <key>PayloadUUID</key>
+    <string>{profile_uuid}</string>
+    <key>PayloadVersion</key>
+    <integer>1</integer>
+</dict>
+</plist>
+"#,
+        url = xml_escape(template),
+        payload_id = payload_id,
+        payload_uuid = Uuid::new_v5(&Uuid::NAMESPACE_DNS, payload_id.as_bytes()).to_string().to_uppercase(),
+        profile_id = DOH_PROFILE_ID,
+        profile_uuid = Uuid::new_v5(&Uuid::NAMESPACE_DNS, DOH_PROFILE_ID.as_bytes()).to_string().to_uppercase(),
+    );
+
+    fs::write(doh_profile_path(), profile)
+}
+
+/// 转义 XML 文本中的特殊字符
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn networksetup_args(args: &[&str]) -> Vec<String> {
+    std::iter::once("networksetup")
+        .chain(args.iter().copied())
+        .map(|s| s.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::{MacBackend, parse_location_configs, plan_proxy};
+    use crate::config::{ConfigType, DnsMode, Ipv6Mode, NetworkConfig};
+    use crate::network::{NetworkBackend, NetworkError};
+    use crate::network::runner::{MockRunner, with_runner};
+
+    /// 用 MockRunner 应用配置，返回依次执行的命令行
+    fn apply(config: &NetworkConfig) -> Vec<String> {
+        let runner = Rc::new(MockRunner::new());
+        with_runner(runner.clone(), || MacBackend.apply_config("Wi-Fi", config)).unwrap();
+        runner.calls().iter().map(|argv| argv.join(" ")).collect()
+    }
+
+    fn config() -> NetworkConfig {
+        NetworkConfig::new("Office".to_string(), "Office".to_string(), None, ConfigType::Wifi, None)
+    }
+
+    #[test]
+    fn apply_dhcp_config() {
+        assert_eq!(
+            apply(&config()),
+            vec![
+                "networksetup -setdhcp Wi-Fi",
+                "networksetup -setdnsservers Wi-Fi Empty",
+                "networksetup -setsearchdomains Wi-Fi Empty",
+            ]
+        );
+    }
+
+    #[test]
+    fn apply_automatic_ipv6() {
+        let config = NetworkConfig { ipv6_mode: Ipv6Mode::Automatic, ..config() };
+        assert_eq!(
+            apply(&config),
+            vec![
+                "networksetup -setdhcp Wi-Fi",
+                "networksetup -setv6automatic Wi-Fi",
+                "networksetup -setdnsservers Wi-Fi Empty",
+                "networksetup -setsearchdomains Wi-Fi Empty",
+            ]
+        );
+    }
+
+    #[test]
+    fn apply_static_config() {
+        let config = NetworkConfig {
+            use_dhcp: false,
+            ip_address: Some("10.0.0.20".to_string()),
+            subnet_mask: Some("255.255.252.0".to_string()),
+            router: Some("10.0.0.1".to_string()),
+            search_domains: vec!["corp.example".to_string(), "lab.example".to_string()],
+            ..config()
+        };
+        assert_eq!(
+            apply(&config),
+            vec![
+                "networksetup -setmanual Wi-Fi 10.0.0.20 255.255.252.0 10.0.0.1",
+                "networksetup -setdnsservers Wi-Fi Empty",
+                "networksetup -setsearchdomains Wi-Fi corp.example lab.example",
+            ]
+        );
+    }
+
+    #[test]
+    fn current_config_from_networksetup() {
+        let runner = MockRunner::new()
+            .respond(
+                "networksetup -getinfo",
+                "Manual Configuration\nIP address: 10.0.0.20\nSubnet mask: 255.255.252.0\nRouter: 10.0.0.1\nIPv6: Automatic\nIPv6 IP address: none\nIPv6 Router: none\n",
+            )
+            .respond("networksetup -getdnsservers", "1.1.1.1\n8.8.8.8\n")
+            .respond("networksetup -getsearchdomains", "There aren't any Search Domains set on Wi-Fi.\n")
+            .respond("networksetup -getMTU", "Active MTU: 1400 (Current Setting: 1400)\n")
+            .respond("networksetup -getwinsservers", "There aren't any WINS Servers set on Wi-Fi.\n")
+            .respond("networksetup -getwebproxy", "Enabled: Yes\nServer: proxy.corp.example\nPort: 8080\nAuthenticated Proxy Enabled: 0\n")
+            .respond("networksetup -getsecurewebproxy", "Enabled: No\nServer: \nPort: 0\n")
+            .respond("networksetup -getautoproxyurl", "URL: (null)\nEnabled: No\n")
+            .respond("networksetup -getproxybypassdomains", "*.local\n169.254/16\n");
+        let config = with_runner(Rc::new(runner), || MacBackend.current_config("Wi-Fi")).unwrap();
+
+        assert!(!config.use_dhcp);
+        assert_eq!(config.ip_address.as_deref(), Some("10.0.0.20"));
+        assert_eq!(config.subnet_mask.as_deref(), Some("255.255.252.0"));
+        assert_eq!(config.router.as_deref(), Some("10.0.0.1"));
+        assert_eq!(config.ipv6_mode, Ipv6Mode::Automatic);
+        assert_eq!(config.ipv6_address, None);
+        assert_eq!(config.dns_mode, DnsMode::Manual);
+        assert_eq!(config.dns_servers, ["1.1.1.1", "8.8.8.8"]);
+        assert!(config.search_domains.is_empty());
+        assert_eq!(config.mtu, Some(1400));
+        assert!(config.wins_servers.is_empty());
+        assert_eq!(config.http_proxy.as_deref(), Some("proxy.corp.example:8080"));
+        assert_eq!(config.https_proxy, None);
+        assert_eq!(config.pac_url, None);
+        assert_eq!(config.proxy_bypass, ["*.local", "169.254/16"]);
+    }
+
+    #[test]
+    fn apply_dns_servers() {
+        let config = NetworkConfig { manage_ip: false, ..config() };
+        assert_eq!(apply(&config)[0], "networksetup -setdnsservers Wi-Fi Empty");
+
+        let config = NetworkConfig {
+            dns_mode: DnsMode::Manual,
+            dns_servers: vec!["1.1.1.1".to_string(), "2606:4700:4700::1111".to_string()],
+            ..config
+        };
+        assert_eq!(apply(&config)[0], "networksetup -setdnsservers Wi-Fi 1.1.1.1 2606:4700:4700::1111");
+    }
+
+    #[test]
+    fn apply_permission_error_reports_admin_group() {
+        let runner = MockRunner::new()
+            .fail("networksetup -setdhcp", 14, "** Error: Command requires admin privileges.")
+            .respond("id -Gn", "staff everyone localaccounts");
+        let result = with_runner(Rc::new(runner), || MacBackend.apply_config("Wi-Fi", &config()));
+        assert!(matches!(result, Err(NetworkError::PermissionDenied { admin: false, .. })));
+    }
+
+    #[test]
+    fn proxy_commands_keep_unset_proxies_unless_clearing() {
+        let config = NetworkConfig {
+            http_proxy: Some("proxy.corp.example:8080".to_string()),
+            ..NetworkConfig::default()
+        };
+        let commands: Vec<String> = plan_proxy("Wi-Fi", &config).iter().map(|argv| argv.join(" ")).collect();
+        assert_eq!(commands, vec!["networksetup -setwebproxy Wi-Fi proxy.corp.example 8080"]);
+
+        let config = NetworkConfig { clear_proxy: true, ..config };
+        let commands: Vec<String> = plan_proxy("Wi-Fi", &config).iter().map(|argv| argv.join(" ")).collect();
+        assert_eq!(
+            commands,
+            vec![
+                "networksetup -setwebproxy Wi-Fi proxy.corp.example 8080",
+                "networksetup -setsecurewebproxystate Wi-Fi off",
+                "networksetup -setautoproxystate Wi-Fi off",
+                "networksetup -setproxybypassdomains Wi-Fi Empty",
+            ]
+        );
+    }
+
+    #[test]
+    fn clearing_doh_removes_profile() {
+        let config = NetworkConfig { manage_ip: false, manage_dns: false, doh_template: Some(String::new()), ..config() };
+        let commands: Vec<String> = MacBackend.plan_config("Wi-Fi", &config).iter().map(|argv| argv.join(" ")).collect();
+        assert_eq!(commands, vec!["profiles remove -identifier com.network-switcher.doh"]);
+    }
+
+    #[test]
+    fn location_services_to_configs() {
+        let sets = serde_json::json!({
+            "0A1B": {
+                "UserDefinedName": "Automatic",
+                "Network": { "Service": { "S1": { "__LINK__": "/NetworkServices/S1" } } }
+            },
+            "2C3D": {
+                "UserDefinedName": "Office",
+                "Network": {
+                    "Service": {
+                        "S1": { "__LINK__": "/NetworkServices/S1" },
+                        "S2": { "__LINK__": "/NetworkServices/S2" },
+                        "S3": { "__LINK__": "/NetworkServices/S3" }
+                    }
+                }
+            }
+        });
+        let services = serde_json::json!({
+            "S1": {
+                "UserDefinedName": "Wi-Fi",
+                "IPv4": { "ConfigMethod": "DHCP" },
+                "DNS": { "ServerAddresses": ["1.1.1.1", "8.8.8.8"], "SearchDomains": ["corp.example.com"] }
+            },
+            "S2": {
+                "UserDefinedName": "Ethernet",
+                "IPv4": {
+                    "ConfigMethod": "Manual",
+                    "Addresses": ["10.0.0.20"],
+                    "SubnetMasks": ["255.255.255.0"],
+                    "Router": "10.0.0.1"
+                },
+                "IPv6": { "ConfigMethod": "Manual", "Addresses": ["fd00::20"], "PrefixLength": [64], "Router": "fd00::1" }
+            },
+            "S3": {
+                "UserDefinedName": "Thunderbolt Bridge",
+                "__INACTIVE__": 1,
+                "IPv4": { "ConfigMethod": "DHCP" }
+            }
+        });
+
+        let configs = parse_location_configs(&sets, &services, "Office").unwrap();
+        assert_eq!(configs.len(), 2);
+
+        let ethernet = &configs[0];
+        assert_eq!(ethernet.name, "Office - Ethernet");
+        assert_eq!(ethernet.target_service.as_deref(), Some("Ethernet"));
+        assert!(!ethernet.use_dhcp);
+        assert_eq!(ethernet.ip_address.as_deref(), Some("10.0.0.20"));
+        assert_eq!(ethernet.subnet_mask.as_deref(), Some("255.255.255.0"));
+        assert_eq!(ethernet.router.as_deref(), Some("10.0.0.1"));
+        assert_eq!(ethernet.ipv6_mode, Ipv6Mode::Manual);
+        assert_eq!(ethernet.ipv6_address.as_deref(), Some("fd00::20"));
+        assert_eq!(ethernet.ipv6_prefix, Some(64));
+        assert_eq!(ethernet.dns_mode, DnsMode::Auto);
+
+        let wifi = &configs[1];
+        assert_eq!(wifi.name, "Office - Wi-Fi");
+        assert!(wifi.use_dhcp);
+        assert_eq!(wifi.dns_mode, DnsMode::Manual);
+        assert_eq!(wifi.dns_servers, vec!["1.1.1.1", "8.8.8.8"]);
+        assert_eq!(wifi.search_domains, vec!["corp.example.com"]);
+
+        assert!(parse_location_configs(&sets, &services, "Home").is_none());
+    }
+}
@@ -1,383 +1,1007 @@
+use std::net::ToSocketAddrs;
 use std::process::Command;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use log::{debug, info, warn};
+use serde::Serialize;
+
+use crate::config::{DEFAULT_APPLY_RETRIES, DEFAULT_DNS_IGNORE_PREFIXES, DnsMode, Ipv6Mode, NetworkConfig};
+use crate::i18n::{t, tf};
+
+mod diagnostics;
+mod error;
+mod history;
+mod hook;
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(not(target_os = "linux"))]
+mod macos;
+// 只有 macOS 实现使用，测试在所有平台上运行
+#[cfg(any(not(target_os = "linux"), test))]
+mod parse;
+mod runner;
+
+pub use diagnostics::diagnostics_report;
+pub use error::NetworkError;
+pub use history::{ApplyLogEntry, log_apply, read_apply_log};
+pub use hook::{run_post_apply_hook, set_allow_hooks};
+use runner::CommandOutput;
+
+/// 静态 IP 配置缺省值
+const DEFAULT_STATIC_IP: &str = "192.168.1.100";
+const DEFAULT_SUBNET_MASK: &str = "255.255.255.0";
+const DEFAULT_ROUTER: &str = "192.168.1.1";
+
+/// 平台相关的网络操作
+/// macOS 使用 networksetup，Linux 使用 nmcli；"服务"在 Linux 下对应 NetworkManager 的连接名
+pub trait NetworkBackend: Send + Sync {
+    /// 获取当前连接的 WiFi SSID
+    fn current_ssid(&self) -> Option<String>;
+
+    /// 获取 WiFi 连接状态，区分已关闭、未连接和无法读取 SSID 的隐藏网络
+    fn ssid_status(&self) -> SsidStatus {
+        match self.current_ssid() {
+            Some(ssid) => SsidStatus::Connected(ssid),
+            None => SsidStatus::NotAssociated,
+        }
+    }
 
-use crate::config::NetworkConfig;
+    /// 快速获取 SSID（用于判断网络是否变化，可以不如 current_ssid 可靠）
+    fn quick_ssid(&self) -> Option<String> {
+        self.current_ssid()
+    }
 
-/// 获取当前连接的 WiFi SSID
-pub fn get_current_ssid() -> Option<String> {
-    // 方法1: 使用 ioreg (最可靠，不会被隐私保护遮蔽)
-    if let Some(ssid) = get_ssid_via_ioreg() {
-        return Some(ssid);
+    /// 获取 Wi-Fi 网卡的设备名（如 en0 / en1 / wlan0），没有 Wi-Fi 网卡时返回 None
+    fn wifi_device(&self) -> Option<String> {
+        None
     }
 
-    // 方法2: 使用 networksetup
-    if let Some(ssid) = get_ssid_via_networksetup() {
-        return Some(ssid);
+    /// 获取服务当前使用的网卡设备名（如 en7 / enp3s0）
+    fn service_device(&self, _service: &str) -> Option<String> {
+        None
     }
 
-    // 方法3: 使用 system_profiler
-    if let Some(ssid) = get_ssid_via_system_profiler() {
-        return Some(ssid);
+    /// 网卡的链路是否已连接（网线已插入），无法判断时返回 None
+    fn link_up(&self, _device: &str) -> Option<bool> {
+        None
     }
 
-    None
-}
+    /// 默认路由所在的网卡（跳过 VPN 等隧道接口）
+    fn default_interface(&self) -> Option<String> {
+        None
+    }
 
-/// 通过 ioreg 获取 SSID (不受隐私保护影响)
-fn get_ssid_via_ioreg() -> Option<String> {
-    // 使用 shell 管道直接 grep，比读取全部输出更快
-    let output = Command::new("sh")
-        .args(["-c", "ioreg -l | grep 'IO80211SSID' | head -1"])
-        .output()
-        .ok()?;
+    /// 获取 Wi-Fi 网卡所属的服务名（macOS 的网络服务可以被改名，Linux 为当前的连接名），无法确定时返回 None
+    fn wifi_service(&self) -> Option<String> {
+        None
+    }
 
-    if output.status.success() {
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let line = stdout.trim();
-        if line.contains("IO80211SSID") {
-            // 格式: "IO80211SSID" = "NetworkName"
-            if let Some(start) = line.find("= \"") {
-                let rest = &line[start + 3..];
-                if let Some(end) = rest.find('"') {
-                    let ssid = &rest[..end];
-                    if !ssid.is_empty() {
-                        return Some(ssid.to_string());
-                    }
-                }
-            }
-        }
+    /// 获取所有网络服务
+    fn network_services(&self) -> Vec<String>;
+
+    /// 获取网络服务的状态（包括已停用的服务），无法判断时按已连接处理
+    fn service_state(&self, service: &str) -> ServiceState;
+
+    /// 获取已连接的有线网络服务名
+    fn ethernet_service(&self) -> Option<String>;
+
+    /// 获取路由器 MAC 地址作为网络的唯一标识
+    /// 指定 service 时使用该服务所在接口的网关，避免多网卡或连接 VPN 时取到其他接口的默认路由
+    fn router_mac(&self, service: Option<&str>) -> Option<String>;
+
+    /// 从邻居表（ARP 缓存）中查找 IP 地址对应的 MAC
+    fn neighbor_mac(&self, _ip: &str) -> Option<String> {
+        None
     }
-    None
-}
 
-fn get_ssid_via_networksetup() -> Option<String> {
-    let output = Command::new("networksetup")
-        .args(["-getairportnetwork", "en0"])
-        .output()
-        .ok()?;
+    /// 获取当前连接的 WiFi 接入点 BSSID
+    fn bssid(&self) -> Option<String> {
+        None
+    }
+
+    /// 获取网络服务所用网卡自身的硬件 MAC 地址（如扩展坞的有线网卡），无法获取时返回 None
+    fn interface_mac(&self, _service: &str) -> Option<String> {
+        None
+    }
+
+    /// 是否有已连接的 VPN
+    fn vpn_active(&self) -> bool {
+        false
+    }
+
+    /// 服务是否启用了 HTTP/HTTPS 代理
+    fn proxy_enabled(&self, _service: &str) -> bool {
+        false
+    }
 
-    if output.status.success() {
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        if let Some(ssid) = stdout.strip_prefix("Current Wi-Fi Network: ") {
-            let ssid = ssid.trim();
-            if !ssid.is_empty() && !stdout.contains("not associated") {
-                return Some(ssid.to_string());
+    /// 获取当前网络配置
+    fn current_config(&self, service: &str) -> Result<NetworkConfig, NetworkError>;
+
+    /// 获取链路速度（如 "1000baseT <full-duplex>"），无法获取时返回 None
+    fn link_speed(&self, _service: &str) -> Option<String> {
+        None
+    }
+
+    /// 获取设备允许的 MTU 范围，无法获取时返回 None
+    fn mtu_range(&self, _service: &str) -> Option<(u32, u32)> {
+        None
+    }
+
+    /// 列出 macOS 的「位置」（网络偏好设置中的 Sets），不支持时返回空列表
+    fn list_locations(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// 读取指定位置中每个网络服务的设置，转换为配置（不会切换当前位置）
+    fn location_configs(&self, location: &str) -> Result<Vec<NetworkConfig>, NetworkError> {
+        Err(NetworkError::InvalidConfig(tf("locations_unsupported", &[&location])))
+    }
+
+    /// 扫描信号范围内的 WiFi 网络，不支持或扫描失败时返回空列表
+    fn scan_wifi(&self) -> Vec<ScanResult> {
+        Vec::new()
+    }
+
+    /// 诊断报告中需要记录原始输出的命令（系统版本和各服务的网络设置）
+    fn diagnostic_commands(&self) -> Vec<Vec<String>>;
+
+    /// 执行步骤的命令前准备命令需要的文件（如 DoH 描述文件），plan_steps 只生成命令，不写文件
+    fn prepare_step(&self, _step: &str, _config: &NetworkConfig) -> Result<(), NetworkError> {
+        Ok(())
+    }
+
+    /// 删除应用配置时生成的临时文件，程序退出时调用
+    fn cleanup_temp_files(&self) {}
+
+    /// 连接到指定的 WiFi 网络，password 为空时使用系统已保存的密码
+    fn join_wifi(&self, service: &str, ssid: &str, password: Option<&str>) -> Result<(), String>;
+
+    /// 生成应用配置所需执行的命令（不实际执行）
+    /// 每一项是 (步骤名, argv)，argv 的第一个元素为命令名
+    fn plan_steps(&self, service: &str, config: &NetworkConfig) -> Vec<(&'static str, Vec<String>)>;
+
+    /// 生成应用配置所需执行的命令行（不含步骤名）
+    fn plan_config(&self, service: &str, config: &NetworkConfig) -> Vec<Vec<String>> {
+        self.plan_steps(service, config).into_iter().map(|(_, argv)| argv).collect()
+    }
+
+    /// 应用网络配置，依次执行 plan_config 生成的命令
+    fn apply_config(&self, service: &str, config: &NetworkConfig) -> Result<(), NetworkError> {
+        self.apply_config_with_progress(service, config, &|_| {})
+    }
+
+    /// 应用网络配置，执行每个步骤前通过 progress 报告正在进行的步骤
+    fn apply_config_with_progress(&self, service: &str, config: &NetworkConfig, progress: &dyn Fn(&str)) -> Result<(), NetworkError> {
+        check_mtu_range(self, service, config)?;
+        if config.check_ip_conflict && config.manage_ip && !config.use_dhcp {
+            progress(t("checking_ip_conflict"));
+        }
+        check_ip_conflict(self, service, config)?;
+
+        let mut prepared = None;
+        for (step, argv) in self.plan_steps(service, config) {
+            progress(step_label(step));
+            // 代理等步骤有多条命令，只准备一次
+            if prepared != Some(step) {
+                self.prepare_step(step, config)?;
+                prepared = Some(step);
             }
+            run_step_with_retry(config, service, step, &argv, progress)?;
         }
+
+        Ok(())
     }
-    None
 }
 
-fn get_ssid_via_system_profiler() -> Option<String> {
-    let output = Command::new("system_profiler")
-        .args(["SPAirPortDataType"])
-        .output()
-        .ok()?;
+/// 命令临时失败时的重试次数（来自 AppConfig::apply_retries）
+static APPLY_RETRIES: AtomicU32 = AtomicU32::new(DEFAULT_APPLY_RETRIES);
 
-    if output.status.success() {
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let mut in_current_network = false;
+/// 第一次重试前的等待时间，之后每次翻倍
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
 
-        for line in stdout.lines() {
-            let trimmed = line.trim();
-            if trimmed == "Current Network Information:" {
-                in_current_network = true;
-                continue;
-            }
-            if in_current_network {
-                // SSID 是下一行，格式是 "SSID_NAME:"
-                if trimmed.ends_with(':') && !trimmed.contains("Network") {
-                    let ssid = trimmed.trim_end_matches(':');
-                    if !ssid.is_empty() {
-                        return Some(ssid.to_string());
-                    }
-                }
-                // 如果遇到其他信息就停止
-                if trimmed.starts_with("PHY Mode") || trimmed.starts_with("Other") {
-                    break;
-                }
-            }
-        }
+/// 设置命令临时失败时的重试次数
+pub fn set_apply_retries(retries: u32) {
+    APPLY_RETRIES.store(retries, Ordering::Relaxed);
+}
+
+/// 读取实际使用的 DNS 时忽略的地址前缀（来自 AppConfig::dns_ignore_prefixes），未设置时使用默认值
+static DNS_IGNORE_PREFIXES: Mutex<Option<Vec<String>>> = Mutex::new(None);
+
+/// 设置读取实际使用的 DNS 时忽略的地址前缀
+pub fn set_dns_ignore_prefixes(prefixes: &[String]) {
+    if let Ok(mut guard) = DNS_IGNORE_PREFIXES.lock() {
+        *guard = Some(prefixes.to_vec());
     }
-    None
 }
 
-/// 获取所有网络服务
-pub fn get_network_services() -> Vec<String> {
-    let output = Command::new("networksetup")
-        .args(["-listallnetworkservices"])
-        .output()
-        .ok();
-
-    match output {
-        Some(output) if output.status.success() => {
-            String::from_utf8_lossy(&output.stdout)
-                .lines()
-                .skip(1) // 跳过第一行提示
-                .filter(|line| !line.starts_with('*')) // 跳过禁用的服务
-                .map(|s| s.to_string())
-                .collect()
+/// 读取实际使用的 DNS 时忽略的地址前缀
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+fn dns_ignore_prefixes() -> Vec<String> {
+    DNS_IGNORE_PREFIXES
+        .lock()
+        .ok()
+        .and_then(|guard| guard.clone())
+        .unwrap_or_else(|| DEFAULT_DNS_IGNORE_PREFIXES.iter().map(|s| s.to_string()).collect())
+}
+
+/// 执行一个应用步骤的命令，遇到临时错误时等待后重试（500ms、1s、2s……）
+/// 每次重试都写入历史记录
+fn run_step_with_retry(config: &NetworkConfig, service: &str, step: &str, argv: &[String], progress: &dyn Fn(&str)) -> Result<(), NetworkError> {
+    let args: Vec<&str> = argv[1..].iter().map(|s| s.as_str()).collect();
+    let retries = APPLY_RETRIES.load(Ordering::Relaxed);
+    let mut attempt = 0;
+    loop {
+        debug!("{}: {}", service, argv.join(" "));
+        match run_command(&argv[0], &args) {
+            Err(e) if attempt < retries && e.is_transient() => {
+                attempt += 1;
+                warn!("{} {} 临时失败，第 {} 次重试: {}", service, step_label(step), attempt, e);
+                history::log_retry(config, service, step_label(step), attempt, &e);
+                progress(&tf("step_retry", &[&step_label(step), &attempt]));
+                thread::sleep(RETRY_BASE_DELAY * 2u32.pow(attempt - 1));
+            }
+            result => return result,
         }
-        _ => vec!["Wi-Fi".to_string()],
     }
 }
 
-/// 检测有线网络连接状态
-/// 返回连接的以太网接口名称，如 "Ethernet" 或 "USB 10/100/1000 LAN"
-pub fn get_ethernet_status() -> Option<String> {
-    // 获取所有硬件端口
-    let output = Command::new("networksetup")
-        .args(["-listallhardwareports"])
-        .output()
-        .ok()?;
+/// 应用步骤的显示名称
+pub fn step_label(step: &str) -> &str {
+    match step {
+        "ip" => t("step_ip"),
+        "ipv6" => t("step_ipv6"),
+        "dns" => t("step_dns"),
+        "search" => t("step_search"),
+        "mtu" => t("step_mtu"),
+        "wins" => t("step_wins"),
+        "doh" => t("step_doh"),
+        "proxy" => t("step_proxy"),
+        "activate" => t("step_activate"),
+        other => other,
+    }
+}
 
-    if !output.status.success() {
-        return None;
+/// 检查 MTU 是否在设备允许的范围内
+fn check_mtu_range<B: NetworkBackend + ?Sized>(backend: &B, service: &str, config: &NetworkConfig) -> Result<(), NetworkError> {
+    if let Some(mtu) = config.mtu
+        && let Some((min, max)) = backend.mtu_range(service)
+        && !(min..=max).contains(&mtu)
+    {
+        return Err(NetworkError::InvalidConfig(tf("err_mtu_device_range", &[&mtu, &min, &max])));
     }
+    Ok(())
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut current_service: Option<String> = None;
-    let mut current_device: Option<String> = None;
-
-    for line in stdout.lines() {
-        let line = line.trim();
-        if let Some(name) = line.strip_prefix("Hardware Port: ") {
-            current_service = Some(name.to_string());
-        } else if let Some(dev) = line.strip_prefix("Device: ") {
-            current_device = Some(dev.to_string());
-        }
+/// 检查静态 IP 是否已被局域网中的其他设备占用（配置开启 check_ip_conflict 时）
+fn check_ip_conflict<B: NetworkBackend + ?Sized>(backend: &B, service: &str, config: &NetworkConfig) -> Result<(), NetworkError> {
+    if !config.manage_ip || config.use_dhcp || !config.check_ip_conflict {
+        return Ok(());
+    }
+    let ip = config.ip_address.as_deref().unwrap_or(DEFAULT_STATIC_IP);
 
-        // 当我们有了服务名和设备名，检查是否是以太网且已连接
-        if let (Some(service), Some(device)) = (&current_service, &current_device) {
-            // 检查是否是以太网类型（排除 Wi-Fi 和 Bluetooth）
-            let is_ethernet = !service.to_lowercase().contains("wi-fi")
-                && !service.to_lowercase().contains("bluetooth")
-                && !service.to_lowercase().contains("thunderbolt bridge")
-                && (service.to_lowercase().contains("ethernet")
-                    || service.to_lowercase().contains("lan")
-                    || service.to_lowercase().contains("usb")
-                    || device.starts_with("en"));
-
-            if is_ethernet {
-                // 检查接口是否有 IP（即已连接）
-                if let Ok(info_output) = Command::new("networksetup")
-                    .args(["-getinfo", service])
-                    .output()
-                {
-                    let info = String::from_utf8_lossy(&info_output.stdout);
-                    // 如果有 IP 地址，说明已连接
-                    for info_line in info.lines() {
-                        if info_line.starts_with("IP address:") {
-                            let ip = info_line.replace("IP address:", "").trim().to_string();
-                            if !ip.is_empty() && ip != "none" {
-                                return Some(service.clone());
-                            }
-                        }
-                    }
-                }
-            }
+    // 重新应用本机正在使用的地址不算冲突
+    let current_ip = backend.current_config(service).ok().and_then(|current| current.ip_address);
+    if current_ip.as_deref() == Some(ip) {
+        return Ok(());
+    }
 
-            current_service = None;
-            current_device = None;
-        }
+    // ping 一次让系统发出 ARP 请求，有设备应答时邻居表中会出现它的 MAC
+    ping(ip);
+    match backend.neighbor_mac(ip) {
+        Some(mac) => Err(NetworkError::AddressInUse { ip: ip.to_string(), mac }),
+        None => Ok(()),
     }
+}
 
-    None
+/// 是否为 VPN 等隧道接口（其默认路由不代表所在的局域网）
+fn is_tunnel_interface(interface: &str) -> bool {
+    ["utun", "ppp", "ipsec", "tun", "tap", "wg", "gif", "stf", "tailscale"]
+        .iter()
+        .any(|prefix| interface.starts_with(prefix))
 }
 
-/// 获取路由器 MAC 地址作为网络的唯一标识
-pub fn get_router_mac() -> Option<String> {
-    // 1. 先获取默认路由器 IP
-    let output = Command::new("sh")
-        .args(["-c", "netstat -rn | grep default | awk '{print $2}' | head -1"])
-        .output()
-        .ok()?;
+/// 获取当前平台的网络实现
+pub fn backend() -> &'static dyn NetworkBackend {
+    #[cfg(target_os = "linux")]
+    {
+        &linux::NmcliBackend
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        &macos::MacBackend
+    }
+}
 
-    if !output.status.success() {
-        return None;
+/// 网络服务的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceState {
+    /// 已连接
+    Active,
+    /// 已启用但没有连接（如网线未插、WiFi 未连接）
+    Disconnected,
+    /// 在系统设置中停用
+    Disabled,
+    /// 服务不存在（如 USB 网卡已拔出或服务被删除）
+    Missing,
+}
+
+impl ServiceState {
+    pub fn label(self) -> &'static str {
+        match self {
+            ServiceState::Active => t("service_active"),
+            ServiceState::Disconnected => t("service_disconnected"),
+            ServiceState::Disabled => t("service_disabled"),
+            ServiceState::Missing => t("service_missing"),
+        }
     }
 
-    let router_ip = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    if router_ip.is_empty() {
-        return None;
+    /// 能否把配置应用到该服务（已停用或不存在的服务无法应用）
+    pub fn can_apply(self) -> bool {
+        matches!(self, ServiceState::Active | ServiceState::Disconnected)
     }
+}
 
-    // 2. 通过 ARP 获取路由器 MAC
-    let arp_output = Command::new("arp")
-        .args(["-n", &router_ip])
-        .output()
-        .ok()?;
+/// WiFi 连接状态
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
+#[serde(tag = "state", content = "ssid", rename_all = "snake_case")]
+pub enum SsidStatus {
+    /// 已连接到指定 SSID
+    Connected(String),
+    /// WiFi 已打开但没有连接
+    #[default]
+    NotAssociated,
+    /// WiFi 已关闭
+    PoweredOff,
+    /// 已连接但读不到 SSID（隐藏网络或被系统隐私保护遮蔽）
+    Hidden,
+}
 
-    if arp_output.status.success() {
-        let stdout = String::from_utf8_lossy(&arp_output.stdout);
-        // 格式: ? (192.168.1.1) at aa:bb:cc:dd:ee:ff on en0 ifscope [ethernet]
-        if let Some(at_pos) = stdout.find(" at ") {
-            let rest = &stdout[at_pos + 4..];
-            if let Some(on_pos) = rest.find(" on ") {
-                let mac = rest[..on_pos].trim();
-                if !mac.is_empty() && mac != "(incomplete)" {
-                    return Some(mac.to_lowercase());
-                }
-            }
+impl SsidStatus {
+    /// 已连接时的 SSID
+    pub fn ssid(&self) -> Option<&str> {
+        match self {
+            SsidStatus::Connected(ssid) => Some(ssid),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            SsidStatus::Connected(ssid) => ssid,
+            SsidStatus::NotAssociated => t("wifi_not_associated"),
+            SsidStatus::PoweredOff => t("wifi_powered_off"),
+            SsidStatus::Hidden => t("wifi_hidden"),
         }
     }
+}
+
+/// WiFi 扫描到的网络
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanResult {
+    pub ssid: String,
+    /// 接入点 BSSID，系统隐藏时为空
+    pub bssid: Option<String>,
+}
 
-    None
+/// 扫描信号范围内的 WiFi 网络
+pub fn scan_wifi() -> Vec<ScanResult> {
+    backend().scan_wifi()
 }
 
 /// 获取当前网络的完整标识信息
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct NetworkIdentity {
-    pub ssid: Option<String>,           // WiFi SSID
-    pub router_mac: Option<String>,     // 路由器 MAC 地址
-    pub is_wired: bool,                 // 是否有线
-    pub service_name: Option<String>,   // 有线网络服务名
+    pub ssid: Option<String>,             // WiFi SSID
+    pub router_mac: Option<String>,       // 路由器 MAC 地址
+    pub bssid: Option<String>,            // WiFi 接入点 BSSID
+    pub is_wired: bool,                   // 是否有线
+    pub service_name: Option<String>,     // 有线网络服务名
+    pub adapter_mac: Option<String>,      // 有线网卡自身的 MAC 地址（区分相同型号的扩展坞）
+    pub vpn_active: bool,                 // 是否有已连接的 VPN
+    pub proxy_enabled: bool,              // 当前服务是否启用了 HTTP/HTTPS 代理
+    pub captive_portal: bool,             // WiFi 是否需要登录门户
+    pub portal_url: Option<String>,       // 登录门户地址
+    pub wifi_status: SsidStatus,          // WiFi 连接状态
+    pub wifi_device: Option<String>,      // WiFi 网卡设备名（如 en0）
+    pub ethernet_service: Option<String>, // 已连接的有线服务（同时连着 WiFi 时也会记录）
 }
 
 
 
+/// 网络标识缓存有效期
+const IDENTITY_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// 缓存的网络标识
+struct CachedIdentity {
+    identity: NetworkIdentity,
+    fetched_at: Instant,
+    // 获取时快速查询到的 SSID、默认路由的网卡和已连接的有线网卡，用于判断网络是否变化
+    quick_ssid: Option<String>,
+    default_interface: Option<String>,
+    wired_device: Option<String>,
+}
+
+static IDENTITY_CACHE: Mutex<Option<CachedIdentity>> = Mutex::new(None);
+
 /// 获取当前网络的完整标识
-pub fn get_network_identity() -> NetworkIdentity {
-    let router_mac = get_router_mac();
+/// 结果会缓存一段时间，期间只快速检查 SSID、默认路由的网卡和之前连接的有线网卡的链路（拔网线）是否变化，
+/// 每项只执行一条命令，避免频繁执行 ioreg/netstat/arp 和逐个检查硬件端口；force 为 true 时忽略缓存
+pub fn get_network_identity(force: bool) -> NetworkIdentity {
+    let backend = backend();
+    let quick_ssid = backend.quick_ssid();
+    let default_interface = backend.default_interface();
+
+    if !force
+        && let Ok(cache) = IDENTITY_CACHE.lock()
+        && let Some(cached) = cache.as_ref()
+        && cached.fetched_at.elapsed() < IDENTITY_CACHE_TTL
+        && cached.quick_ssid == quick_ssid
+        && cached.default_interface == default_interface
+        && cached.wired_device.as_deref().is_none_or(|device| backend.link_up(device) != Some(false))
+    {
+        return cached.identity.clone();
+    }
+
+    let identity = detect_network_identity();
+    let wired_device = identity.ethernet_service.as_deref().and_then(|service| backend.service_device(service));
+    if let Ok(mut cache) = IDENTITY_CACHE.lock() {
+        *cache = Some(CachedIdentity {
+            identity: identity.clone(),
+            fetched_at: Instant::now(),
+            quick_ssid,
+            default_interface,
+            wired_device,
+        });
+    }
+    identity
+}
+
+/// 实际检测当前网络的完整标识
+fn detect_network_identity() -> NetworkIdentity {
+    let backend = backend();
+    let vpn_active = backend.vpn_active();
+    let ethernet_service = backend.ethernet_service();
 
     // 优先检查 WiFi
-    if let Some(ssid) = get_current_ssid() {
+    let wifi_status = backend.ssid_status();
+    if let Some(ssid) = wifi_status.ssid().map(str::to_string) {
+        // Wi-Fi 服务可能被改名（Linux 上为连接名），按 Wi-Fi 网卡查找
+        let wifi_service = backend.wifi_service();
+        let router_mac = backend.router_mac(wifi_service.as_deref());
+        let portal_url = captive_portal_url(&ssid, router_mac.as_deref());
         return NetworkIdentity {
             ssid: Some(ssid),
             router_mac,
+            bssid: backend.bssid(),
             is_wired: false,
             service_name: None,
+            adapter_mac: None,
+            vpn_active,
+            proxy_enabled: wifi_service.as_deref().is_some_and(|service| backend.proxy_enabled(service)),
+            captive_portal: portal_url.is_some(),
+            portal_url,
+            wifi_status,
+            wifi_device: backend.wifi_device(),
+            ethernet_service,
         };
     }
 
     // 检查有线网络
-    if let Some(ethernet) = get_ethernet_status() {
+    if let Some(ethernet) = ethernet_service {
+        let proxy_enabled = backend.proxy_enabled(&ethernet);
+        let router_mac = backend.router_mac(Some(&ethernet));
+        let adapter_mac = backend.interface_mac(&ethernet);
         return NetworkIdentity {
             ssid: None,
             router_mac,
+            bssid: None,
             is_wired: true,
-            service_name: Some(ethernet),
+            service_name: Some(ethernet.clone()),
+            adapter_mac,
+            vpn_active,
+            proxy_enabled,
+            captive_portal: false,
+            portal_url: None,
+            wifi_status,
+            wifi_device: None,
+            ethernet_service: Some(ethernet),
         };
     }
 
-    NetworkIdentity::default()
+    NetworkIdentity {
+        vpn_active,
+        wifi_status,
+        ..NetworkIdentity::default()
+    }
 }
 
-/// 获取当前网络配置
-pub fn get_current_config(service: &str) -> NetworkConfig {
-    let mut config = NetworkConfig::default();
+/// 门户检测地址，正常网络返回空的 204 响应
+const CAPTIVE_CHECK_URL: &str = "http://connectivitycheck.gstatic.com/generate_204";
 
-    // 获取 IP 信息
-    if let Ok(output) = Command::new("networksetup")
-        .args(["-getinfo", service])
-        .output()
+/// 上次门户检测的结果：(SSID, 路由器 MAC, 门户地址)
+type CaptiveCheck = (String, Option<String>, Option<String>);
+
+static CAPTIVE_CHECK: Mutex<Option<CaptiveCheck>> = Mutex::new(None);
+
+/// 检测当前 WiFi 是否需要登录门户，需要时返回门户地址
+/// 每个网络只检测一次，网络变化或调用 reset_captive_check 后重新检测
+fn captive_portal_url(ssid: &str, router_mac: Option<&str>) -> Option<String> {
+    if let Ok(cache) = CAPTIVE_CHECK.lock()
+        && let Some((cached_ssid, cached_mac, portal)) = cache.as_ref()
+        && cached_ssid == ssid
+        && cached_mac.as_deref() == router_mac
     {
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        for line in stdout.lines() {
-            if let Some(ip) = line.strip_prefix("IP address: ") {
-                config.ip_address = Some(ip.trim().to_string());
-            } else if let Some(mask) = line.strip_prefix("Subnet mask: ") {
-                config.subnet_mask = Some(mask.trim().to_string());
-            } else if let Some(router) = line.strip_prefix("Router: ") {
-                config.router = Some(router.trim().to_string());
-            }
-        }
-        config.use_dhcp = stdout.contains("DHCP Configuration");
+        return portal.clone();
     }
 
-    // 获取 DNS (先尝试 networksetup，再尝试 scutil)
-    config.dns_servers = get_dns_servers(service);
+    let portal = check_captive_portal();
+    if let Ok(mut cache) = CAPTIVE_CHECK.lock() {
+        *cache = Some((ssid.to_string(), router_mac.map(str::to_string), portal.clone()));
+    }
+    portal
+}
 
-    config
+/// 请求检测地址，返回不是空的 204 时认为被门户拦截
+fn check_captive_portal() -> Option<String> {
+    // 输出格式: 204 0 （状态码、响应大小、重定向地址）
+    let output = Command::new("curl")
+        .args(["-s", "-o", "/dev/null", "--max-time", "5", "-w", "%{http_code} %{size_download} %{redirect_url}", CAPTIVE_CHECK_URL])
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut fields = stdout.split_whitespace();
+    let code = fields.next()?;
+    let size = fields.next()?;
+
+    // 请求失败（如没有网络）时状态码为 000，不算门户
+    if code == "000" || (code == "204" && size == "0") {
+        return None;
+    }
+    Some(fields.next().unwrap_or(CAPTIVE_CHECK_URL).to_string())
 }
 
-/// 获取 DNS 服务器
-fn get_dns_servers(service: &str) -> Vec<String> {
-    // 方法1: 从 networksetup 获取该服务配置的 DNS
-    if let Ok(output) = Command::new("networksetup")
-        .args(["-getdnsservers", service])
+/// 通过回显服务查询当前的公网 IP，url 需要返回纯文本的 IP 地址
+/// 最多等待 5 秒，没有网络时返回错误
+pub fn fetch_public_ip(url: &str) -> Result<String, String> {
+    let output = Command::new("curl")
+        .args(["-sS", "--max-time", "5", url])
         .output()
-    {
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        if !stdout.contains("There aren't any DNS Servers") {
-            let servers: Vec<String> = stdout
-                .lines()
-                .filter(|line| !line.is_empty() && !line.contains("Error"))
-                .map(|s| s.trim().to_string())
-                .collect();
-            if !servers.is_empty() {
-                return servers;
-            }
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    let body = String::from_utf8_lossy(&output.stdout);
+    let body = body.trim();
+    body.parse::<std::net::IpAddr>()
+        .map(|ip| ip.to_string())
+        .map_err(|_| tf("unrecognized_response", &[&body.chars().take(40).collect::<String>()]))
+}
+
+/// 清除门户检测结果，下次获取网络标识时重新检测（如登录门户之后）
+pub fn reset_captive_check() {
+    if let Ok(mut cache) = CAPTIVE_CHECK.lock() {
+        *cache = None;
+    }
+}
+
+/// 默认的配置应用顺序
+pub const DEFAULT_APPLY_STEPS: [&str; 8] = ["ip", "ipv6", "dns", "search", "mtu", "wins", "doh", "proxy"];
+
+/// 未设置 MTU 时使用的缺省值
+const DEFAULT_MTU: u32 = 1500;
+
+/// 计算实际的应用顺序：先按配置指定的顺序，未列出的步骤按默认顺序追加
+fn apply_steps(config: &NetworkConfig) -> Vec<&'static str> {
+    let mut steps: Vec<&'static str> = Vec::new();
+
+    for name in &config.apply_steps_order {
+        let name = name.trim().to_lowercase();
+        if name.is_empty() {
+            continue;
+        }
+        match DEFAULT_APPLY_STEPS.iter().find(|step| **step == name) {
+            Some(step) if !steps.contains(step) => steps.push(step),
+            Some(_) => {}
+            None => warn!("配置 {} 中存在未知的应用步骤: {}，已忽略", config.name, name),
         }
     }
 
-    // 方法2: 从 scutil --dns 获取实际使用的 DNS
-    if let Ok(output) = Command::new("scutil")
-        .args(["--dns"])
-        .output()
-    {
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let mut servers = Vec::new();
-
-        for line in stdout.lines() {
-            let trimmed = line.trim();
-            if trimmed.starts_with("nameserver") {
-                // 格式: "nameserver[0] : 8.8.8.8"
-                if let Some(dns) = trimmed.split(':').nth(1) {
-                    let dns = dns.trim();
-                    // 过滤掉 VPN 的 DNS (198.18.x.x)
-                    if !dns.starts_with("198.18.") && !servers.contains(&dns.to_string()) {
-                        servers.push(dns.to_string());
-                    }
-                }
+    for step in DEFAULT_APPLY_STEPS {
+        if !steps.contains(&step) {
+            steps.push(step);
+        }
+    }
+
+    // 不管理的设置、未设置的 MTU、WINS、DoH、代理或 DNS 选择保持不变时保持系统当前值
+    if !config.manage_ip {
+        steps.retain(|step| !matches!(*step, "ip" | "ipv6"));
+    }
+    if config.ipv6_mode == Ipv6Mode::Unmanaged {
+        steps.retain(|step| *step != "ipv6");
+    }
+    if !config.manage_dns {
+        steps.retain(|step| !matches!(*step, "dns" | "search"));
+    }
+    if config.dns_mode == DnsMode::Inherit {
+        steps.retain(|step| *step != "dns");
+    }
+    if config.mtu.is_none() {
+        steps.retain(|step| *step != "mtu");
+    }
+    if config.wins_servers.is_empty() {
+        steps.retain(|step| *step != "wins");
+    }
+    if config.doh_template.is_none() {
+        steps.retain(|step| *step != "doh");
+    }
+    if !config.manages_proxy() {
+        steps.retain(|step| *step != "proxy");
+    }
+
+    steps
+}
+
+/// 把 argv 格式化为可读的命令行（含空格的参数加引号）
+pub fn format_command(argv: &[String]) -> String {
+    argv.iter()
+        .map(|arg| {
+            if arg.is_empty() || arg.contains(char::is_whitespace) {
+                format!("\"{}\"", arg)
+            } else {
+                arg.clone()
             }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 应用网络配置，失败时自动恢复到应用前的配置
+/// 配置要求检测连通性时，检测失败也会回滚；progress 用于报告正在进行的步骤
+pub fn apply_config_with_rollback(service: &str, config: &NetworkConfig, progress: &dyn Fn(&str)) -> Result<(), NetworkError> {
+    info!("应用配置 {} -> {}", config.name, service);
+    // 先保存当前配置快照，读取失败时无法回滚
+    let backend = backend();
+    progress(t("reading_current_config"));
+    let snapshot = backend.current_config(service);
+
+    let result = backend.apply_config_with_progress(service, config, progress).and_then(|()| {
+        if config.probe_after_apply {
+            progress(t("probing_connectivity"));
+            probe_connectivity(service).map_err(NetworkError::ProbeFailed)
+        } else {
+            Ok(())
         }
+    });
 
-        if !servers.is_empty() {
-            return servers;
+    match result {
+        Ok(()) => {
+            info!("已应用配置 {} -> {}", config.name, service);
+            Ok(())
         }
+        // 权限不足时命令没有执行成功，也无法回滚
+        Err(e @ NetworkError::PermissionDenied { .. }) => Err(e),
+        // 地址冲突在执行命令前就检查出来了，不需要回滚
+        Err(e @ NetworkError::AddressInUse { .. }) => Err(e),
+        Err(e) => {
+            warn!("应用配置 {} -> {} 失败，回滚: {}", config.name, service, e);
+            progress(t("rolling_back"));
+            let rollback = snapshot.and_then(|snapshot| backend.apply_config(service, &snapshot));
+            Err(NetworkError::RolledBack {
+                error: Box::new(e),
+                rollback_error: rollback.err().map(Box::new),
+            })
+        }
+    }
+}
+
+/// 应用后等待多久再检测连通性
+const PROBE_DELAY: Duration = Duration::from_secs(5);
+
+/// 检测连通性时解析的公网域名
+const PROBE_HOSTNAME: &str = "apple.com";
+
+/// 等待新配置生效后检测网络是否可用：能 ping 通路由器并能解析公网域名
+pub fn probe_connectivity(service: &str) -> Result<(), String> {
+    thread::sleep(PROBE_DELAY);
+
+    // DHCP 可能还没拿到路由器地址，此时只检查域名解析
+    if let Some(router) = backend().current_config(service).ok().and_then(|config| config.router)
+        && !ping(&router)
+    {
+        return Err(tf("ping_router_failed", &[&router]));
     }
 
-    Vec::new()
+    let mut addrs = (PROBE_HOSTNAME, 443)
+        .to_socket_addrs()
+        .map_err(|e| tf("resolve_failed", &[&PROBE_HOSTNAME, &e]))?;
+    if addrs.next().is_none() {
+        return Err(tf("no_resolve_result", &[&PROBE_HOSTNAME]));
+    }
+    Ok(())
 }
 
-/// 应用网络配置
-pub fn apply_config(service: &str, config: &NetworkConfig) -> Result<(), String> {
-    if config.use_dhcp {
-        // 使用 DHCP
-        run_command("networksetup", &["-setdhcp", service])?;
-    } else {
-        // 使用静态 IP
-        let ip = config.ip_address.as_deref().unwrap_or("192.168.1.100");
-        let mask = config.subnet_mask.as_deref().unwrap_or("255.255.255.0");
-        let router = config.router.as_deref().unwrap_or("192.168.1.1");
-        
-        run_command("networksetup", &["-setmanual", service, ip, mask, router])?;
+/// 后台联网检测 ping 的公网地址，直接用 IP 避免依赖 DNS
+const REACHABILITY_HOST: &str = "223.5.5.5";
+
+/// 联网状态
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Reachability {
+    /// 可以访问公网，附带往返延迟
+    Online(Duration),
+    /// 路由器可达，但无法访问公网
+    RouterOnly,
+    /// 路由器和公网都不可达
+    Offline,
+}
+
+/// 检测当前是否能访问公网，不能时再检查路由器，区分"没有外网"和"完全断网"
+pub fn check_reachability(router: Option<&str>) -> Reachability {
+    if let Some(latency) = ping_latency(REACHABILITY_HOST) {
+        return Reachability::Online(latency);
+    }
+    match router {
+        Some(router) if ping(router) => Reachability::RouterOnly,
+        _ => Reachability::Offline,
     }
+}
 
-    // 设置 DNS
-    if config.dns_servers.is_empty() {
-        run_command("networksetup", &["-setdnsservers", service, "Empty"])?;
-    } else {
-        let mut args = vec!["-setdnsservers", service];
-        for dns in &config.dns_servers {
-            args.push(dns.as_str());
+/// ping 一次指定地址，2 秒内没有响应视为失败
+fn ping(host: &str) -> bool {
+    ping_once(host).is_some_and(|output| output.status.success())
+}
+
+/// ping 一次指定地址，返回往返延迟
+fn ping_latency(host: &str) -> Option<Duration> {
+    let output = ping_once(host).filter(|output| output.status.success())?;
+    parse_ping_time(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn ping_once(host: &str) -> Option<std::process::Output> {
+    // Linux 的 -W 为等待秒数，macOS 用 -t 指定超时秒数
+    #[cfg(target_os = "linux")]
+    let args = ["-c", "1", "-W", "2", host];
+    #[cfg(not(target_os = "linux"))]
+    let args = ["-c", "1", "-t", "2", host];
+
+    Command::new("ping").args(args).output().ok()
+}
+
+/// 从 ping 输出的 "time=12.3 ms" 中取出延迟
+fn parse_ping_time(output: &str) -> Option<Duration> {
+    let millis: f64 = output
+        .split_whitespace()
+        .find_map(|word| word.strip_prefix("time="))?
+        .parse()
+        .ok()?;
+    Some(Duration::from_secs_f64(millis / 1000.0))
+}
+
+/// 校验配置是否实际生效，返回未生效的字段列表
+pub fn verify_config(service: &str, expected: &NetworkConfig) -> Result<(), Vec<String>> {
+    let Ok(current) = backend().current_config(service) else {
+        return Err(vec![t("verify_read_failed").to_string()]);
+    };
+    let mut mismatched = Vec::new();
+
+    if !expected.manage_ip {
+        // 不管理 IP 设置时不比较地址
+    } else if expected.use_dhcp != current.use_dhcp {
+        mismatched.push("DHCP".to_string());
+    } else if !expected.use_dhcp {
+        // 静态 IP 才需要比较地址（DHCP 分配的地址不可预知）
+        let ip = expected.ip_address.as_deref().unwrap_or(DEFAULT_STATIC_IP);
+        let mask = expected.subnet_mask.as_deref().unwrap_or(DEFAULT_SUBNET_MASK);
+        let router = expected.router.as_deref().unwrap_or(DEFAULT_ROUTER);
+        if current.ip_address.as_deref() != Some(ip) {
+            mismatched.push("IP".to_string());
+        }
+        if current.subnet_mask.as_deref() != Some(mask) {
+            mismatched.push(t("field_subnet_mask").to_string());
+        }
+        if current.router.as_deref() != Some(router) {
+            mismatched.push(t("field_router").to_string());
         }
-        run_command("networksetup", &args)?;
     }
 
-    Ok(())
-}
+    if expected.manage_ip
+        && expected.ipv6_mode == Ipv6Mode::Manual
+        && let Some(ipv6) = expected.ipv6_address.as_deref()
+        && current.ipv6_address.as_deref() != Some(ipv6)
+    {
+        mismatched.push("IPv6".to_string());
+    }
 
-fn run_command(cmd: &str, args: &[&str]) -> Result<(), String> {
-    let output = Command::new(cmd)
-        .args(args)
-        .output()
-        .map_err(|e| e.to_string())?;
+    // 只有手动 DNS 才比较，自动获取或保持不变时实际使用的 DNS 不可预知
+    if expected.manage_dns && expected.dns_mode == DnsMode::Manual && expected.dns_servers != current.dns_servers {
+        mismatched.push("DNS".to_string());
+    }
+
+    if expected.manage_dns && expected.search_domains != current.search_domains {
+        mismatched.push(t("field_search_domains").to_string());
+    }
+
+    if expected.mtu.is_some() && expected.mtu != current.mtu {
+        mismatched.push("MTU".to_string());
+    }
 
-    if output.status.success() {
+    if !expected.wins_servers.is_empty() && expected.wins_servers != current.wins_servers {
+        mismatched.push("WINS".to_string());
+    }
+
+    if mismatched.is_empty() {
         Ok(())
     } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+        Err(mismatched)
+    }
+}
+
+/// 测试 DNS 时解析的域名
+const DNS_TEST_DOMAIN: &str = "example.com";
+
+/// 用指定的 DNS 服务器解析测试域名，成功时返回耗时
+pub fn test_dns_server(server: &str) -> Result<Duration, String> {
+    let started = Instant::now();
+    let output = Command::new("dig")
+        .args([&format!("@{}", server), DNS_TEST_DOMAIN, "+time=2", "+tries=1", "+short"])
+        .output()
+        .map_err(|e| tf("dig_failed", &[&e]))?;
+    let elapsed = started.elapsed();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() {
+        // dig 超时等错误信息输出在 stdout（以 ;; 开头）
+        let reason = stdout.lines().find(|l| l.starts_with(";;")).unwrap_or("").trim_start_matches(";; ");
+        return Err(if reason.is_empty() { t("dns_lookup_failed") } else { reason }.to_string());
+    }
+    if stdout.trim().is_empty() {
+        return Err(t("dns_no_answer").to_string());
+    }
+    Ok(elapsed)
+}
+
+/// 以管理员身份应用配置（macOS 通过 osascript 弹出授权对话框，Linux 通过 pkexec）
+pub fn apply_config_elevated(service: &str, config: &NetworkConfig) -> Result<(), NetworkError> {
+    let backend = backend();
+    check_mtu_range(backend, service, config)?;
+    check_ip_conflict(backend, service, config)?;
+
+    let plan = backend.plan_steps(service, config);
+    let mut prepared = None;
+    for (step, _) in &plan {
+        if prepared != Some(*step) {
+            backend.prepare_step(step, config)?;
+            prepared = Some(*step);
+        }
+    }
+
+    let script = plan
+        .iter()
+        .map(|(_, argv)| argv.iter().map(|arg| shell_quote(arg)).collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>()
+        .join(" && ");
+
+    run_elevated(&script)
+}
+
+/// 以管理员身份执行 shell 命令（macOS 通过 osascript 弹出授权对话框，Linux 通过 pkexec）
+fn run_elevated(script: &str) -> Result<(), NetworkError> {
+    #[cfg(target_os = "linux")]
+    {
+        run_command("pkexec", &["sh", "-c", script])
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let apple_script = format!(
+            "do shell script \"{}\" with administrator privileges",
+            script.replace('\\', "\\\\").replace('"', "\\\"")
+        );
+        run_command("osascript", &["-e", &apple_script])
     }
 }
 
+/// 安装描述文件的结果（Linux 上不支持安装，不会构造）
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProfileInstall {
+    /// 已通过 profiles 命令安装
+    Installed,
+    /// 已在系统设置中打开，等待用户确认安装
+    PendingApproval,
+    /// 用户取消了管理员授权
+    Declined,
+}
+
+impl ProfileInstall {
+    pub fn label(self) -> &'static str {
+        match self {
+            ProfileInstall::Installed => t("profile_installed"),
+            ProfileInstall::PendingApproval => t("profile_pending"),
+            ProfileInstall::Declined => t("profile_declined"),
+        }
+    }
+}
+
+/// 安装 802.1X 等企业网络的描述文件（.mobileconfig）
+/// macOS 11 之前以管理员身份执行 profiles install（弹出授权对话框），
+/// 之后的版本不再支持命令行安装，改为在系统设置中打开，由用户确认
+pub fn install_profile(path: &str) -> Result<ProfileInstall, NetworkError> {
+    if !std::path::Path::new(path).is_file() {
+        return Err(NetworkError::InvalidConfig(tf("profile_not_found", &[&path])));
+    }
+    info!("安装描述文件 {}", path);
+
+    #[cfg(target_os = "linux")]
+    {
+        Err(NetworkError::InvalidConfig(t("profile_macos_only").to_string()))
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        if macos_major_version().is_none_or(|major| major >= 11) {
+            return run_command("open", &[path]).map(|()| ProfileInstall::PendingApproval);
+        }
+        match run_elevated(&format!("profiles install -path {}", shell_quote(path))) {
+            Ok(()) => Ok(ProfileInstall::Installed),
+            // osascript 授权对话框被取消时返回 -128
+            Err(NetworkError::CommandFailed { stderr, .. }) if stderr.contains("(-128)") => Ok(ProfileInstall::Declined),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// macOS 的主版本号（如 14），无法读取时为 None
+#[cfg(not(target_os = "linux"))]
+fn macos_major_version() -> Option<u32> {
+    let version = command_output("sw_vers", &["-productVersion"]).ok()?;
+    version.trim().split('.').next()?.parse().ok()
+}
+
+/// 给 shell 参数加单引号
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
+fn run_command(cmd: &str, args: &[&str]) -> Result<(), NetworkError> {
+    command_output(cmd, args).map(|_| ())
+}
+
+/// 执行命令并返回标准输出，失败时按原因区分错误类型
+fn command_output(cmd: &str, args: &[&str]) -> Result<String, NetworkError> {
+    let output = runner::run(cmd, args).map_err(|e| NetworkError::from_io(cmd, e))?;
+    if output.success() {
+        return Ok(output.stdout);
+    }
+
+    let CommandOutput { code, stdout, stderr } = output;
+    if looks_like_permission_error(&stderr) || looks_like_permission_error(&stdout) {
+        let detail = if stderr.trim().is_empty() { stdout.trim() } else { stderr.trim() };
+        return Err(NetworkError::PermissionDenied {
+            admin: current_user_is_admin(),
+            detail: detail.to_string(),
+        });
+    }
+    Err(NetworkError::CommandFailed { code, stderr })
+}
+
+/// 命令输出是否像是权限不足导致的失败
+fn looks_like_permission_error(output: &str) -> bool {
+    let output = output.to_lowercase();
+    ["not allowed", "requires admin", "requires root", "permission denied", "not authorized", "operation not permitted", "not privileged"]
+        .iter()
+        .any(|pattern| output.contains(pattern))
+}
+
+/// 当前用户是否属于管理员组（macOS 为 admin，Linux 通常为 sudo 或 wheel），无法判断时按是处理
+pub fn current_user_is_admin() -> bool {
+    runner::run("id", &["-Gn"])
+        .map(|output| {
+            output
+                .stdout
+                .split_whitespace()
+                .any(|group| matches!(group, "admin" | "sudo" | "wheel" | "root"))
+        })
+        .unwrap_or(true)
+}
+
@@ -0,0 +1,787 @@
+//! networksetup / ioreg / arp 等命令输出的解析，只处理文本，不执行命令
+
+use super::{ScanResult, is_tunnel_interface};
+
+/// networksetup -getinfo 的结果
+#[derive(Debug, Default, PartialEq)]
+pub struct ServiceInfo {
+    pub dhcp: bool,
+    pub ip_address: Option<String>,
+    pub subnet_mask: Option<String>,
+    pub router: Option<String>,
+    /// IPv6 配置方式（Automatic、Manual、Off 等）
+    pub ipv6_method: Option<String>,
+    pub ipv6_address: Option<String>,
+    pub ipv6_prefix: Option<u8>,
+    pub ipv6_router: Option<String>,
+}
+
+/// 解析 networksetup -getinfo 的输出
+/// 格式: IP address: 192.168.1.10 / Subnet mask: ... / Router: ... / IPv6 IP address: none
+/// IPv4 字段保留原值，IPv6 字段的 none 视为未设置
+/// 只有 "IPv6: Manual" 时读取 IPv6 地址，自动配置时的地址是 SLAAC 或临时地址，不能作为静态地址恢复
+pub fn parse_getinfo(output: &str) -> ServiceInfo {
+    let mut info = ServiceInfo {
+        dhcp: output.contains("DHCP Configuration"),
+        ..ServiceInfo::default()
+    };
+    info.ipv6_method = output
+        .lines()
+        .find_map(|line| line.strip_prefix("IPv6: "))
+        .map(|method| method.trim().to_string());
+    let ipv6_manual = info.ipv6_method.as_deref() == Some("Manual");
+    for line in output.lines() {
+        if let Some(ip) = line.strip_prefix("IP address: ") {
+            info.ip_address = Some(ip.trim().to_string());
+        } else if let Some(mask) = line.strip_prefix("Subnet mask: ") {
+            info.subnet_mask = Some(mask.trim().to_string());
+        } else if let Some(router) = line.strip_prefix("Router: ") {
+            info.router = Some(router.trim().to_string());
+        } else if ipv6_manual && let Some(ip) = line.strip_prefix("IPv6 IP address: ") {
+            info.ipv6_address = parse_optional_value(ip);
+        } else if ipv6_manual && let Some(prefix) = line.strip_prefix("IPv6 Prefix Length: ") {
+            info.ipv6_prefix = prefix.trim().parse().ok();
+        } else if ipv6_manual && let Some(router) = line.strip_prefix("IPv6 Router: ") {
+            info.ipv6_router = parse_optional_value(router);
+        }
+    }
+    info
+}
+
+/// 解析 networksetup 输出中的值，"none" 或空值视为未设置
+pub fn parse_optional_value(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() || value == "none" {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+/// 解析 -getdnsservers / -getsearchdomains / -getwinsservers 的输出，每行一项
+/// 没有设置时输出 "There aren't any DNS Servers set on Wi-Fi."，服务名错误时输出错误说明
+pub fn parse_list(output: &str) -> Vec<String> {
+    if output.contains("There aren't any") || output.contains("** Error") {
+        return Vec::new();
+    }
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// 解析 scutil --dns 输出中实际使用的 DNS（去重，跳过以 ignore_prefixes 中任一前缀开头的地址）
+/// 格式: nameserver[0] : 8.8.8.8
+pub fn parse_scutil_dns(output: &str, ignore_prefixes: &[String]) -> Vec<String> {
+    let ignored = |dns: &str| {
+        ignore_prefixes
+            .iter()
+            .map(|prefix| prefix.trim())
+            .any(|prefix| !prefix.is_empty() && dns.starts_with(prefix))
+    };
+    let mut servers: Vec<String> = Vec::new();
+    for line in output.lines() {
+        let line = line.trim();
+        if !line.starts_with("nameserver") {
+            continue;
+        }
+        // 只按第一个冒号拆分，IPv6 地址本身含有冒号
+        if let Some((_, dns)) = line.split_once(':') {
+            let dns = dns.trim();
+            if !dns.is_empty() && !ignored(dns) && !servers.iter().any(|s| s == dns) {
+                servers.push(dns.to_string());
+            }
+        }
+    }
+    servers
+}
+
+/// 解析 networksetup -listallhardwareports 的输出，返回 (硬件端口, 设备名)
+/// 格式: Hardware Port: Wi-Fi / Device: en0 / Ethernet Address: ...
+pub fn parse_hardware_ports(output: &str) -> Vec<(String, String)> {
+    let mut ports = Vec::new();
+    let mut port: Option<&str> = None;
+    for line in output.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix("Hardware Port: ") {
+            port = Some(name);
+        } else if let Some(device) = line.strip_prefix("Device: ")
+            && let Some(name) = port.take()
+        {
+            ports.push((name.to_string(), device.trim().to_string()));
+        }
+    }
+    ports
+}
+
+/// 解析 networksetup -listnetworkserviceorder 的输出，返回 (服务名, 设备名)
+/// 格式: (1) Wi-Fi / (Hardware Port: Wi-Fi, Device: en0)，已停用的服务序号为 *
+pub fn parse_service_order(output: &str) -> Vec<(String, String)> {
+    let mut services = Vec::new();
+    let mut service: Option<&str> = None;
+    for line in output.lines().map(str::trim) {
+        if let Some(port) = line.strip_prefix("(Hardware Port: ") {
+            if let Some(name) = service.take()
+                && let Some((_, device)) = port.split_once("Device: ")
+            {
+                services.push((name.to_string(), device.trim_end_matches(')').trim().to_string()));
+            }
+        } else if line.starts_with('(')
+            && let Some((_, name)) = line.split_once(") ")
+        {
+            service = Some(name);
+        }
+    }
+    services
+}
+
+/// 解析 networksetup -listallnetworkservices 的输出，返回 (服务名, 是否启用)
+/// 第一行是说明，已停用的服务以 * 开头
+pub fn parse_network_services(output: &str) -> Vec<(String, bool)> {
+    output
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with("An asterisk"))
+        .map(|line| match line.strip_prefix('*') {
+            Some(name) => (name.to_string(), false),
+            None => (line.to_string(), true),
+        })
+        .collect()
+}
+
+/// 解析 arp -n 的输出中的 MAC 地址
+/// 格式: ? (192.168.1.1) at aa:bb:cc:dd:ee:ff on en0 ifscope [ethernet]
+pub fn parse_arp_mac(output: &str) -> Option<String> {
+    let (_, rest) = output.split_once(" at ")?;
+    let (mac, _) = rest.split_once(" on ")?;
+    let mac = mac.trim();
+    if mac.is_empty() || mac == "(incomplete)" {
+        None
+    } else {
+        Some(mac.to_lowercase())
+    }
+}
+
+/// 解析 networksetup -getmacaddress 的输出中网卡自身的 MAC 地址
+/// 格式: Ethernet Address: 00:e0:4c:68:01:02 (Device: en7)，没有硬件地址的服务为 (null) 或 N/A
+pub fn parse_mac_address(output: &str) -> Option<String> {
+    output
+        .lines()
+        .find_map(|line| line.strip_prefix("Ethernet Address:"))
+        .and_then(|rest| rest.split_whitespace().next())
+        .filter(|mac| is_mac_address(mac))
+        .map(str::to_lowercase)
+}
+
+/// 解析 networksetup -getwebproxy / -getsecurewebproxy 的输出，代理启用时返回 "主机:端口"
+/// 格式: Enabled: Yes / Server: proxy.example.com / Port: 8080 / Authenticated Proxy Enabled: 0
+pub fn parse_web_proxy(output: &str) -> Option<String> {
+    let (mut enabled, mut server, mut port) = (false, None, None);
+    for line in output.lines() {
+        match line.split_once(':') {
+            Some(("Enabled", value)) => enabled = value.trim() == "Yes",
+            Some(("Server", value)) => server = parse_optional_value(value),
+            Some(("Port", value)) => port = value.trim().parse::<u16>().ok().filter(|port| *port > 0),
+            _ => {}
+        }
+    }
+    enabled.then(|| Some(format!("{}:{}", server?, port?))).flatten()
+}
+
+/// 解析 networksetup -getautoproxyurl 的输出，自动代理启用时返回 PAC 地址
+/// 格式: URL: http://example.com/proxy.pac / Enabled: Yes，没有设置时 URL 为 (null)
+pub fn parse_auto_proxy_url(output: &str) -> Option<String> {
+    let mut url = None;
+    let mut enabled = false;
+    for line in output.lines() {
+        match line.split_once(':') {
+            Some(("URL", value)) => url = parse_optional_value(value).filter(|value| value != "(null)"),
+            Some(("Enabled", value)) => enabled = value.trim() == "Yes",
+            _ => {}
+        }
+    }
+    url.filter(|_| enabled)
+}
+
+/// 解析 ioreg 输出中的 SSID 行
+/// 格式: "IO80211SSID" = "NetworkName"（引号以 \" 转义）
+/// 或:   "IO80211SSID" = <4e6574776f726b>（原始字节的十六进制）
+pub fn parse_ioreg_ssid(line: &str) -> Option<String> {
+    if !line.contains("IO80211SSID") {
+        return None;
+    }
+    let (_, value) = line.split_once("= ")?;
+    let value = value.trim();
+
+    let ssid = if let Some(hex) = value.strip_prefix('<') {
+        let hex = hex.split('>').next()?;
+        let bytes = (0..hex.len())
+            .step_by(2)
+            .map(|i| hex.get(i..i + 2).and_then(|b| u8::from_str_radix(b, 16).ok()))
+            .collect::<Option<Vec<u8>>>()?;
+        String::from_utf8(bytes).ok()?
+    } else {
+        let mut chars = value.strip_prefix('"')?.chars();
+        let mut ssid = String::new();
+        loop {
+            match chars.next()? {
+                '\\' => ssid.push(chars.next()?),
+                '"' => break,
+                c => ssid.push(c),
+            }
+        }
+        ssid
+    };
+
+    if ssid.is_empty() { None } else { Some(ssid) }
+}
+
+/// 解析 ioreg 输出中的 BSSID 行
+/// 格式: "IO80211BSSID" = <a0b1c2d3e4f5>
+pub fn parse_ioreg_bssid(line: &str) -> Option<String> {
+    let (_, value) = line.trim().split_once("= <")?;
+    let hex = value.split('>').next()?;
+    if hex.len() != 12 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let octets: Vec<&str> = (0..12).step_by(2).map(|i| &hex[i..i + 2]).collect();
+    Some(octets.join(":").to_lowercase())
+}
+
+/// 解析 networksetup -getairportnetwork 的输出
+/// 格式: Current Wi-Fi Network: NetworkName
+/// 未连接时: You are not associated with an AirPort network.
+pub fn parse_airport_network(output: &str) -> Option<String> {
+    if output.contains("not associated") {
+        return None;
+    }
+    let ssid = output.strip_prefix("Current Wi-Fi Network: ")?.trim();
+    if ssid.is_empty() { None } else { Some(ssid.to_string()) }
+}
+
+/// "当前网络信息"段落的标题，包括常见的本地化版本
+const CURRENT_NETWORK_LABELS: [&str; 7] = [
+    "Current Network Information",
+    "当前网络信息",
+    "目前的網路資訊",
+    "現在のネットワーク情報",
+    "Aktuelle Netzwerkinformationen",
+    "Informations sur le réseau actuel",
+    "Información de la red actual",
+];
+
+/// "其他本地 Wi-Fi 网络"段落的标题，包括常见的本地化版本
+const OTHER_NETWORKS_LABELS: [&str; 4] = [
+    "Other Local Wi-Fi Networks",
+    "其他本地 Wi-Fi 网络",
+    "其他本機 Wi-Fi 網路",
+    "その他のローカルWi-Fiネットワーク",
+];
+
+/// 从 system_profiler SPAirPortDataType 的输出中提取 SSID
+/// 按缩进找到"当前网络信息"段落，它的第一个子项（"SSID_NAME:"）就是 SSID
+pub fn parse_system_profiler_ssid(output: &str) -> Option<String> {
+    section_entries(output, &CURRENT_NETWORK_LABELS).into_iter().next().and_then(entry_ssid)
+}
+
+/// 从 system_profiler SPAirPortDataType 的输出中提取当前网络和附近的其他网络
+/// 这里没有 BSSID
+pub fn parse_system_profiler_networks(output: &str) -> Vec<ScanResult> {
+    let mut results: Vec<ScanResult> = Vec::new();
+    let current = parse_system_profiler_ssid(output);
+    let others = section_entries(output, &OTHER_NETWORKS_LABELS).into_iter().filter_map(entry_ssid);
+    for ssid in current.into_iter().chain(others) {
+        if !results.iter().any(|result| result.ssid == ssid) {
+            results.push(ScanResult { ssid, bssid: None });
+        }
+    }
+    results
+}
+
+/// 按缩进找到标题为 labels 之一的段落，返回它的直接子项（去掉缩进）
+/// 标题的下一行缩进更深才是段落的子项，否则段落为空（如未连接）
+fn section_entries<'a>(output: &'a str, labels: &[&str]) -> Vec<&'a str> {
+    let mut lines = output.lines().filter(|line| !line.trim().is_empty());
+    let Some(header_indent) = lines.by_ref().find(|line| is_section_label(line, labels)).map(indent_of) else {
+        return Vec::new();
+    };
+    let mut lines = lines.take_while(|line| indent_of(line) > header_indent).peekable();
+    let Some(child_indent) = lines.peek().map(|line| indent_of(line)) else {
+        return Vec::new();
+    };
+    lines.filter(|line| indent_of(line) == child_indent).map(str::trim).collect()
+}
+
+/// 段落子项中的 SSID："SSID_NAME:" 形式，子项是 "键: 值" 说明段落里没有 SSID 这一层
+fn entry_ssid(entry: &str) -> Option<String> {
+    let ssid = entry.strip_suffix(':').or_else(|| entry.strip_suffix('：'))?;
+    // 新版 macOS 没有定位权限时会隐藏 SSID
+    if ssid.is_empty() || ssid == "<redacted>" {
+        return None;
+    }
+    Some(ssid.to_string())
+}
+
+/// 是否是指定段落的标题行
+fn is_section_label(line: &str, labels: &[&str]) -> bool {
+    let label = line.trim().trim_end_matches([':', '：']).trim();
+    labels.iter().any(|known| known.eq_ignore_ascii_case(label))
+}
+
+/// 解析 airport -s 的扫描结果
+/// 格式:        SSID BSSID             RSSI CHANNEL HT CC SECURITY
+///         Home WiFi a0:b1:c2:d3:e4:f5 -52  149,+1  Y  US WPA2(PSK/AES/AES)
+/// SSID 右对齐且可能含空格，以 BSSID 列为界拆分
+pub fn parse_airport_scan(output: &str) -> Vec<ScanResult> {
+    let mut results: Vec<ScanResult> = Vec::new();
+    for line in output.lines() {
+        let Some(bssid) = line.split_whitespace().find(|token| is_mac_address(token)) else {
+            continue;
+        };
+        let Some(position) = line.find(&format!(" {} ", bssid)) else {
+            continue;
+        };
+        let ssid = line[..position].trim();
+        if !ssid.is_empty() {
+            results.push(ScanResult { ssid: ssid.to_string(), bssid: Some(bssid.to_lowercase()) });
+        }
+    }
+    results
+}
+
+/// 是否是 aa:bb:cc:dd:ee:ff 形式的 MAC 地址
+fn is_mac_address(token: &str) -> bool {
+    let parts: Vec<&str> = token.split(':').collect();
+    parts.len() == 6 && parts.iter().all(|part| part.len() == 2 && part.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// 行首缩进的长度
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// 解析 netstat -rn 输出中的默认路由
+/// 格式: default            192.168.1.1        UGScg                 en0
+/// VPN 的默认路由网关为 link#N 等非 IP 地址，或者接口为 utun 等隧道接口
+pub fn parse_default_gateway(netstat: &str) -> Option<String> {
+    parse_default_route(netstat).map(|(gateway, _)| gateway)
+}
+
+/// 解析 netstat -rn 输出中的默认路由，返回 (网关, 接口)，跳过的路由同 parse_default_gateway
+pub fn parse_default_route(netstat: &str) -> Option<(String, String)> {
+    netstat.lines().find_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields.as_slice() {
+            ["default", gateway, _flags, interface, ..]
+                if !is_tunnel_interface(interface) && gateway.parse::<std::net::Ipv4Addr>().is_ok() =>
+            {
+                Some((gateway.to_string(), interface.to_string()))
+            }
+            _ => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn getinfo_dhcp_with_ipv6() {
+        let output = "\
+DHCP Configuration
+IP address: 192.168.1.23
+Subnet mask: 255.255.255.0
+Router: 192.168.1.1
+Client ID:
+IPv6: Automatic
+IPv6 IP address: 2001:db8::1c2d
+IPv6 Prefix Length: 64
+IPv6 Router: fe80::1
+Wi-Fi ID: a0:b1:c2:d3:e4:f5
+";
+        let info = parse_getinfo(output);
+        assert!(info.dhcp);
+        assert_eq!(info.ip_address.as_deref(), Some("192.168.1.23"));
+        assert_eq!(info.subnet_mask.as_deref(), Some("255.255.255.0"));
+        assert_eq!(info.router.as_deref(), Some("192.168.1.1"));
+        // 自动配置的 IPv6 地址不读取
+        assert_eq!(info.ipv6_method.as_deref(), Some("Automatic"));
+        assert_eq!(info.ipv6_address, None);
+        assert_eq!(info.ipv6_prefix, None);
+        assert_eq!(info.ipv6_router, None);
+    }
+
+    #[test]
+    fn getinfo_manual_ipv6() {
+        let output = "\
+DHCP Configuration
+IP address: 192.168.1.23
+IPv6: Manual
+IPv6 IP address: fd00::20
+IPv6 Prefix Length: 64
+IPv6 Router: fd00::1
+";
+        let info = parse_getinfo(output);
+        assert_eq!(info.ipv6_method.as_deref(), Some("Manual"));
+        assert_eq!(info.ipv6_address.as_deref(), Some("fd00::20"));
+        assert_eq!(info.ipv6_prefix, Some(64));
+        assert_eq!(info.ipv6_router.as_deref(), Some("fd00::1"));
+    }
+
+    #[test]
+    fn getinfo_manual_without_ipv6() {
+        let output = "\
+Manual Configuration
+IP address: 10.0.0.20
+Subnet mask: 255.255.255.0
+Router: 10.0.0.1
+IPv6: Automatic
+IPv6 IP address: none
+IPv6 Router: none
+Ethernet Address: 00:11:22:33:44:55
+";
+        let info = parse_getinfo(output);
+        assert!(!info.dhcp);
+        assert_eq!(info.ip_address.as_deref(), Some("10.0.0.20"));
+        assert_eq!(info.router.as_deref(), Some("10.0.0.1"));
+        assert_eq!(info.ipv6_address, None);
+        assert_eq!(info.ipv6_prefix, None);
+        assert_eq!(info.ipv6_router, None);
+    }
+
+    #[test]
+    fn getinfo_disconnected() {
+        let info = parse_getinfo("DHCP Configuration\nClient ID: \nIPv6: Automatic\nIPv6 IP address: none\nIPv6 Router: none\n");
+        assert!(info.dhcp);
+        assert_eq!(info.ip_address, None);
+    }
+
+    #[test]
+    fn dns_servers_list() {
+        assert_eq!(parse_list("1.1.1.1\n2606:4700:4700::1111\n"), vec!["1.1.1.1", "2606:4700:4700::1111"]);
+        assert!(parse_list("There aren't any DNS Servers set on Wi-Fi.\n").is_empty());
+        assert!(parse_list("Wi-Fi is not a recognized network service.\n** Error: The parameters were not valid.\n").is_empty());
+        assert!(parse_list("").is_empty());
+    }
+
+    #[test]
+    fn scutil_dns_dedup_and_ipv6() {
+        let output = "\
+DNS configuration
+
+resolver #1
+  search domain[0] : corp.example.com
+  nameserver[0] : 192.168.1.1
+  nameserver[1] : fe80::1%en0
+  if_index : 11 (en0)
+
+resolver #2
+  nameserver[0] : 198.18.0.2
+  nameserver[1] : 192.168.1.1
+";
+        let ignore = vec!["198.18.".to_string()];
+        assert_eq!(parse_scutil_dns(output, &ignore), vec!["192.168.1.1", "fe80::1%en0"]);
+        assert_eq!(parse_scutil_dns(output, &[]), vec!["192.168.1.1", "fe80::1%en0", "198.18.0.2"]);
+        assert_eq!(parse_scutil_dns(output, &["fe80:".to_string(), " ".to_string()]), vec!["192.168.1.1", "198.18.0.2"]);
+    }
+
+    const HARDWARE_PORTS: &str = "
+
+Hardware Port: Ethernet
+Device: en0
+Ethernet Address: 00:11:22:33:44:55
+
+Hardware Port: Wi-Fi
+Device: en1
+Ethernet Address: a0:b1:c2:d3:e4:f5
+
+Hardware Port: Thunderbolt Bridge
+Device: bridge0
+Ethernet Address: N/A
+
+VLAN Configurations
+===================
+";
+
+    #[test]
+    fn hardware_ports() {
+        let ports = parse_hardware_ports(HARDWARE_PORTS);
+        assert_eq!(
+            ports,
+            vec![
+                ("Ethernet".to_string(), "en0".to_string()),
+                ("Wi-Fi".to_string(), "en1".to_string()),
+                ("Thunderbolt Bridge".to_string(), "bridge0".to_string()),
+            ]
+        );
+        assert!(parse_hardware_ports("").is_empty());
+    }
+
+    #[test]
+    fn service_order_with_renamed_wifi() {
+        let output = "\
+An asterisk (*) denotes that a network service is disabled.
+(1) Office LAN
+(Hardware Port: USB 10/100/1000 LAN, Device: en7)
+
+(2) 无线局域网
+(Hardware Port: Wi-Fi, Device: en0)
+
+(*) Thunderbolt Bridge
+(Hardware Port: Thunderbolt Bridge, Device: bridge0)
+";
+        assert_eq!(
+            parse_service_order(output),
+            vec![
+                ("Office LAN".to_string(), "en7".to_string()),
+                ("无线局域网".to_string(), "en0".to_string()),
+                ("Thunderbolt Bridge".to_string(), "bridge0".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn network_services_with_disabled() {
+        let output = "\
+An asterisk (*) denotes that a network service is disabled.
+Wi-Fi
+USB 10/100/1000 LAN
+*Thunderbolt Bridge
+iPhone USB
+";
+        assert_eq!(
+            parse_network_services(output),
+            vec![
+                ("Wi-Fi".to_string(), true),
+                ("USB 10/100/1000 LAN".to_string(), true),
+                ("Thunderbolt Bridge".to_string(), false),
+                ("iPhone USB".to_string(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn arp_entries() {
+        let found = "? (192.168.1.1) at A0:B1:C2:D3:E4:F5 on en0 ifscope [ethernet]\n";
+        assert_eq!(parse_arp_mac(found).as_deref(), Some("a0:b1:c2:d3:e4:f5"));
+        let incomplete = "? (192.168.1.99) at (incomplete) on en0 ifscope [ethernet]\n";
+        assert_eq!(parse_arp_mac(incomplete), None);
+        assert_eq!(parse_arp_mac("192.168.1.50 (192.168.1.50) -- no entry\n"), None);
+    }
+
+    #[test]
+    fn interface_mac_address() {
+        let dock = "Ethernet Address: 00:E0:4C:68:01:02 (Device: en7)\n";
+        assert_eq!(parse_mac_address(dock).as_deref(), Some("00:e0:4c:68:01:02"));
+        assert_eq!(parse_mac_address("Ethernet Address: (null) (Device: utun0)\n"), None);
+        assert_eq!(parse_mac_address("Ethernet Address: N/A\n"), None);
+        assert_eq!(parse_mac_address("** Error: The parameters were not valid.\n"), None);
+    }
+
+    #[test]
+    fn web_proxy() {
+        let enabled = "Enabled: Yes\nServer: proxy.corp.example\nPort: 8080\nAuthenticated Proxy Enabled: 0\n";
+        assert_eq!(parse_web_proxy(enabled).as_deref(), Some("proxy.corp.example:8080"));
+        let disabled = "Enabled: No\nServer: proxy.corp.example\nPort: 8080\nAuthenticated Proxy Enabled: 0\n";
+        assert_eq!(parse_web_proxy(disabled), None);
+        assert_eq!(parse_web_proxy("Enabled: Yes\nServer: \nPort: 0\n"), None);
+    }
+
+    #[test]
+    fn auto_proxy_url() {
+        let enabled = "URL: http://wpad.corp.example/proxy.pac\nEnabled: Yes\n";
+        assert_eq!(parse_auto_proxy_url(enabled).as_deref(), Some("http://wpad.corp.example/proxy.pac"));
+        assert_eq!(parse_auto_proxy_url("URL: http://wpad.corp.example/proxy.pac\nEnabled: No\n"), None);
+        assert_eq!(parse_auto_proxy_url("URL: (null)\nEnabled: No\n"), None);
+    }
+
+    #[test]
+    fn ioreg_plain_ssid() {
+        let line = r#"    |   "IO80211SSID" = "Home WiFi""#;
+        assert_eq!(parse_ioreg_ssid(line).as_deref(), Some("Home WiFi"));
+    }
+
+    #[test]
+    fn ioreg_escaped_quote_ssid() {
+        let line = r#"    |   "IO80211SSID" = "Joe\"s WiFi""#;
+        assert_eq!(parse_ioreg_ssid(line).as_deref(), Some("Joe\"s WiFi"));
+    }
+
+    #[test]
+    fn ioreg_hex_ssid() {
+        // Joe"s WiFi
+        let line = r#"    |   "IO80211SSID" = <4a6f6522732057694669>"#;
+        assert_eq!(parse_ioreg_ssid(line).as_deref(), Some("Joe\"s WiFi"));
+    }
+
+    #[test]
+    fn ioreg_empty_or_missing() {
+        assert_eq!(parse_ioreg_ssid(r#""IO80211SSID" = """#), None);
+        assert_eq!(parse_ioreg_ssid(r#""IO80211SSID" = "unterminated"#), None);
+        assert_eq!(parse_ioreg_ssid(""), None);
+    }
+
+    #[test]
+    fn ioreg_bssid() {
+        let line = r#"    |   "IO80211BSSID" = <a0b1c2d3e4f5>"#;
+        assert_eq!(parse_ioreg_bssid(line).as_deref(), Some("a0:b1:c2:d3:e4:f5"));
+        assert_eq!(parse_ioreg_bssid(r#""IO80211BSSID" = <a0b1>"#), None);
+        assert_eq!(parse_ioreg_bssid(""), None);
+    }
+
+    #[test]
+    fn airport_network() {
+        assert_eq!(parse_airport_network("Current Wi-Fi Network: Home WiFi\n").as_deref(), Some("Home WiFi"));
+        assert_eq!(parse_airport_network("You are not associated with an AirPort network.\n"), None);
+        assert_eq!(parse_airport_network("Current Wi-Fi Network: \n"), None);
+    }
+
+    /// macOS 12 Monterey 的 system_profiler 输出（节选）
+    const PROFILER_MONTEREY: &str = "\
+Wi-Fi:
+
+      Software Versions:
+          CoreWLAN: 16.0 (1657)
+          IO80211_driverkit: V1.0.0
+      Interfaces:
+        en0:
+          Card Type: Wi-Fi  (0x14E4, 0x7BF)
+          Firmware Version: Broadcom BCM43xx 1.0 (7.77.111.1 AirPortDriverBrcmNIC-1710.3)
+          MAC Address: a0:b1:c2:d3:e4:f5
+          Status: Connected
+          Current Network Information:
+            Home WiFi: 5G:
+              PHY Mode: 802.11ac
+              Channel: 149 (5GHz, 80MHz)
+              Security: WPA2 Personal
+          Other Local Wi-Fi Networks:
+            Neighbor:
+              PHY Mode: 802.11n
+";
+
+    /// macOS 14 Sonoma 的 system_profiler 输出（节选），各级缩进与旧版本不同
+    const PROFILER_SONOMA: &str = "\
+Wi-Fi:
+
+    Software Versions:
+        CoreWLAN: 16.0 (1657)
+    Interfaces:
+      en0:
+        Card Type: Wi-Fi  (0x14E4, 0x4378)
+        Status: Connected
+        Current Network Information:
+          Office-Guest:
+            PHY Mode: 802.11ax
+            Channel: 36 (5GHz, 80MHz)
+            Network Type: Infrastructure
+        Other Local Wi-Fi Networks:
+          Cafe:
+            PHY Mode: 802.11ac
+";
+
+    /// 简体中文系统的输出（节选）
+    const PROFILER_CHINESE: &str = "\
+Wi-Fi：
+
+      接口：
+        en0：
+          状态：已连接
+          当前网络信息：
+            家里的网络：
+              PHY 模式：802.11ac
+          其他本地 Wi-Fi 网络：
+            邻居：
+              PHY 模式：802.11n
+";
+
+    #[test]
+    fn system_profiler_monterey() {
+        assert_eq!(parse_system_profiler_ssid(PROFILER_MONTEREY).as_deref(), Some("Home WiFi: 5G"));
+    }
+
+    #[test]
+    fn system_profiler_sonoma() {
+        assert_eq!(parse_system_profiler_ssid(PROFILER_SONOMA).as_deref(), Some("Office-Guest"));
+    }
+
+    #[test]
+    fn system_profiler_localized() {
+        assert_eq!(parse_system_profiler_ssid(PROFILER_CHINESE).as_deref(), Some("家里的网络"));
+    }
+
+    #[test]
+    fn system_profiler_not_connected_or_redacted() {
+        // 未连接时没有当前网络段落的子项，不能误取其他网络
+        let disconnected = "\
+        Status: Off
+        Current Network Information:
+        Other Local Wi-Fi Networks:
+          Neighbor:
+            PHY Mode: 802.11n
+";
+        assert_eq!(parse_system_profiler_ssid(disconnected), None);
+
+        let redacted = "\
+        Current Network Information:
+          <redacted>:
+            PHY Mode: 802.11ax
+";
+        assert_eq!(parse_system_profiler_ssid(redacted), None);
+        assert_eq!(parse_system_profiler_ssid(""), None);
+    }
+
+    #[test]
+    fn system_profiler_nearby_networks() {
+        let networks: Vec<String> = parse_system_profiler_networks(PROFILER_SONOMA).into_iter().map(|r| r.ssid).collect();
+        assert_eq!(networks, vec!["Office-Guest", "Cafe"]);
+        let networks: Vec<String> = parse_system_profiler_networks(PROFILER_CHINESE).into_iter().map(|r| r.ssid).collect();
+        assert_eq!(networks, vec!["家里的网络", "邻居"]);
+        assert!(parse_system_profiler_networks("").is_empty());
+    }
+
+    #[test]
+    fn airport_scan() {
+        let output = "\
+                            SSID BSSID             RSSI CHANNEL HT CC SECURITY (auth/unicast/group)
+                       Home WiFi A0:B1:C2:D3:E4:F5 -52  149,+1  Y  US WPA2(PSK/AES/AES)
+                         Cafe 5G 00:11:22:33:44:55 -70  36      Y  -- WPA2(PSK/AES/AES)
+";
+        assert_eq!(
+            parse_airport_scan(output),
+            vec![
+                ScanResult { ssid: "Home WiFi".to_string(), bssid: Some("a0:b1:c2:d3:e4:f5".to_string()) },
+                ScanResult { ssid: "Cafe 5G".to_string(), bssid: Some("00:11:22:33:44:55".to_string()) },
+            ]
+        );
+        assert!(parse_airport_scan("No networks found\n").is_empty());
+    }
+
+    #[test]
+    fn default_gateway_skips_vpn_routes() {
+        let netstat = "\
+Routing tables
+
+Internet:
+Destination        Gateway            Flags               Netif Expire
+default            link#22            UCSIg               utun4
+default            10.8.0.1           UGScIg              utun3
+default            192.168.1.1        UGScg                 en0
+127                127.0.0.1          UCS                   lo0
+192.168.1.1/32     link#11            UCS                   en0      !
+";
+        assert_eq!(parse_default_gateway(netstat).as_deref(), Some("192.168.1.1"));
+        assert_eq!(parse_default_route(netstat), Some(("192.168.1.1".to_string(), "en0".to_string())));
+    }
+
+    #[test]
+    fn default_gateway_missing() {
+        let netstat = "\
+Internet:
+Destination        Gateway            Flags               Netif Expire
+default            link#22            UCSIg               utun4
+127                127.0.0.1          UCS                   lo0
+";
+        assert_eq!(parse_default_gateway(netstat), None);
+    }
+}
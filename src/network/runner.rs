@@ -0,0 +1,119 @@
+//! 外部命令的执行：默认执行真实命令，测试中可替换为返回固定输出的实现
+
+use std::cell::RefCell;
+use std::io;
+use std::process::Command;
+use std::rc::Rc;
+
+/// 命令执行结果
+#[derive(Debug, Clone, Default)]
+pub struct CommandOutput {
+    /// 退出码，被信号终止时为空
+    pub code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl CommandOutput {
+    pub fn success(&self) -> bool {
+        self.code == Some(0)
+    }
+}
+
+/// 执行外部命令
+pub trait CommandRunner {
+    fn run(&self, cmd: &str, args: &[&str]) -> io::Result<CommandOutput>;
+}
+
+/// 执行真实的系统命令
+pub struct SystemRunner;
+
+impl CommandRunner for SystemRunner {
+    fn run(&self, cmd: &str, args: &[&str]) -> io::Result<CommandOutput> {
+        let output = Command::new(cmd).args(args).output()?;
+        Ok(CommandOutput {
+            code: output.status.code(),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        })
+    }
+}
+
+thread_local! {
+    /// 当前线程替换的执行器，按线程隔离，并行的测试互不影响
+    static RUNNER: RefCell<Option<Rc<dyn CommandRunner>>> = const { RefCell::new(None) };
+}
+
+/// 用当前线程的执行器执行命令，没有替换时执行真实命令
+pub fn run(cmd: &str, args: &[&str]) -> io::Result<CommandOutput> {
+    match RUNNER.with(|runner| runner.borrow().clone()) {
+        Some(runner) => runner.run(cmd, args),
+        None => SystemRunner.run(cmd, args),
+    }
+}
+
+/// 在 f 执行期间用 runner 执行当前线程的命令
+#[cfg(test)]
+pub fn with_runner<T>(runner: Rc<dyn CommandRunner>, f: impl FnOnce() -> T) -> T {
+    /// f panic 时也恢复为真实执行器
+    struct Reset;
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            RUNNER.with(|runner| runner.borrow_mut().take());
+        }
+    }
+
+    RUNNER.with(|slot| *slot.borrow_mut() = Some(runner));
+    let _reset = Reset;
+    f()
+}
+
+/// 记录执行过的命令，并按命令行前缀返回预设输出的执行器；没有预设的命令成功且无输出
+#[cfg(test)]
+#[derive(Default)]
+pub struct MockRunner {
+    calls: RefCell<Vec<Vec<String>>>,
+    responses: Vec<(String, CommandOutput)>,
+}
+
+#[cfg(test)]
+impl MockRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 命令行（命令与参数以空格连接）以 prefix 开头时成功并输出 stdout
+    pub fn respond(mut self, prefix: &str, stdout: &str) -> Self {
+        let output = CommandOutput { code: Some(0), stdout: stdout.to_string(), stderr: String::new() };
+        self.responses.push((prefix.to_string(), output));
+        self
+    }
+
+    /// 命令行以 prefix 开头时以 code 退出并输出 stderr
+    pub fn fail(mut self, prefix: &str, code: i32, stderr: &str) -> Self {
+        let output = CommandOutput { code: Some(code), stdout: String::new(), stderr: stderr.to_string() };
+        self.responses.push((prefix.to_string(), output));
+        self
+    }
+
+    /// 依次执行过的命令，每条为命令加参数
+    pub fn calls(&self) -> Vec<Vec<String>> {
+        self.calls.borrow().clone()
+    }
+}
+
+#[cfg(test)]
+impl CommandRunner for MockRunner {
+    fn run(&self, cmd: &str, args: &[&str]) -> io::Result<CommandOutput> {
+        let argv: Vec<String> = std::iter::once(cmd).chain(args.iter().copied()).map(str::to_string).collect();
+        let line = argv.join(" ");
+        self.calls.borrow_mut().push(argv);
+        let output = self
+            .responses
+            .iter()
+            .find(|(prefix, _)| line.starts_with(prefix.as_str()))
+            .map(|(_, output)| output.clone())
+            .unwrap_or(CommandOutput { code: Some(0), ..Default::default() });
+        Ok(output)
+    }
+}
@@ -0,0 +1,20 @@
+use std::process::Command;
+use std::thread;
+
+/// 发送 macOS 系统通知（通过 osascript，失败时静默忽略）
+pub fn send_notification(title: &str, message: &str) {
+    let script = format!(
+        "display notification \"{}\" with title \"{}\"",
+        escape_applescript(message),
+        escape_applescript(title)
+    );
+    // 在后台线程执行，避免阻塞界面
+    thread::spawn(move || {
+        let _ = Command::new("osascript").args(["-e", &script]).output();
+    });
+}
+
+/// 转义 AppleScript 字符串中的反斜杠和双引号
+fn escape_applescript(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -0,0 +1,102 @@
+//! 本地状态接口：在 Unix socket 上输出当前状态的 JSON，供菜单栏脚本等读取
+
+use std::fs;
+use std::io::Write;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use serde::Serialize;
+
+use crate::config::AppConfig;
+use crate::network::{self, NetworkIdentity};
+
+/// 由界面维护的应用状态
+#[derive(Debug, Clone, Default)]
+pub struct AppStatus {
+    pub last_applied: Option<String>,
+    pub auto_switch: bool,
+}
+
+/// 每次连接时写出的 JSON
+#[derive(Serialize)]
+struct StatusReport {
+    identity: NetworkIdentity,
+    last_applied: Option<String>,
+    auto_switch: bool,
+}
+
+/// 状态 socket 服务，drop 时停止监听并删除 socket 文件
+pub struct StatusServer {
+    path: PathBuf,
+    status: Arc<Mutex<AppStatus>>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl StatusServer {
+    /// socket 文件路径（与 config.json 同目录）
+    pub fn socket_path() -> PathBuf {
+        AppConfig::config_path().with_file_name("status.sock")
+    }
+
+    /// 创建 socket 并在后台线程监听，残留的旧 socket 文件会被替换
+    pub fn start(status: AppStatus) -> Result<Self, String> {
+        let path = Self::socket_path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| e.to_string())?;
+        }
+        let listener = UnixListener::bind(&path).map_err(|e| e.to_string())?;
+
+        let status = Arc::new(Mutex::new(status));
+        let stopped = Arc::new(AtomicBool::new(false));
+        let thread_status = Arc::clone(&status);
+        let thread_stopped = Arc::clone(&stopped);
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if thread_stopped.load(Ordering::SeqCst) {
+                    break;
+                }
+                if let Ok(stream) = stream {
+                    let status = thread_status.lock().map(|s| s.clone()).unwrap_or_default();
+                    write_report(stream, status);
+                }
+            }
+        });
+
+        Ok(Self { path, status, stopped })
+    }
+
+    /// 更新对外输出的应用状态
+    pub fn update(&self, f: impl FnOnce(&mut AppStatus)) {
+        if let Ok(mut status) = self.status.lock() {
+            f(&mut status);
+        }
+    }
+}
+
+impl Drop for StatusServer {
+    fn drop(&mut self) {
+        // 先连接一次唤醒阻塞在 accept 上的监听线程，让它退出
+        self.stopped.store(true, Ordering::SeqCst);
+        let _ = UnixStream::connect(&self.path);
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// 写出一次状态后关闭连接
+fn write_report(mut stream: UnixStream, status: AppStatus) {
+    let report = StatusReport {
+        identity: network::get_network_identity(false),
+        last_applied: status.last_applied,
+        auto_switch: status.auto_switch,
+    };
+    if let Ok(json) = serde_json::to_string(&report) {
+        let _ = writeln!(stream, "{}", json);
+    }
+}
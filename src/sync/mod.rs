@@ -0,0 +1,156 @@
+//! 局域网同步：在 HTTP 端口上提供已保存的配置，另一台机器可以拉取并合并
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use crate::config::{AppConfig, NetworkConfig};
+use crate::i18n::{t, tf};
+
+/// 单个连接的读写超时，避免慢连接卡住监听线程
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 同步服务，drop 时停止监听
+pub struct SyncServer {
+    port: u16,
+    token: Arc<Mutex<String>>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl SyncServer {
+    /// 在所有网卡（包括回环和局域网）上监听，只响应带正确令牌的 GET /configs
+    pub fn start(port: u16, token: String) -> Result<Self, String> {
+        let listener = TcpListener::bind((Ipv4Addr::UNSPECIFIED, port)).map_err(|e| e.to_string())?;
+        let token = Arc::new(Mutex::new(token));
+        let stopped = Arc::new(AtomicBool::new(false));
+        let thread_token = Arc::clone(&token);
+        let thread_stopped = Arc::clone(&stopped);
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if thread_stopped.load(Ordering::SeqCst) {
+                    break;
+                }
+                if let Ok(stream) = stream {
+                    let token = thread_token.lock().map(|t| t.clone()).unwrap_or_default();
+                    handle_request(stream, &token);
+                }
+            }
+        });
+
+        Ok(Self { port, token, stopped })
+    }
+
+    /// 更换令牌，之后的请求需要使用新令牌
+    pub fn set_token(&self, token: String) {
+        if let Ok(mut current) = self.token.lock() {
+            *current = token;
+        }
+    }
+
+    /// 正在监听的端口
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+}
+
+impl Drop for SyncServer {
+    fn drop(&mut self) {
+        // 先连接一次唤醒阻塞在 accept 上的监听线程，让它退出
+        self.stopped.store(true, Ordering::SeqCst);
+        let _ = TcpStream::connect_timeout(&SocketAddr::from((Ipv4Addr::LOCALHOST, self.port)), IO_TIMEOUT);
+    }
+}
+
+/// 处理一个请求后关闭连接，每次都从磁盘读取已保存的配置
+fn handle_request(mut stream: TcpStream, token: &str) {
+    let _ = stream.set_read_timeout(Some(IO_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(IO_TIMEOUT));
+
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).is_err() {
+        return;
+    }
+    let mut authorized = false;
+    loop {
+        let mut header = String::new();
+        match reader.read_line(&mut header) {
+            Ok(0) | Err(_) => break,
+            Ok(_) if header.trim().is_empty() => break,
+            Ok(_) => {}
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.trim().eq_ignore_ascii_case("authorization")
+        {
+            authorized = !token.is_empty() && value.trim().strip_prefix("Bearer ") == Some(token);
+        }
+    }
+
+    let (status, body) = if request_line.split_whitespace().take(2).ne(["GET", "/configs"]) {
+        ("404 Not Found", String::new())
+    } else if !authorized {
+        ("401 Unauthorized", String::new())
+    } else {
+        match shared_configs() {
+            Ok(json) => ("200 OK", json),
+            Err(e) => ("500 Internal Server Error", e),
+        }
+    };
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+}
+
+/// 对外提供的配置 JSON，不包含 WiFi 密码
+fn shared_configs() -> Result<String, String> {
+    let path = AppConfig::config_path();
+    // 还没有保存过配置
+    if !path.exists() {
+        return Ok("{}".to_string());
+    }
+    let mut config = AppConfig::import_from_path(&path)?;
+    for cfg in config.configs.values_mut() {
+        cfg.wifi_password = None;
+        cfg.wifi_password_account = None;
+    }
+    serde_json::to_string(&config.configs).map_err(|e| e.to_string())
+}
+
+/// 从另一台机器拉取配置，peer 可以是 "ip:端口" 或 "http://ip:端口/configs"
+pub fn pull(peer: &str, token: &str) -> Result<HashMap<String, NetworkConfig>, String> {
+    let host = peer.trim().trim_start_matches("http://");
+    let host = host.split('/').next().unwrap_or_default();
+    let addr = host
+        .to_socket_addrs()
+        .map_err(|e| tf("sync_invalid_address", &[&host, &e]))?
+        .next()
+        .ok_or_else(|| tf("sync_unresolved", &[&host]))?;
+
+    let mut stream = TcpStream::connect_timeout(&addr, IO_TIMEOUT).map_err(|e| e.to_string())?;
+    let _ = stream.set_read_timeout(Some(IO_TIMEOUT));
+    write!(
+        stream,
+        "GET /configs HTTP/1.1\r\nHost: {}\r\nAuthorization: Bearer {}\r\nConnection: close\r\n\r\n",
+        host, token
+    )
+    .map_err(|e| e.to_string())?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).map_err(|e| e.to_string())?;
+    let (head, body) = response.split_once("\r\n\r\n").ok_or(t("sync_bad_response"))?;
+    let status = head.lines().next().unwrap_or_default();
+    match status.split_whitespace().nth(1) {
+        Some("200") => serde_json::from_str(body).map_err(|e| tf("sync_parse_failed", &[&e])),
+        Some("401") => Err(t("sync_bad_token").to_string()),
+        _ => Err(tf("sync_peer_status", &[&status])),
+    }
+}
@@ -0,0 +1,107 @@
+use std::sync::mpsc::{self, Receiver};
+
+use eframe::egui;
+use tray_icon::menu::{Menu, MenuEvent, MenuId, MenuItem, PredefinedMenuItem};
+use tray_icon::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent};
+
+use super::TrayAction;
+
+/// 菜单栏事件（由 tray-icon 的回调线程发送）
+enum TrayEvent {
+    Click,
+    Menu(MenuId),
+}
+
+/// macOS 菜单栏图标
+pub struct Tray {
+    icon: TrayIcon,
+    events: Receiver<TrayEvent>,
+    // 菜单项 ID 与配置键的对应关系
+    config_items: Vec<(MenuId, String)>,
+    // 当前菜单内容，内容不变时不重建菜单
+    menu_state: Option<(String, Vec<(String, String)>)>,
+}
+
+impl Tray {
+    pub fn new(ctx: &egui::Context) -> Option<Self> {
+        let icon = TrayIconBuilder::new()
+            .with_title("🌐")
+            .with_tooltip("Network Switcher")
+            .with_menu_on_left_click(false)
+            .build()
+            .ok()?;
+
+        let (tx, events) = mpsc::channel();
+
+        // 左键点击图标：显示主窗口
+        let click_tx = tx.clone();
+        let click_ctx = ctx.clone();
+        TrayIconEvent::set_event_handler(Some(move |event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                click_ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+                click_ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+                let _ = click_tx.send(TrayEvent::Click);
+                click_ctx.request_repaint();
+            }
+        }));
+
+        // 菜单点击：交给 GUI 线程处理
+        let menu_ctx = ctx.clone();
+        MenuEvent::set_event_handler(Some(move |event: MenuEvent| {
+            let _ = tx.send(TrayEvent::Menu(event.id));
+            menu_ctx.request_repaint();
+        }));
+
+        Some(Self {
+            icon,
+            events,
+            config_items: Vec::new(),
+            menu_state: None,
+        })
+    }
+
+    /// 更新菜单：当前网络作为不可点击的标题，下面列出所有配置
+    /// configs 为 (配置键, 配置名称) 列表
+    pub fn update_menu(&mut self, header: &str, configs: &[(String, String)]) {
+        if let Some((old_header, old_configs)) = &self.menu_state
+            && old_header == header
+            && old_configs == configs
+        {
+            return;
+        }
+
+        let menu = Menu::new();
+        let _ = menu.append(&MenuItem::new(header, false, None));
+        let _ = menu.append(&PredefinedMenuItem::separator());
+
+        self.config_items.clear();
+        for (key, name) in configs {
+            let item = MenuItem::new(name, true, None);
+            let _ = menu.append(&item);
+            self.config_items.push((item.id().clone(), key.clone()));
+        }
+
+        self.icon.set_menu(Some(Box::new(menu)));
+        self.menu_state = Some((header.to_string(), configs.to_vec()));
+    }
+
+    /// 取出待处理的菜单栏操作
+    pub fn poll(&self) -> Vec<TrayAction> {
+        self.events
+            .try_iter()
+            .filter_map(|event| match event {
+                TrayEvent::Click => Some(TrayAction::Show),
+                TrayEvent::Menu(id) => self
+                    .config_items
+                    .iter()
+                    .find(|(item_id, _)| *item_id == id)
+                    .map(|(_, key)| TrayAction::Apply(key.clone())),
+            })
+            .collect()
+    }
+}
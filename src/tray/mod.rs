@@ -0,0 +1,35 @@
+//! 菜单栏图标（仅 macOS），其他平台下不创建图标
+
+#[cfg(target_os = "macos")]
+mod macos;
+
+#[cfg(target_os = "macos")]
+pub use macos::Tray;
+
+/// 菜单栏图标触发的操作
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub enum TrayAction {
+    /// 点击图标，显示主窗口
+    Show,
+    /// 点击菜单中的配置，按配置键应用
+    Apply(String),
+}
+
+/// 非 macOS 平台没有菜单栏图标
+#[cfg(not(target_os = "macos"))]
+pub enum Tray {}
+
+#[cfg(not(target_os = "macos"))]
+impl Tray {
+    pub fn new(_ctx: &eframe::egui::Context) -> Option<Self> {
+        None
+    }
+
+    pub fn update_menu(&mut self, _header: &str, _configs: &[(String, String)]) {
+        match *self {}
+    }
+
+    pub fn poll(&self) -> Vec<TrayAction> {
+        match *self {}
+    }
+}